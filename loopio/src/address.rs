@@ -94,6 +94,12 @@ impl Address {
         self.filter.as_deref()
     }
 
+    /// Returns the tag,
+    ///
+    pub fn tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+
     /// Returns the filter as a form_urlencoded Parser,
     ///
     pub fn filter(&self) -> Option<url::form_urlencoded::Parse<'_>> {
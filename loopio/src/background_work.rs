@@ -253,12 +253,16 @@ impl BackgroundFuture {
 
     /// Spawn the hosted resource w/ frame updates,
     ///
+    /// **Note** The updates are applied to a copy of the hosted node, so they only apply to the call being spawned and
+    /// the hosted resource keeps its compiled state for later calls.
+    ///
     pub fn spawn_with_updates(&mut self, updates: FrameUpdates) -> CallStatus {
         if matches!(self.status(), CallStatus::Enabled) {
             if let Some(eh) = self.tc.cached::<EngineHandle>() {
                 debug!("Spawning from background future {}", self.address);
                 let address = self.address.to_string();
                 let handle = self.tc.node.runtime.clone().unwrap();
+                let runtime = handle.clone();
 
                 self.cancellation = self.tc.cancellation.child_token();
                 let cancel = self.cancellation.clone();
@@ -269,13 +273,16 @@ impl BackgroundFuture {
                             // Rebuild the environment for the current context
                             let resource = resource?;
                             let rk = resource.plugin_rk();
+
+                            // Updates are applied to a copy of the hosted node so that later calls are not affected
+                            let node = resource.context().node().await.clone();
+                            context.node = node.into_thread_safe_with(runtime);
+                            context.attribute = resource.context().attribute;
                             context
                                 .node()
                                 .await
                                 .lazy_put_resource::<FrameUpdates>(updates, rk.transmute());
                             context.process_node_updates().await;
-                            context.node = resource.context().node.clone();
-                            context.attribute = resource.context().attribute;
                             if let Some(plugin) = resource.context().attribute.plugin().and_then(|p| p.call()) {
                                Ok(plugin(context).await?.unwrap())
                             } else {
@@ -518,3 +525,91 @@ where
         })
     }
 }
+
+#[test]
+#[tracing_test::traced_test]
+fn test_spawn_with_updates() {
+    use crate::ext::std_ext::Println;
+    use crate::prelude::ActionExt;
+    use crate::prelude::Engine;
+    use crate::prelude::ForegroundEngine;
+
+    let mut workspace = Workspace::new();
+    workspace.add_buffer(
+        "demo.md",
+        r#"
+```runmd
++ .operation a
+<builtin.println>   Hello World updates
+: .label            default
+```
+"#,
+    );
+
+    let mut builder = Engine::builder();
+    builder.set_workspace(workspace);
+    let engine = ForegroundEngine::new(builder);
+
+    let plugin = engine.package.search("println?b=0&n=1").pop().unwrap();
+    let address = plugin.host.address().unwrap().to_string();
+    let label = plugin
+        .program
+        .context()
+        .ok()
+        .and_then(|tc| tc.attribute.repr())
+        .and_then(|r| r.as_recv())
+        .and_then(|r| r.fields())
+        .and_then(|f| {
+            f.iter()
+                .find(|f| f.as_field().and_then(|f| f.name()) == Some("label"))
+                .copied()
+        })
+        .unwrap();
+
+    let mut updates = FrameUpdates::default();
+    updates.frame.fields.push(
+        ResourceKey::<Property>::with_repr(label)
+            .field_packet()
+            .unwrap()
+            .parse("updated".to_string()),
+    );
+
+    let mut eh = engine.engine_handle();
+    let bg = eh.background().unwrap();
+
+    // Updates are applied to the call they are spawned w/
+    {
+        let mut bgf = bg.call(address.as_str()).unwrap();
+        assert!(matches!(
+            bgf.spawn_with_updates(updates),
+            CallStatus::Running
+        ));
+        let mut tc = bgf.into_foreground().unwrap();
+        let println = engine
+            .runtime()
+            .block_on(async { tc.as_remote_plugin::<Println>().await });
+        assert_eq!("updated", println.label);
+    }
+
+    // The hosted node is not modified by the updates of a previous call
+    engine.runtime().block_on(async {
+        let resource = engine
+            .engine_handle()
+            .hosted_resource(address.as_str())
+            .await
+            .unwrap();
+        let node = resource.context().node().await;
+        assert!(node
+            .resource::<FrameUpdates>(resource.plugin_rk().transmute())
+            .is_none());
+    });
+    {
+        let mut bgf = bg.call(address.as_str()).unwrap();
+        bgf.spawn();
+        let mut tc = bgf.into_foreground().unwrap();
+        let println = engine
+            .runtime()
+            .block_on(async { tc.as_remote_plugin::<Println>().await });
+        assert_eq!("default", println.label);
+    }
+}
@@ -3,14 +3,23 @@ use crate::action::HostAction;
 use crate::background_work::BackgroundWorkEngineHandle;
 use crate::host;
 use crate::host::Event;
+use crate::metrics::Metrics;
+use crate::metrics::MetricsSnapshot;
 use crate::operation::Operation;
+use crate::pipeline::Pipeline;
 use crate::prelude::Action;
 use crate::prelude::ActionExt;
 use crate::prelude::Address;
 use crate::prelude::EngineBuildMiddleware;
 use crate::prelude::Ext;
 use crate::prelude::VirtualBus;
+use crate::schedule::Schedule;
+use crate::schedule::ScheduleState;
+use crate::schedule::Scheduler;
 use crate::sequence::Sequence;
+use crate::timeline::span_result;
+use crate::timeline::ENGINE_CALL_SPAN;
+use crate::timeline::TIMELINE_TARGET;
 use anyhow::anyhow;
 use bytes::Bytes;
 use futures_util::StreamExt;
@@ -30,6 +39,7 @@ use tracing::error;
 use tracing::info;
 use tracing::trace;
 use tracing::warn;
+use tracing::Instrument;
 
 #[cfg(feature = "hyper-ext")]
 use crate::prelude::secure_client;
@@ -47,6 +57,9 @@ pub struct EngineBuilder {
     /// Workspace,
     ///
     pub(crate) workspace: Workspace,
+    /// Command line used to set the arguments of the workspace before it is compiled,
+    ///
+    pub(crate) command_args: Option<Vec<String>>,
 }
 
 impl EngineBuilder {
@@ -65,6 +78,7 @@ impl EngineBuilder {
             plugins: vec![],
             runtime_builder,
             workspace: EmptyWorkspace.workspace(),
+            command_args: None,
         }
     }
 
@@ -119,6 +133,7 @@ impl EngineBuilder {
                 ResourceLevel::new::<P>(),
                 None,
             );
+            block_obj.schema = <P as BlockObject>::schema;
 
             parser.add_object_type_with(P::symbol(), block_obj);
         });
@@ -155,13 +170,44 @@ impl EngineBuilder {
         &mut self.workspace
     }
 
-    pub async fn compile(self) -> anyhow::Result<Engine> {
-        let workspace = self.workspace.clone();
+    /// Sets the command line to match against the command generated from the workspace package,
+    ///
+    /// **Note** When the engine is compiled, matched arguments are set as workspace arguments that can be interpolated
+    /// w/ `${arg:name}` and `--profile` selects the profile.
+    ///
+    pub fn set_command_args(&mut self, args: impl IntoIterator<Item = impl Into<String>>) {
+        self.command_args = Some(args.into_iter().map(Into::into).collect());
+    }
+
+    pub async fn compile(mut self) -> anyhow::Result<Engine> {
+        let mut workspace = self.workspace.clone();
+        let command_args = self.command_args.take();
         let engine = self.build();
 
+        if let Some(args) = command_args {
+            workspace
+                .set_args_from_command(engine.project(), args)
+                .await?;
+        }
+
         let engine = engine.compile(workspace).await?;
         Ok(engine)
     }
+
+    /// Returns an attribute parser w/ each plugin registered w/ this builder,
+    ///
+    /// **Note** Can be used to look up the fields of a plugin that are not defined by a runmd block, i.e.
+    /// `AttributeParser::object_type_fields`.
+    ///
+    pub fn parser(&self) -> AttributeParser<Shared> {
+        let mut parser = AttributeParser::<Shared>::default();
+
+        for plugin in self.plugins.iter() {
+            plugin(&mut parser);
+        }
+
+        parser
+    }
 }
 
 impl reality::prelude::RegisterWith for EngineBuilder {
@@ -233,6 +279,12 @@ pub struct Engine {
     /// Map of virtual buses,
     ///
     __bus: BTreeMap<Address, VirtualBus>,
+    /// Schedulers to drive after startup,
+    ///
+    __schedulers: Vec<Scheduler>,
+    /// Status of schedules by address,
+    ///
+    __schedules: BTreeMap<Address, ScheduleState>,
 }
 
 impl Debug for Engine {
@@ -277,6 +329,34 @@ impl Engine {
         self.plugins.push(Arc::new(plugin));
     }
 
+    /// Returns the schema of every plugin registered w/ this engine,
+    ///
+    pub fn schemas(&self) -> Vec<PluginSchema> {
+        let mut parser = AttributeParser::<Shared>::default();
+
+        for plugin in self.plugins.iter() {
+            plugin(&mut parser);
+        }
+
+        parser.schemas()
+    }
+
+    /// Returns a JSON Schema document describing every plugin registered w/ this engine,
+    ///
+    /// **Note** Each plugin's schema is listed under `$defs` keyed by the plugin's symbol.
+    ///
+    pub fn json_schema(&self) -> serde_json::Value {
+        PluginSchema::document(self.schemas().iter())
+    }
+
+    /// Returns markdown reference pages of every plugin registered w/ this engine keyed by plugin group,
+    ///
+    /// **Note** Each plugin section includes a runmd example block that loads the plugin w/ an example value for each field.
+    ///
+    pub fn reference_docs(&self) -> BTreeMap<String, String> {
+        PluginSchema::reference_pages(self.schemas().iter())
+    }
+
     /// Creates a new engine,
     ///
     /// **Note** By default creates a new multi_thread runtime w/ all features enabled
@@ -306,6 +386,7 @@ impl Engine {
             handle: EngineHandle {
                 host: None,
                 sender: Arc::new(sender),
+                metrics: Metrics::default(),
                 background_work: None,
             },
             packet_rx: rx,
@@ -314,6 +395,8 @@ impl Engine {
             __internal_resources: BTreeMap::new(),
             __published: BTreeMap::new(),
             __bus: BTreeMap::new(),
+            __schedulers: vec![],
+            __schedules: BTreeMap::new(),
         }
     }
 
@@ -357,9 +440,9 @@ impl Engine {
         parser.push_link_recv::<T>();
     }
 
-    /// Compiles a workspace,
+    /// Returns a new project w/ the node plugins of this engine,
     ///
-    pub async fn compile(mut self, workspace: Workspace) -> anyhow::Result<Self> {
+    pub(crate) fn project(&self) -> Project<Shared> {
         let mut project = Project::new(Shared::default());
         project.add_block_plugin(None, None, |_| {});
 
@@ -388,12 +471,26 @@ impl Engine {
 
         project.add_node_plugin("sequence", Self::add_node_plugin::<Sequence>);
         project.add_node_plugin("host", Self::add_node_plugin::<Host>);
+        project.add_node_plugin("schedule", Self::add_node_plugin::<Schedule>);
+        project.add_node_plugin("pipeline", Self::add_node_plugin::<Pipeline>);
+        project
+    }
 
-        let project = workspace.compile(project).await?.project.take().unwrap();
+    /// Compiles a workspace,
+    ///
+    pub async fn compile(mut self, workspace: Workspace) -> anyhow::Result<Self> {
+        let project = workspace
+            .compile(self.project())
+            .await?
+            .project
+            .take()
+            .unwrap();
         let package = project.package().await?;
 
         let contents = package.search("*");
         let mut hosts = vec![];
+        let mut schedules = vec![];
+        let mut pipelines = vec![];
         for p in contents {
             if let Some(address) = p
                 .host
@@ -403,10 +500,14 @@ impl Engine {
                 info!("Publishing address -- {}", address);
                 let mut context = p.program.context()?;
                 context.cancellation = self.cancellation.child_token();
-                self.__published.insert(address, context.clone());
+                self.__published.insert(address.clone(), context.clone());
 
                 if context.attribute.is_resource::<Host>() {
                     hosts.push(context);
+                } else if context.attribute.is_resource::<Schedule>() {
+                    schedules.push((address, context));
+                } else if context.attribute.is_resource::<Pipeline>() {
+                    pipelines.push((address, context));
                 }
             }
         }
@@ -468,6 +569,26 @@ impl Engine {
             }
         }
 
+        for (address, mut context) in schedules {
+            let schedule = context.as_remote_plugin::<Schedule>().await;
+            let scheduler = schedule.scheduler()?.on_runtime(self.handle());
+
+            info!("Registering schedule - {} -> {}", address, scheduler.target);
+            self.__schedules.insert(address, scheduler.state());
+            self.__schedulers.push(scheduler);
+        }
+
+        // Pipelines are validated up front so that cycles are reported at compile time
+        for (address, mut context) in pipelines {
+            let pipeline = context.as_remote_plugin::<Pipeline>().await;
+            let order = pipeline
+                .graph()?
+                .topo_order()
+                .map_err(|err| anyhow!("Invalid pipeline {address}, {err}"))?;
+
+            debug!("Pipeline {} order - {:?}", address, order);
+        }
+
         self.package = Some(package);
 
         Ok(self)
@@ -494,6 +615,7 @@ impl Engine {
 
                 let mut root = node.root();
                 root.maybe_put(|| self.engine_handle());
+                root.maybe_put(|| self.handle.metrics.wire());
 
                 node.drain_dispatch_queues();
             }
@@ -509,6 +631,44 @@ impl Engine {
         }
     }
 
+    /// Re-hydrates a snapshot of plugin P onto the resource hosted at the snapshot's address,
+    ///
+    /// **Note** The returned context uses a copy of the hosted resource's node storage so that replaying does not
+    /// modify the state of the resource that is hosted by this engine. Typed transient and cached resources can be
+    /// restored on the returned context before it is called.
+    ///
+    pub async fn rehydrate<P>(&self, snapshot: &Snapshot) -> anyhow::Result<ThunkContext>
+    where
+        P: Plugin + serde::de::DeserializeOwned + Sync + Send + 'static,
+    {
+        let address = snapshot
+            .address
+            .as_ref()
+            .ok_or(anyhow!("Snapshot does not have an address to replay"))?;
+
+        let resource = self.get_resource(address).await?;
+
+        let mut tc = resource.context().clone();
+        let node = tc.node().await.clone();
+        tc.node = node.into_thread_safe_with(self.handle());
+        tc.reset();
+
+        snapshot.restore::<P>(&mut tc).await?;
+        Ok(tc)
+    }
+
+    /// Re-hydrates a snapshot of plugin P and calls the plugin,
+    ///
+    pub async fn replay<P>(&self, snapshot: &Snapshot) -> anyhow::Result<ThunkContext>
+    where
+        P: Plugin + serde::de::DeserializeOwned + Sync + Send + 'static,
+    {
+        let tc = self.rehydrate::<P>(snapshot).await?;
+
+        info!(address = snapshot.address, "Replaying snapshot");
+        Ok(tc.call().await?.unwrap_or(tc))
+    }
+
     /// Returns a tokio runtime handle,
     ///
     pub fn handle(&self) -> tokio::runtime::Handle {
@@ -524,6 +684,18 @@ impl Engine {
         self.handle.clone()
     }
 
+    /// Returns a snapshot of the metrics collected by this engine,
+    ///
+    pub fn metrics(&self) -> MetricsSnapshot {
+        self.handle.metrics()
+    }
+
+    /// Returns the addresses of resources published by this engine,
+    ///
+    pub fn published_addresses(&self) -> impl Iterator<Item = &Address> {
+        self.__published.keys()
+    }
+
     /// Takes ownership of the engine and starts listening for packets,
     ///
     pub fn spawn(
@@ -543,6 +715,9 @@ impl Engine {
         mut self,
     ) -> anyhow::Result<(EngineHandle, JoinHandle<anyhow::Result<Self>>)> {
         let remote_actions = self.__remote_actions.drain(..).collect::<Vec<_>>();
+        let schedulers = self.__schedulers.drain(..).collect::<Vec<_>>();
+        let cancellation = self.cancellation.clone();
+        let runtime = self.handle();
         let startup = self.spawn(|_, p| {
             trace!("{:?}", p);
             Some(p)
@@ -558,6 +733,15 @@ impl Engine {
             }
         }
 
+        // Drive all schedules
+        for scheduler in schedulers {
+            let eh = eh.clone();
+            runtime.spawn(scheduler.drive(cancellation.child_token(), move |target| {
+                let eh = eh.clone();
+                async move { eh.run(target.to_string()).await.map(|_| ()) }
+            }));
+        }
+
         Ok(startup)
     }
 
@@ -568,6 +752,8 @@ impl Engine {
         middleware: impl Fn(&mut Engine, EnginePacket) -> Option<EnginePacket>,
     ) -> anyhow::Result<Self> {
         while let Some(packet) = self.packet_rx.recv().await {
+            self.handle.metrics.packet_received();
+
             if self.cancellation.is_cancelled() {
                 break;
             }
@@ -575,12 +761,21 @@ impl Engine {
             if let Some(packet) = middleware(&mut self, packet) {
                 trace!("Handling packet {:?}", packet.action);
                 match packet.action {
-                    EngineAction::Call { address, mut tx } => {
+                    EngineAction::Call {
+                        address,
+                        mut tx,
+                        span,
+                    } => {
                         trace!(address, "Looking up hosted resource");
                         if let Some(tx) = tx.take() {
                             if let Ok(resource) = self.get_resource(address).await {
                                 trace!("Sending call output");
-                                if tx.send(resource.spawn()).is_err() {
+                                // Spawned thunk is instrumented w/ the caller's span
+                                let output = match span {
+                                    Some(span) => span.in_scope(|| resource.spawn()),
+                                    None => resource.spawn(),
+                                };
+                                if tx.send(output).is_err() {
                                     error!("Could not call resource");
                                 }
                             } else {
@@ -608,6 +803,15 @@ impl Engine {
 
                                 let published = Published {
                                     label: String::new(),
+                                    next_run: self
+                                        .__schedules
+                                        .iter()
+                                        .filter_map(|(a, s)| {
+                                            s.status()
+                                                .next_run
+                                                .map(|n| (a.to_string(), n.to_rfc3339()))
+                                        })
+                                        .collect(),
                                     resources: published
                                         .iter()
                                         .filter_map(|a| Decorated::from_str(a).ok())
@@ -722,6 +926,10 @@ pub struct Published {
     ///
     #[reality(vec_of=Decorated<Address>)]
     pub resources: Vec<Decorated<Address>>,
+    /// Map of schedule addresses to the next time they are due, (RFC 3339)
+    ///
+    #[reality(map_of=String)]
+    pub next_run: BTreeMap<String, String>,
 }
 
 async fn build_published(tc: &mut ThunkContext) -> anyhow::Result<()> {
@@ -784,6 +992,10 @@ enum EngineAction {
         ///
         #[serde(skip)]
         tx: Option<tokio::sync::oneshot::Sender<CallOutput>>,
+        /// Span of the caller,
+        ///
+        #[serde(skip)]
+        span: Option<tracing::Span>,
     },
     /// Retrieves a hosted resource,
     ///
@@ -827,7 +1039,7 @@ enum EngineAction {
 impl Debug for EngineAction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Call { address, tx } => f
+            Self::Call { address, tx, .. } => f
                 .debug_struct("Call")
                 .field("address", address)
                 .field("has_tx", &tx.is_some())
@@ -869,6 +1081,9 @@ pub struct EngineHandle {
     /// Sends engine packets to the engine,
     ///
     sender: Arc<tokio::sync::mpsc::UnboundedSender<EnginePacket>>,
+    /// Metrics registry shared w/ the engine,
+    ///
+    metrics: Metrics,
     /// Background work engine handle,
     ///
     pub(crate) background_work: Option<BackgroundWorkEngineHandle>,
@@ -879,6 +1094,7 @@ impl Clone for EngineHandle {
         Self {
             host: self.host,
             sender: self.sender.clone(),
+            metrics: self.metrics.clone(),
             background_work: self.background_work.clone(),
         }
     }
@@ -900,11 +1116,58 @@ impl EngineHandle {
         self
     }
 
+    /// Returns the name of the host this handle is attached to,
+    ///
+    pub fn host_name(&self) -> Option<Arc<String>> {
+        self.host.and_then(|h| h.host()).and_then(|h| h.address())
+    }
+
     /// Runs an operation by sending a packet and waits for a response,
     ///
     pub async fn run(&self, address: impl Into<String>) -> anyhow::Result<ThunkContext> {
         let address = address.into();
 
+        let span = tracing::info_span!(
+            target: TIMELINE_TARGET,
+            ENGINE_CALL_SPAN,
+            address,
+            host = tracing::field::Empty,
+            result = tracing::field::Empty
+        );
+        if let Some(host) = self.host_name() {
+            span.record("host", host.as_str());
+        }
+
+        let result = self
+            .send_call(address.clone())
+            .instrument(span.clone())
+            .await;
+        span.record("result", span_result(&result));
+        self.metrics.record_call(address, &result);
+        result
+    }
+
+    /// Returns a snapshot of the metrics collected by the engine,
+    ///
+    pub fn metrics(&self) -> MetricsSnapshot {
+        self.metrics.snapshot()
+    }
+
+    /// Sends a packet to the engine,
+    ///
+    fn send(&self, packet: EnginePacket) -> anyhow::Result<()> {
+        // Queued before sending so that the engine never observes the packet before it is counted
+        self.metrics.packet_queued();
+        if let Err(err) = self.sender.send(packet) {
+            self.metrics.packet_received();
+            return Err(err.into());
+        }
+        Ok(())
+    }
+
+    /// Sends a call packet and waits for the response,
+    ///
+    async fn send_call(&self, address: String) -> anyhow::Result<ThunkContext> {
         debug!("Looking for {}", &address);
         let (tx, rx) = tokio::sync::oneshot::channel::<CallOutput>();
 
@@ -912,10 +1175,11 @@ impl EngineHandle {
             action: EngineAction::Call {
                 address,
                 tx: Some(tx),
+                span: Some(tracing::Span::current()),
             },
         };
 
-        self.sender.send(packet)?;
+        self.send(packet)?;
 
         match rx.await? {
             CallOutput::Spawn(Some(jh)) => {
@@ -951,7 +1215,7 @@ impl EngineHandle {
             },
         };
 
-        self.sender.send(packet)?;
+        self.send(packet)?;
 
         match rx.await? {
             Some(resource) => Ok(resource),
@@ -972,7 +1236,7 @@ impl EngineHandle {
             },
         };
 
-        self.sender.send(packet)?;
+        self.send(packet)?;
 
         rx.await?
     }
@@ -984,7 +1248,7 @@ impl EngineHandle {
             action: EngineAction::Shutdown(delay),
         };
 
-        self.sender.send(packet)?;
+        self.send(packet)?;
         Ok(())
     }
 
@@ -997,7 +1261,7 @@ impl EngineHandle {
             action: EngineAction::Sync { tx: Some(tx) },
         };
 
-        self.sender.send(packet)?;
+        self.send(packet)?;
 
         Ok(rx.await?)
     }
@@ -1024,7 +1288,7 @@ impl EngineHandle {
             },
         };
 
-        self.sender.send(packet)?;
+        self.send(packet)?;
 
         Ok(rx.await?)
     }
@@ -1100,3 +1364,333 @@ impl EngineHandle {
         }
     }
 }
+
+/// Compiles a runmd fixture w/ an engine,
+///
+/// **Note** The fixture is added to an empty workspace as `demo.md`.
+///
+#[cfg(test)]
+async fn compile_fixture(engine: Engine, fixture: &str) -> anyhow::Result<Engine> {
+    let mut workspace = Workspace::new();
+    workspace.add_buffer("demo.md", fixture);
+    engine.compile(workspace).await
+}
+
+#[tokio::test]
+#[tracing_test::traced_test]
+async fn test_engine_replay() {
+    use crate::prelude::Println;
+
+    let fixture = r#"
+```runmd
++ .operation a
+<builtin.println>                   Hello World a
+```
+"#;
+
+    let engine = compile_fixture(Engine::builder().build(), fixture)
+        .await
+        .unwrap();
+
+    let tc = engine
+        .package
+        .as_ref()
+        .unwrap()
+        .search("println?b=0&n=1")
+        .pop()
+        .unwrap()
+        .program
+        .context()
+        .unwrap();
+
+    let mut snapshot = tc.snapshot::<Println>().await;
+    assert!(snapshot.address.is_some());
+
+    let mut plugin = snapshot.plugin::<Println>().unwrap();
+    assert_eq!("Hello World a", plugin.line);
+    plugin.line = String::from("Hello World replayed");
+    snapshot.frame = plugin.to_wire_frame(tc.attribute);
+
+    let snapshot = Snapshot::from_bytes(snapshot.to_bytes().unwrap()).unwrap();
+    let replayed = engine.replay::<Println>(&snapshot).await.unwrap();
+    assert_eq!(
+        "Hello World replayed",
+        replayed.initialized::<Println>().await.line
+    );
+
+    // Hosted resource should not be modified by the replay
+    let hosted = engine
+        .get_resource(snapshot.address.as_ref().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        "Hello World a",
+        hosted.context().initialized::<Println>().await.line
+    );
+}
+
+#[tokio::test]
+#[tracing_test::traced_test]
+async fn test_engine_metrics() {
+    let fixture = r#"
+```runmd
++ .operation metrics-a
+<builtin.println>                   Hello World metrics
+```
+"#;
+
+    let engine = compile_fixture(Engine::builder().build(), fixture)
+        .await
+        .unwrap();
+    let (eh, _) = engine.default_startup().await.unwrap();
+
+    eh.run("engine://metrics-a").await.unwrap();
+    eh.run("engine://metrics-a").await.unwrap();
+    assert!(eh.run("engine://metrics-missing").await.is_err());
+
+    let metrics = eh.metrics();
+    assert_eq!(
+        crate::metrics::CallCounters {
+            calls: 2,
+            failures: 0
+        },
+        metrics.calls("engine://metrics-a")
+    );
+    assert_eq!(
+        crate::metrics::CallCounters {
+            calls: 1,
+            failures: 1
+        },
+        metrics.calls("engine://metrics-missing")
+    );
+    assert_eq!(0, metrics.packet_queue_depth);
+    assert!(metrics.packet_queue_depth_max >= 1);
+
+    let text = metrics.to_prometheus();
+    assert!(text.contains("loopio_engine_calls_total{address=\"engine://metrics-a\"} 2"));
+    assert!(
+        text.contains("loopio_engine_call_failures_total{address=\"engine://metrics-missing\"} 1")
+    );
+}
+
+#[tokio::test]
+#[tracing_test::traced_test]
+async fn test_engine_wire_metrics() {
+    use crate::prelude::Println;
+
+    let compile = || async {
+        let fixture = r#"
+```runmd
++ .operation a
+<builtin.println>                   Hello World wire metrics
+```
+"#;
+
+        compile_fixture(Engine::builder().build(), fixture)
+            .await
+            .unwrap()
+    };
+    let engine = compile().await;
+    let other = compile().await;
+
+    let address = engine
+        .package
+        .as_ref()
+        .unwrap()
+        .search("println?b=0&n=1")
+        .pop()
+        .unwrap()
+        .host
+        .address()
+        .unwrap()
+        .to_string();
+
+    // Wire servers created by a hosted plugin record to the registry of the engine hosting it
+    let mut tc = engine
+        .get_resource(&address)
+        .await
+        .unwrap()
+        .context()
+        .clone();
+    let server = WireServer::<Println>::new(&mut tc).await.unwrap();
+    let packet = PacketRoutes::<Println>::new(Println::default())
+        .route::<0>()
+        .encode();
+    server.commit(&[packet]).unwrap();
+
+    assert_eq!(1, server.counters().forwarded);
+    assert_eq!(1, engine.metrics().wire.plugin::<Println>().forwarded);
+    assert_eq!(
+        WireCounters::default(),
+        other.metrics().wire.plugin::<Println>()
+    );
+}
+
+#[tokio::test(start_paused = true)]
+#[tracing_test::traced_test]
+async fn test_engine_schedule() {
+    let fixture = r#"
+```runmd
++ .operation schedule-a
+<builtin.println>                   Hello World schedule
+
++ .schedule schedule-heartbeat
+: .every    1s
+: .run      schedule-a
+: .overlap  queue
+```
+"#;
+
+    // Schedules are driven on the engine's runtime, so the engine uses the test's runtime w/ the paused clock instead
+    let mut engine = Engine::builder().build();
+    if let Some(runtime) = engine.runtime.take() {
+        runtime.shutdown_background();
+    }
+    let engine = compile_fixture(engine, fixture).await.unwrap();
+    let (eh, _) = engine.default_startup().await.unwrap();
+
+    // The paused clock only advances once every run that is due has completed, so exactly three runs are due
+    tokio::time::sleep(std::time::Duration::from_millis(3500)).await;
+    assert_eq!(3, eh.metrics().calls("engine://schedule-a").calls);
+
+    let resource = eh.hosted_resource("engine://schedule-a").await.unwrap();
+    let published = resource.context().cached::<Published>().unwrap();
+    assert!(published
+        .next_run
+        .contains_key("engine://schedule-heartbeat"));
+
+    let fixture = r#"
+```runmd
++ .operation schedule-b
+<builtin.println>                   Hello World schedule
+
++ .schedule schedule-invalid
+: .cron     not a cron expression
+: .run      schedule-b
+```
+"#;
+
+    let engine = Engine::builder().build();
+    assert!(compile_fixture(engine, fixture).await.is_err());
+}
+
+#[tokio::test]
+#[tracing_test::traced_test]
+async fn test_engine_pipeline() {
+    let fixture = r#"
+```runmd
++ .operation pipeline-fetch
+<builtin.println>                   fetch
+
++ .operation pipeline-build
+<builtin.println>                   build
+
++ .operation pipeline-test
+<builtin.println>                   test
+
++ .pipeline pipeline-ci
+: .step pipeline-test
+|# needs = pipeline-fetch, pipeline-build
+: .step pipeline-fetch
+: .step pipeline-build
+|# needs = pipeline-fetch
+```
+"#;
+
+    let engine = compile_fixture(Engine::builder().build(), fixture)
+        .await
+        .unwrap();
+    let (eh, _) = engine.default_startup().await.unwrap();
+
+    let tc = eh.run("engine://pipeline-ci").await.unwrap();
+    let report = tc
+        .transient()
+        .await
+        .root_ref()
+        .current::<crate::pipeline::PipelineReport>()
+        .unwrap();
+    assert_eq!(
+        vec!["pipeline-fetch", "pipeline-build", "pipeline-test"],
+        report.order
+    );
+
+    let fixture = r#"
+```runmd
++ .operation pipeline-cycle-a
+<builtin.println>                   a
+
++ .operation pipeline-cycle-b
+<builtin.println>                   b
+
++ .pipeline pipeline-cycle
+: .step pipeline-cycle-a
+|# needs = pipeline-cycle-b
+: .step pipeline-cycle-b
+|# needs = pipeline-cycle-a
+```
+"#;
+
+    let engine = Engine::builder().build();
+    let err = compile_fixture(engine, fixture).await.unwrap_err();
+    assert!(err.to_string().contains("cycle"), "{err}");
+}
+
+#[tokio::test]
+async fn test_engine_json_schema() {
+    let mut builder = Engine::builder();
+    builder.enable::<Published>();
+    let engine = builder.build();
+
+    let symbols = engine
+        .schemas()
+        .into_iter()
+        .map(|s| s.symbol)
+        .collect::<Vec<_>>();
+    assert!(symbols.iter().any(|s| s == Published::symbol()));
+
+    let schema = engine.json_schema();
+
+    let published = &schema["$defs"][Published::symbol()];
+    assert_eq!(
+        "List of all published addresses hosted on an engine,",
+        published["description"]
+    );
+    assert_eq!("label", published["x-reality-input"]);
+    assert_eq!(
+        "Label for this list,",
+        published["properties"]["label"]["description"]
+    );
+    assert_eq!("array", published["properties"]["resources"]["type"]);
+    assert_eq!("object", published["properties"]["next_run"]["type"]);
+    assert_eq!(
+        "string",
+        published["properties"]["next_run"]["additionalProperties"]["type"]
+    );
+}
+
+#[tokio::test]
+async fn test_engine_reference_docs() {
+    let engine = Engine::builder().build();
+
+    let docs = engine.reference_docs();
+    let builtin = docs.get("builtin").expect("should have builtin plugins");
+    assert!(builtin.contains("## builtin.process"));
+    assert!(builtin
+        .contains("| `.arg` | `String` | vec | no | List of arguments to add to the process, |"));
+
+    // Each example block should compile, secrets are resolved when parsed
+    std::env::set_var("EXAMPLE_SECRET", "example");
+    let mut fixture = String::new();
+    for (group, page) in docs.iter() {
+        for (idx, example) in page.split("```runmd").skip(1).enumerate() {
+            let example = example.split("```").next().unwrap_or_default().replacen(
+                ".operation example",
+                &format!(".operation {group}-{idx}"),
+                1,
+            );
+            fixture.push_str(&format!("```runmd{example}```\n"));
+        }
+    }
+
+    let _ = compile_fixture(engine, &fixture).await.unwrap();
+}
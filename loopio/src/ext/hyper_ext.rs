@@ -274,6 +274,10 @@ pub struct Request {
     ///
     #[reality(map_of=String)]
     headers: BTreeMap<String, String>,
+    /// Headers w/ secret values to attach to the request, i.e. `: Authorization .secret_header env:API_TOKEN`,
+    ///
+    #[reality(map_of=Secret)]
+    secret_header: BTreeMap<String, Secret>,
     /// Http method to use for the request,
     ///
     method: String,
@@ -326,6 +330,10 @@ impl CallAsync for Request {
             request = request.header(header, value);
         }
 
+        for (header, secret) in initialized.secret_header.iter() {
+            request = request.header(header, secret.reveal()?);
+        }
+
         // Body of the request
         let body = if let Some(data) = initialized.data.as_ref() {
             Body::from(tokio::fs::read(data).await?)
@@ -72,6 +72,11 @@ pub mod utility {
         #[cfg(feature = "poem-ext")]
         #[reality(ext)]
         reverse_proxy: super::poem_ext::ReverseProxy,
+        /// Adds a metrics plugin,
+        ///
+        #[cfg(feature = "poem-ext")]
+        #[reality(ext)]
+        metrics: super::poem_ext::MetricsEndpoint,
         /// Adds a request plugin,
         ///
         #[cfg(feature = "hyper-ext")]
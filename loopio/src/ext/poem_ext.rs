@@ -450,6 +450,72 @@ async fn on_proxy(
     }
 }
 
+/// Plugin that responds w/ the engine's metrics in the prometheus text format,
+///
+/// Can be mounted on an engine proxy by adding it to an operation and routing to that operation,
+///
+/// ```md
+/// + .operation metrics
+/// <builtin.metrics>
+///
+/// + .operation start_engine_proxy
+/// <builtin.engine-proxy>  localhost:8080
+/// : .route metrics
+/// |# path = /metrics
+/// ```
+///
+#[derive(Reality, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[reality(plugin, call = respond_with_metrics, rename = "metrics", group = "builtin")]
+pub struct MetricsEndpoint {
+    /// Unused,
+    ///
+    #[reality(derive_fromstr)]
+    _unused: String,
+}
+
+async fn respond_with_metrics(tc: &mut ThunkContext) -> anyhow::Result<()> {
+    let eh = tc
+        .engine_handle()
+        .await
+        .ok_or(anyhow::anyhow!("Metrics requires an engine handle"))?;
+
+    let text = eh.metrics().to_prometheus();
+
+    #[cfg(feature = "hyper-ext")]
+    {
+        let response = hyper::Response::builder()
+            .status(StatusCode::OK)
+            .header(
+                header::CONTENT_TYPE,
+                crate::metrics::PROMETHEUS_CONTENT_TYPE,
+            )
+            .body(hyper::Body::from(text))?;
+
+        tc.transient_mut().await.root().put(response);
+    }
+
+    #[cfg(not(feature = "hyper-ext"))]
+    {
+        let mut parts = ResponseParts {
+            status: StatusCode::OK,
+            version: Version::HTTP_11,
+            headers: HeaderMap::new(),
+            extensions: Extensions::new(),
+        };
+        parts.headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(crate::metrics::PROMETHEUS_CONTENT_TYPE),
+        );
+
+        let mut transient = tc.transient_mut().await;
+        let mut root = transient.root();
+        root.put(parts);
+        root.put(Body::from_string(text));
+    }
+
+    Ok(())
+}
+
 /// Reverse proxy config,
 ///
 #[derive(Reality, Serialize, Deserialize, Clone, PartialEq, Default)]
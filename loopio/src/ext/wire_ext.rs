@@ -97,8 +97,7 @@ impl VirtualBus {
                     assert!(port
                         .task
                         .set(tokio::spawn(async move {
-                            info!("Starting wire server and port");
-                            tokio::spawn(server.clone().start_port());
+                            info!("Starting wire server");
                             server.start().await?;
                             Ok(())
                         }))
@@ -0,0 +1,574 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fmt::Write;
+use std::str::FromStr;
+
+use reality::prelude::runir::prelude::NodeRepr;
+use reality::prelude::*;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::prelude::ActionExt;
+use crate::prelude::Address;
+use crate::prelude::Host;
+use crate::prelude::Operation;
+use crate::prelude::Sequence;
+
+/// Marker that starts a graph embedded in a markdown file,
+///
+pub const GRAPH_BEGIN_MARKER: &str = "<!-- loopio-graph -->";
+
+/// Marker that ends a graph embedded in a markdown file,
+///
+pub const GRAPH_END_MARKER: &str = "<!-- /loopio-graph -->";
+
+/// Graph of how the hosts, operations, sequences, extensions and events of a compiled package relate,
+///
+/// **Example**
+///
+/// ```rs no_run
+/// let graph = WorkspaceGraph::from_package(&package).await?;
+///
+/// // Graphviz
+/// println!("{}", graph.to_dot());
+///
+/// // Mermaid, embedded in the markdown file the runmd lives in
+/// let markdown = std::fs::read_to_string("demo.md")?;
+/// std::fs::write("demo.md", graph.embed(&markdown))?;
+/// ```
+///
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceGraph {
+    /// Nodes of the graph by id,
+    ///
+    pub nodes: BTreeMap<String, GraphNode>,
+    /// Edges of the graph in the order they were found,
+    ///
+    pub edges: Vec<GraphEdge>,
+}
+
+impl WorkspaceGraph {
+    /// Returns a graph of every addressable program in a package,
+    ///
+    /// Walks the extensions of each host repr, the steps of each `Sequence`, the actions, start and events of each
+    /// `Host`, and the `notify`/`listen` annotations of each extension and host action.
+    ///
+    pub async fn from_package(package: &Package) -> anyhow::Result<Self> {
+        let mut graph = WorkspaceGraph::default();
+
+        for m in package.search("*") {
+            let Some(address) = m.host.address() else {
+                continue;
+            };
+            let id = graph_id(address.as_str());
+            let mut context = m.program.context()?;
+
+            if context.attribute.is_resource::<Host>() {
+                graph.add_node(&id, GraphNodeKind::Host, id.clone());
+
+                let host = context.as_remote_plugin::<Host>().await;
+                if let Some(start) = host.start.as_ref().and_then(|s| s.value()) {
+                    graph.add_edge(&id, &graph_id(start.to_string()), GraphEdgeKind::Start);
+                }
+
+                for action in host.action.iter() {
+                    if let Some(target) = action.value() {
+                        let target = graph_id(target.to_string());
+                        graph.add_edge(&id, &target, GraphEdgeKind::Action);
+
+                        // Decorations on the action apply to the action's target
+                        let node = action
+                            .property
+                            .and_then(|p| p.repr())
+                            .and_then(|r| r.as_node());
+                        graph.add_annotations(&target, node);
+                    }
+                }
+
+                for event in host.event.iter() {
+                    let event = graph.add_event(&event.name);
+                    graph.add_edge(&id, &event, GraphEdgeKind::Event);
+                }
+            } else if context.attribute.is_resource::<Sequence>() {
+                graph.add_node(&id, GraphNodeKind::Sequence, id.clone());
+
+                let sequence = context.as_remote_plugin::<Sequence>().await;
+                for (idx, step) in sequence.steps().iter().enumerate() {
+                    for s in step.iter() {
+                        graph.add_edge(&id, &graph_id(&s.0), GraphEdgeKind::Step(idx + 1));
+                    }
+                }
+            } else if context.attribute.is_resource::<Operation>() {
+                graph.add_node(&id, GraphNodeKind::Operation, id.clone());
+            } else {
+                // Extensions are also published, they are added w/ the node that owns them
+                continue;
+            }
+
+            for ext in m.host.extensions().iter().flat_map(|e| e.iter()) {
+                if let Some(address) = ext.as_host().and_then(|h| h.address()) {
+                    let ext_id = graph_id(address.as_str());
+                    let label = ext
+                        .as_node()
+                        .and_then(|n| n.path())
+                        .map(|p| p.split('?').next().unwrap_or_default().to_string())
+                        .unwrap_or(ext_id.clone());
+
+                    graph.add_node(&ext_id, GraphNodeKind::Extension, label);
+                    graph.add_edge(&id, &ext_id, GraphEdgeKind::Extension);
+                    graph.add_annotations(&ext_id, ext.as_node());
+                }
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /// Adds a node to the graph,
+    ///
+    /// **Note** If the node was previously only referenced by an edge, the kind and label are updated.
+    ///
+    pub fn add_node(&mut self, id: &str, kind: GraphNodeKind, label: impl Into<String>) {
+        let node = self.nodes.entry(id.to_string()).or_insert(GraphNode {
+            id: id.to_string(),
+            label: id.to_string(),
+            kind: GraphNodeKind::Reference,
+        });
+
+        if node.kind == GraphNodeKind::Reference {
+            node.kind = kind;
+            node.label = label.into();
+        }
+    }
+
+    /// Adds an edge to the graph, adding either end as a reference if it has not been added,
+    ///
+    pub fn add_edge(&mut self, from: &str, to: &str, kind: GraphEdgeKind) {
+        self.add_node(from, GraphNodeKind::Reference, from);
+        self.add_node(to, GraphNodeKind::Reference, to);
+
+        let edge = GraphEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind,
+        };
+
+        if !self.edges.contains(&edge) {
+            self.edges.push(edge);
+        }
+    }
+
+    /// Adds edges for the `notify` and `listen` annotations of a node,
+    ///
+    fn add_annotations(&mut self, id: &str, node: Option<NodeRepr>) {
+        if let Some(annotations) = node.and_then(|n| n.annotations()) {
+            if let Some(event) = annotations.get("notify") {
+                let event = self.add_event(event);
+                self.add_edge(id, &event, GraphEdgeKind::Notify);
+            }
+
+            if let Some(event) = annotations.get("listen") {
+                let event = self.add_event(event);
+                self.add_edge(&event, id, GraphEdgeKind::Listen);
+            }
+        }
+    }
+
+    /// Adds an event node and returns its id,
+    ///
+    fn add_event(&mut self, name: &str) -> String {
+        let id = event_id(name);
+        self.add_node(&id, GraphNodeKind::Event, name.trim());
+        id
+    }
+
+    /// Returns the graph in Graphviz DOT format,
+    ///
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph workspace {\n    rankdir=LR;\n");
+
+        for node in self.nodes.values() {
+            let _ = writeln!(
+                dot,
+                "    \"{}\" [label=\"{}\", shape={}];",
+                escape_dot(&node.id),
+                escape_dot(&node.label),
+                node.kind.dot_shape()
+            );
+        }
+
+        for edge in self.edges.iter() {
+            let style = if edge.kind.is_event() {
+                ", style=dashed"
+            } else {
+                ""
+            };
+
+            let _ = writeln!(
+                dot,
+                "    \"{}\" -> \"{}\" [label=\"{}\"{style}];",
+                escape_dot(&edge.from),
+                escape_dot(&edge.to),
+                edge.kind
+            );
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Returns the graph as a Mermaid flowchart,
+    ///
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("flowchart LR\n");
+
+        // Mermaid ids cannot contain most punctuation so nodes are referred to by index
+        let ids = self
+            .nodes
+            .keys()
+            .enumerate()
+            .map(|(idx, id)| (id.as_str(), format!("n{idx}")))
+            .collect::<BTreeMap<_, _>>();
+
+        for node in self.nodes.values() {
+            let (open, close) = node.kind.mermaid_shape();
+            let _ = writeln!(
+                mermaid,
+                "    {}{open}\"{}\"{close}",
+                ids[node.id.as_str()],
+                escape_mermaid(&node.label)
+            );
+        }
+
+        for edge in self.edges.iter() {
+            let arrow = if edge.kind.is_event() { "-.->" } else { "-->" };
+
+            let _ = writeln!(
+                mermaid,
+                "    {} {arrow}|{}| {}",
+                ids[edge.from.as_str()],
+                edge.kind,
+                ids[edge.to.as_str()]
+            );
+        }
+
+        mermaid
+    }
+
+    /// Returns the Mermaid flowchart as a fenced markdown code block,
+    ///
+    pub fn to_markdown(&self) -> String {
+        format!("```mermaid\n{}```\n", self.to_mermaid())
+    }
+
+    /// Returns markdown w/ the Mermaid flowchart embedded,
+    ///
+    /// If the markdown already contains a graph between `GRAPH_BEGIN_MARKER` and `GRAPH_END_MARKER` it is replaced,
+    /// otherwise the graph is appended to the end of the markdown.
+    ///
+    /// **Note** Runmd only parses `runmd` code blocks, so the graph can be embedded in the same file as the runmd it
+    /// was generated from.
+    ///
+    pub fn embed(&self, markdown: &str) -> String {
+        let block = format!(
+            "{GRAPH_BEGIN_MARKER}\n{}{GRAPH_END_MARKER}",
+            self.to_markdown()
+        );
+
+        if let Some(start) = markdown.find(GRAPH_BEGIN_MARKER) {
+            if let Some(end) = markdown[start..].find(GRAPH_END_MARKER) {
+                let end = start + end + GRAPH_END_MARKER.len();
+                return format!("{}{block}{}", &markdown[..start], &markdown[end..]);
+            }
+        }
+
+        let mut embedded = markdown.trim_end().to_string();
+        if !embedded.is_empty() {
+            embedded.push_str("\n\n");
+        }
+        embedded.push_str(&block);
+        embedded.push('\n');
+        embedded
+    }
+}
+
+/// Node of a workspace graph,
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphNode {
+    /// Id of the node, i.e. the address of the program w/o a host,
+    ///
+    pub id: String,
+    /// Label displayed for the node,
+    ///
+    pub label: String,
+    /// Kind of node,
+    ///
+    pub kind: GraphNodeKind,
+}
+
+/// Kind of node in a workspace graph,
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GraphNodeKind {
+    /// `Host` node,
+    ///
+    Host,
+    /// `Operation` node,
+    ///
+    Operation,
+    /// `Sequence` node,
+    ///
+    Sequence,
+    /// Plugin extension of a node,
+    ///
+    Extension,
+    /// Event managed by a host or referenced by a `notify`/`listen` annotation,
+    ///
+    Event,
+    /// Address referenced by an edge that is not defined in the package,
+    ///
+    Reference,
+}
+
+impl GraphNodeKind {
+    /// Returns the Graphviz shape of the node,
+    ///
+    fn dot_shape(&self) -> &'static str {
+        match self {
+            GraphNodeKind::Host => "box3d",
+            GraphNodeKind::Operation => "box",
+            GraphNodeKind::Sequence => "cds",
+            GraphNodeKind::Extension => "ellipse",
+            GraphNodeKind::Event => "diamond",
+            GraphNodeKind::Reference => "plaintext",
+        }
+    }
+
+    /// Returns the opening and closing delimiters of the Mermaid shape of the node,
+    ///
+    fn mermaid_shape(&self) -> (&'static str, &'static str) {
+        match self {
+            GraphNodeKind::Host => ("[[", "]]"),
+            GraphNodeKind::Operation => ("[", "]"),
+            GraphNodeKind::Sequence => ("[/", "/]"),
+            GraphNodeKind::Extension => ("(", ")"),
+            GraphNodeKind::Event => ("{{", "}}"),
+            GraphNodeKind::Reference => ("[", "]"),
+        }
+    }
+}
+
+/// Edge of a workspace graph,
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphEdge {
+    /// Id of the node the edge starts from,
+    ///
+    pub from: String,
+    /// Id of the node the edge ends at,
+    ///
+    pub to: String,
+    /// Kind of edge,
+    ///
+    pub kind: GraphEdgeKind,
+}
+
+/// Kind of edge in a workspace graph,
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GraphEdgeKind {
+    /// Node is extended by a plugin,
+    ///
+    Extension,
+    /// Sequence calls an operation at a step, starting from 1,
+    ///
+    Step(usize),
+    /// Host registers an action,
+    ///
+    Action,
+    /// Host starts w/ an action,
+    ///
+    Start,
+    /// Host manages an event,
+    ///
+    Event,
+    /// Node notifies an event,
+    ///
+    Notify,
+    /// Event is listened to by a node,
+    ///
+    Listen,
+}
+
+impl GraphEdgeKind {
+    /// Returns true if the edge is signaled by an event rather than a call,
+    ///
+    fn is_event(&self) -> bool {
+        matches!(self, GraphEdgeKind::Notify | GraphEdgeKind::Listen)
+    }
+}
+
+impl Display for GraphEdgeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphEdgeKind::Extension => write!(f, "ext"),
+            GraphEdgeKind::Step(idx) => write!(f, "step {idx}"),
+            GraphEdgeKind::Action => write!(f, "action"),
+            GraphEdgeKind::Start => write!(f, "start"),
+            GraphEdgeKind::Event => write!(f, "event"),
+            GraphEdgeKind::Notify => write!(f, "notify"),
+            GraphEdgeKind::Listen => write!(f, "listen"),
+        }
+    }
+}
+
+/// Returns the graph id of an address,
+///
+/// **Note** The host is removed so that actions routed through a host, i.e. `demo://b`, refer to the same node as the
+/// operation they were defined by.
+///
+fn graph_id(address: impl AsRef<str>) -> String {
+    let address = address.as_ref().trim();
+
+    match Address::from_str(address) {
+        Ok(parsed) if !parsed.node().is_empty() => {
+            let mut id = parsed.node().to_string();
+            if !parsed.path().is_empty() {
+                id = format!("{id}/{}", parsed.path());
+            }
+            if let Some(filter) = parsed.filter_str() {
+                id = format!("{id}?{filter}");
+            }
+            if let Some(tag) = parsed.tag() {
+                id = format!("{id}#{tag}");
+            }
+            id
+        }
+        _ => address.to_string(),
+    }
+}
+
+/// Returns the graph id of an event,
+///
+fn event_id(name: &str) -> String {
+    format!("event:{}", name.trim())
+}
+
+/// Escapes a DOT string,
+///
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Escapes a Mermaid label,
+///
+fn escape_mermaid(value: &str) -> String {
+    value.replace('"', "#quot;")
+}
+
+#[tokio::test]
+async fn test_workspace_graph() {
+    let runmd = r#"# Demo
+
+```runmd
++ .operation a
+<start/builtin.println>                   Hello World a
+|# notify =     op_b_complete
+
++ .operation b
+<builtin.println>                         Hello World b
+|# listen =     op_b_complete
+
++ .sequence test
+: .step    demo://b, demo://a
+: .step    a
+
++ .host demo
+: .start        test
+: .action       a/start/builtin.println
+|# notify = a_called
+: .action       b
+: .event        op_b_complete
+```
+"#;
+
+    let mut workspace = Workspace::new();
+    workspace.add_buffer("demo.md", runmd);
+
+    let engine = crate::engine::Engine::builder().build();
+    let engine = engine.compile(workspace).await.unwrap();
+    let graph = WorkspaceGraph::from_package(engine.package.as_ref().unwrap())
+        .await
+        .unwrap();
+
+    let kind = |id: &str| graph.nodes.get(id).map(|n| n.kind);
+    assert_eq!(Some(GraphNodeKind::Host), kind("demo"));
+    assert_eq!(Some(GraphNodeKind::Sequence), kind("test"));
+    assert_eq!(Some(GraphNodeKind::Operation), kind("a"));
+    assert_eq!(
+        Some(GraphNodeKind::Extension),
+        kind("a/start/builtin.println")
+    );
+    assert_eq!(Some(GraphNodeKind::Event), kind("event:op_b_complete"));
+
+    let has_edge = |from: &str, to: &str, kind: GraphEdgeKind| {
+        graph
+            .edges
+            .iter()
+            .any(|e| e.from == from && e.to == to && e.kind == kind)
+    };
+    assert!(has_edge("demo", "test", GraphEdgeKind::Start));
+    assert!(has_edge("demo", "b", GraphEdgeKind::Action));
+    assert!(has_edge(
+        "demo",
+        "event:op_b_complete",
+        GraphEdgeKind::Event
+    ));
+    assert!(has_edge("test", "b", GraphEdgeKind::Step(1)));
+    assert!(has_edge("test", "a", GraphEdgeKind::Step(1)));
+    assert!(has_edge("test", "a", GraphEdgeKind::Step(2)));
+    assert!(has_edge(
+        "a",
+        "a/start/builtin.println",
+        GraphEdgeKind::Extension
+    ));
+    assert!(has_edge(
+        "a/start/builtin.println",
+        "event:op_b_complete",
+        GraphEdgeKind::Notify
+    ));
+    assert!(has_edge(
+        "a/start/builtin.println",
+        "event:a_called",
+        GraphEdgeKind::Notify
+    ));
+    assert!(graph.edges.iter().any(|e| e.from == "event:op_b_complete"
+        && e.to.starts_with("b/builtin.println")
+        && e.kind == GraphEdgeKind::Listen));
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph workspace {"));
+    assert!(dot.contains("\"demo\" -> \"test\" [label=\"start\"];"));
+    assert!(dot.contains("[label=\"notify\", style=dashed]"));
+
+    let mermaid = graph.to_mermaid();
+    assert!(mermaid.starts_with("flowchart LR\n"));
+    assert!(mermaid.contains("[[\"demo\"]]"));
+    assert!(mermaid.contains("-.->|listen|"));
+
+    // The graph can be embedded in, and replaced within, the markdown the runmd lives in
+    let embedded = graph.embed(runmd);
+    assert!(embedded.starts_with(runmd.trim_end()));
+    assert!(embedded.contains(&graph.to_markdown()));
+    assert_eq!(embedded, graph.embed(&embedded));
+
+    let mut workspace = Workspace::new();
+    workspace.add_buffer("demo.md", embedded);
+    let engine = crate::engine::Engine::builder().build();
+    let engine = engine.compile(workspace).await.unwrap();
+    let recompiled = WorkspaceGraph::from_package(engine.package.as_ref().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(graph, recompiled);
+}
@@ -0,0 +1,283 @@
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
+use reality::prelude::*;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+use crate::engine::Engine;
+use crate::engine::EngineBuilder;
+use crate::prelude::Address;
+use crate::prelude::EngineHandle;
+
+/// Test harness that compiles a runmd fixture w/ an isolated engine,
+///
+/// The engine is built w/ a fresh runir entropy value so that fixtures compiled by parallel tests do not
+/// share intern handles. Usually created by `#[derive(RealityTest)]`.
+///
+pub struct TestHarness {
+    /// Handle to the started engine,
+    ///
+    eh: EngineHandle,
+    /// Addresses published by the engine,
+    ///
+    published: Vec<Address>,
+    /// Runs cached by address,
+    ///
+    runs: Mutex<BTreeMap<String, TestRun>>,
+    /// Engine packet listener,
+    ///
+    _listener: JoinHandle<anyhow::Result<Engine>>,
+}
+
+impl TestHarness {
+    /// Returns an engine builder w/ an isolated runtime,
+    ///
+    pub fn builder() -> EngineBuilder {
+        Engine::builder().enable_isolation()
+    }
+
+    /// Compiles a runmd fixture and starts the engine w/ the default startup procedure,
+    ///
+    pub async fn compile(
+        mut builder: EngineBuilder,
+        name: &str,
+        fixture: &str,
+    ) -> anyhow::Result<Self> {
+        builder
+            .workspace_mut()
+            .add_buffer(format!("{name}.md"), fixture);
+
+        let engine = builder.compile().await?;
+        let published = engine.published_addresses().cloned().collect();
+        let (eh, _listener) = engine.default_startup().await?;
+
+        Ok(Self {
+            eh,
+            published,
+            runs: Mutex::new(BTreeMap::new()),
+            _listener,
+        })
+    }
+
+    /// Returns a new engine handle,
+    ///
+    pub fn engine_handle(&self) -> EngineHandle {
+        self.eh.clone()
+    }
+
+    /// Resolves an address w/o a filter to the published address it uniquely refers to,
+    ///
+    /// **Note** Allows plugins to be addressed as `<operation>/<plugin>` w/o knowing the block/node filter.
+    ///
+    pub fn resolve(&self, address: &str) -> String {
+        match address.parse::<Address>() {
+            Ok(parsed) if parsed.filter_str().is_none() => {
+                let mut matches = self
+                    .published
+                    .iter()
+                    .filter(|p| p.node() == parsed.node() && p.path() == parsed.path());
+
+                match (matches.next(), matches.next()) {
+                    (Some(published), None) => published.to_string(),
+                    _ => address.to_string(),
+                }
+            }
+            _ => address.to_string(),
+        }
+    }
+
+    /// Runs an address,
+    ///
+    pub async fn run(&self, address: impl Into<String>) -> anyhow::Result<TestRun> {
+        let address = address.into();
+        let context = self
+            .eh
+            .run(self.resolve(&address))
+            .await
+            .map_err(|err| anyhow!("Could not run {address}, {err}"))?;
+
+        Ok(TestRun { address, context })
+    }
+
+    /// Runs an address if it has not already been run by this harness and returns the result,
+    ///
+    pub async fn run_once(&self, address: impl Into<String>) -> anyhow::Result<TestRun> {
+        let address = address.into();
+        let mut runs = self.runs.lock().await;
+
+        if let Some(run) = runs.get(&address) {
+            return Ok(run.clone());
+        }
+
+        let run = self.run(address.clone()).await?;
+        runs.insert(address, run.clone());
+        Ok(run)
+    }
+}
+
+/// Result of running an address w/ a test harness,
+///
+#[derive(Clone)]
+pub struct TestRun {
+    /// Address that was run,
+    ///
+    pub address: String,
+    /// Context returned by the address,
+    ///
+    pub context: ThunkContext,
+}
+
+impl TestRun {
+    /// Returns the state of plugin P from the returned context,
+    ///
+    /// **Note** The address that was run must refer to the plugin, i.e. `<operation>/<plugin>`.
+    ///
+    pub async fn plugin<P: Plugin + Sync + Send + 'static>(&self) -> P {
+        self.context.initialized::<P>().await
+    }
+
+    /// Returns a resource from the root of the returned context's transient storage,
+    ///
+    pub async fn transient<T>(&self) -> anyhow::Result<T>
+    where
+        T: ToOwned<Owned = T> + Send + Sync + 'static,
+    {
+        self.context
+            .transient()
+            .await
+            .root_ref()
+            .current::<T>()
+            .ok_or(anyhow!(
+                "{} did not put a {} in transient storage",
+                self.address,
+                std::any::type_name::<T>()
+            ))
+    }
+
+    /// Asserts the state of plugin P from the returned context,
+    ///
+    pub async fn assert_plugin<P: Plugin + Sync + Send + 'static>(
+        &self,
+        assert: impl FnOnce(&P),
+    ) -> &Self {
+        assert(&self.plugin::<P>().await);
+        self
+    }
+
+    /// Asserts that the transient storage contains the expected resource,
+    ///
+    pub async fn assert_transient<T>(&self, expected: T) -> &Self
+    where
+        T: ToOwned<Owned = T> + PartialEq + std::fmt::Debug + Send + Sync + 'static,
+    {
+        match self.transient::<T>().await {
+            Ok(actual) => assert_eq!(expected, actual, "transient resource of {}", self.address),
+            Err(err) => panic!("{err}"),
+        }
+        self
+    }
+}
+
+#[allow(unused)]
+mod test {
+    use super::*;
+
+    /// Plugin used to test the harness,
+    ///
+    #[derive(Reality, Debug, Default, Clone)]
+    #[reality(call = harness_echo, plugin, rename = "harness-echo", group = "test")]
+    struct HarnessEcho {
+        #[reality(derive_fromstr)]
+        name: String,
+        greeting: String,
+    }
+
+    async fn harness_echo(tc: &mut ThunkContext) -> anyhow::Result<()> {
+        let init = tc.initialized::<HarnessEcho>().await;
+
+        tc.transient_mut()
+            .await
+            .root()
+            .put(format!("{} {}", init.greeting, init.name));
+        Ok(())
+    }
+
+    #[derive(RealityTest)]
+    #[reality(
+        fixture = r#"
+    ```runmd
+    + .operation harness-echo
+    <test.harness-echo> world
+    : .greeting hello
+    ```
+    "#,
+        enable(HarnessEcho)
+    )]
+    struct HarnessEchoTest {
+        #[reality(plugin = "harness-echo/test.harness-echo")]
+        echo: HarnessEcho,
+        #[reality(transient = "engine://harness-echo")]
+        greeting: String,
+        #[reality(run = "engine://harness-echo")]
+        run: TestRun,
+        not_run: Option<String>,
+    }
+
+    #[tokio::test]
+    async fn test_harness() {
+        let harness = HarnessEchoTest::harness().await.unwrap();
+        let test = HarnessEchoTest::run_with(&harness).await.unwrap();
+
+        assert_eq!("world", test.echo.name);
+        assert_eq!("hello", test.echo.greeting);
+        assert_eq!("hello world", test.greeting);
+        assert_eq!("engine://harness-echo", test.run.address);
+        assert!(harness
+            .resolve("harness-echo/test.harness-echo")
+            .starts_with("engine://harness-echo/test.harness-echo?"));
+        assert!(test.not_run.is_none());
+
+        test.run.assert_transient("hello world".to_string()).await;
+
+        harness
+            .run_once("harness-echo/test.harness-echo")
+            .await
+            .unwrap()
+            .assert_plugin::<HarnessEcho>(|e| assert_eq!("world", e.name))
+            .await
+            .assert_transient("hello world".to_string())
+            .await;
+
+        assert!(harness.run("engine://harness-echo-unknown").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_harness_isolation() {
+        // Compiles the same operation names as test_harness on a separate entropy value
+        let harness = TestHarness::compile(
+            {
+                let mut builder = TestHarness::builder();
+                builder.enable::<HarnessEcho>();
+                builder
+            },
+            "harness-isolation",
+            r#"
+    ```runmd
+    + .operation harness-echo
+    <test.harness-echo> isolated
+    : .greeting hi
+    ```
+    "#,
+        )
+        .await
+        .unwrap();
+
+        harness
+            .run_once("engine://harness-echo")
+            .await
+            .unwrap()
+            .assert_transient("hi isolated".to_string())
+            .await;
+    }
+}
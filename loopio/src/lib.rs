@@ -1,3 +1,6 @@
+// Lets code generated by `#[derive(RealityTest)]` refer to `::loopio` from within this crate
+extern crate self as loopio;
+
 pub mod action;
 pub mod address;
 pub mod background_work;
@@ -5,10 +8,16 @@ pub mod engine;
 pub mod errors;
 mod ext;
 pub mod foreground;
+pub mod graph;
+pub mod harness;
 pub mod host;
+pub mod metrics;
 pub mod operation;
+pub mod pipeline;
 pub mod prelude;
+pub mod schedule;
 pub mod sequence;
+pub mod timeline;
 pub mod work;
 
 #[allow(unused_imports)]
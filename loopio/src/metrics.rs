@@ -0,0 +1,290 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use reality::prelude::*;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Content type of the prometheus text exposition format,
+///
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Counters for calls made to an address,
+///
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallCounters {
+    /// Number of calls made to the address,
+    ///
+    pub calls: u64,
+    /// Number of calls that returned an error,
+    ///
+    pub failures: u64,
+}
+
+/// Registry of metrics collected by an engine,
+///
+/// The registry is shared by every engine handle created from the same engine. Wire servers created by plugins hosted
+/// by the engine record to the engine's wire registry.
+///
+#[derive(Default, Clone)]
+pub struct Metrics {
+    /// Call counters by address,
+    ///
+    calls: Arc<Mutex<BTreeMap<String, CallCounters>>>,
+    /// Current number of packets waiting in the engine's packet queue,
+    ///
+    queue_depth: Arc<AtomicU64>,
+    /// Highest number of packets observed waiting in the engine's packet queue,
+    ///
+    queue_depth_max: Arc<AtomicU64>,
+    /// Wire counters recorded by wire servers of the engine's plugins,
+    ///
+    wire: WireMetricsRegistry,
+}
+
+impl Metrics {
+    /// Records the result of a call to an address,
+    ///
+    pub fn record_call<T>(&self, address: impl AsRef<str>, result: &anyhow::Result<T>) {
+        if let Ok(mut calls) = self.calls.lock() {
+            let counters = calls.entry(address.as_ref().to_string()).or_default();
+            counters.calls += 1;
+            if result.is_err() {
+                counters.failures += 1;
+            }
+        }
+    }
+
+    /// Records that a packet was queued for the engine,
+    ///
+    pub(crate) fn packet_queued(&self) {
+        let depth = self.queue_depth.fetch_add(1, Ordering::Relaxed) + 1;
+        self.queue_depth_max.fetch_max(depth, Ordering::Relaxed);
+    }
+
+    /// Records that a packet was taken from the engine's queue,
+    ///
+    pub(crate) fn packet_received(&self) {
+        let _ = self
+            .queue_depth
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |d| d.checked_sub(1));
+    }
+
+    /// Returns the registry wire servers of the engine's plugins record to,
+    ///
+    pub fn wire(&self) -> WireMetricsRegistry {
+        self.wire.clone()
+    }
+
+    /// Returns a point-in-time copy of the current metrics,
+    ///
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            calls: self.calls.lock().map(|c| c.clone()).unwrap_or_default(),
+            packet_queue_depth: self.queue_depth.load(Ordering::Relaxed),
+            packet_queue_depth_max: self.queue_depth_max.load(Ordering::Relaxed),
+            wire: self.wire.snapshot(),
+        }
+    }
+}
+
+/// Point-in-time copy of engine metrics,
+///
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    /// Call counters by address,
+    ///
+    pub calls: BTreeMap<String, CallCounters>,
+    /// Number of packets waiting in the engine's packet queue,
+    ///
+    pub packet_queue_depth: u64,
+    /// Highest number of packets observed waiting in the engine's packet queue,
+    ///
+    pub packet_queue_depth_max: u64,
+    /// Field packet counters from wire servers of the engine's plugins,
+    ///
+    pub wire: WireMetrics,
+}
+
+impl MetricsSnapshot {
+    /// Returns call counters for an address,
+    ///
+    pub fn calls(&self, address: impl AsRef<str>) -> CallCounters {
+        self.calls
+            .get(address.as_ref())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Renders the snapshot in the prometheus text exposition format,
+    ///
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+
+        write_header(
+            &mut out,
+            "loopio_engine_calls_total",
+            "counter",
+            "Number of calls made to an address.",
+        );
+        for (address, c) in self.calls.iter() {
+            write_sample(
+                &mut out,
+                "loopio_engine_calls_total",
+                &[("address", address)],
+                c.calls,
+            );
+        }
+
+        write_header(
+            &mut out,
+            "loopio_engine_call_failures_total",
+            "counter",
+            "Number of calls to an address that returned an error.",
+        );
+        for (address, c) in self.calls.iter() {
+            write_sample(
+                &mut out,
+                "loopio_engine_call_failures_total",
+                &[("address", address)],
+                c.failures,
+            );
+        }
+
+        write_header(
+            &mut out,
+            "loopio_engine_packet_queue_depth",
+            "gauge",
+            "Number of packets waiting to be handled by the engine.",
+        );
+        write_sample(
+            &mut out,
+            "loopio_engine_packet_queue_depth",
+            &[],
+            self.packet_queue_depth,
+        );
+
+        write_header(
+            &mut out,
+            "loopio_engine_packet_queue_depth_max",
+            "gauge",
+            "Highest number of packets observed waiting to be handled by the engine.",
+        );
+        write_sample(
+            &mut out,
+            "loopio_engine_packet_queue_depth_max",
+            &[],
+            self.packet_queue_depth_max,
+        );
+
+        self.write_wire(
+            &mut out,
+            "reality_wire_packets_received_total",
+            "Number of field packets received by wire servers.",
+            |c| c.received,
+        );
+        self.write_wire(
+            &mut out,
+            "reality_wire_packets_forwarded_total",
+            "Number of field packets committed by wire servers.",
+            |c| c.forwarded,
+        );
+        self.write_wire(
+            &mut out,
+            "reality_wire_packets_retried_total",
+            "Number of committed batches that waited for capacity on a wire port.",
+            |c| c.retried,
+        );
+        self.write_wire(
+            &mut out,
+            "reality_wire_packets_routed_total",
+            "Number of committed field packets queued to frame updates.",
+            |c| c.routed,
+        );
+        self.write_wire(
+            &mut out,
+            "reality_wire_packets_rejected_total",
+            "Number of field packets in batches rejected by wire servers.",
+            |c| c.rejected,
+        );
+
+        out
+    }
+
+    /// Writes a wire counter for each plugin,
+    ///
+    fn write_wire(
+        &self,
+        out: &mut String,
+        name: &str,
+        help: &str,
+        value: impl Fn(&WireCounters) -> u64,
+    ) {
+        write_header(out, name, "counter", help);
+        for (plugin, c) in self.wire.plugins.iter() {
+            write_sample(out, name, &[("plugin", plugin)], value(c));
+        }
+    }
+}
+
+/// Writes the HELP and TYPE lines for a metric,
+///
+fn write_header(out: &mut String, name: &str, ty: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {ty}");
+}
+
+/// Writes a single sample line for a metric,
+///
+fn write_sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: u64) {
+    if labels.is_empty() {
+        let _ = writeln!(out, "{name} {value}");
+    } else {
+        let labels = labels
+            .iter()
+            .map(|(k, v)| format!("{k}=\"{}\"", escape_label(v)))
+            .collect::<Vec<_>>()
+            .join(",");
+        let _ = writeln!(out, "{name}{{{labels}}} {value}");
+    }
+}
+
+/// Escapes a label value,
+///
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[test]
+fn test_prometheus_text() {
+    let metrics = Metrics::default();
+    metrics.record_call("test/a", &Ok(()));
+    metrics.record_call::<()>("test/a", &Err(anyhow::anyhow!("failed")));
+    metrics.packet_queued();
+    metrics.packet_queued();
+    metrics.packet_received();
+
+    let snapshot = metrics.snapshot();
+    assert_eq!(
+        CallCounters {
+            calls: 2,
+            failures: 1
+        },
+        snapshot.calls("test/a")
+    );
+    assert_eq!(1, snapshot.packet_queue_depth);
+    assert_eq!(2, snapshot.packet_queue_depth_max);
+
+    let text = snapshot.to_prometheus();
+    assert!(text.contains("# TYPE loopio_engine_calls_total counter"));
+    assert!(text.contains("loopio_engine_calls_total{address=\"test/a\"} 2"));
+    assert!(text.contains("loopio_engine_call_failures_total{address=\"test/a\"} 1"));
+    assert!(text.contains("loopio_engine_packet_queue_depth 1"));
+}
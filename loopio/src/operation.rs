@@ -6,8 +6,12 @@ use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 use tracing::debug;
 use tracing::info;
+use tracing::Instrument;
 
 use crate::prelude::*;
+use crate::timeline::span_result;
+use crate::timeline::OPERATION_SPAN;
+use crate::timeline::TIMELINE_TARGET;
 
 /// Struct for a top-level node,
 ///
@@ -43,6 +47,27 @@ async fn run_operation(tc: &mut ThunkContext) -> anyhow::Result<()> {
     let mut init = tc.initialized::<Operation>().await;
     init.bind(tc.clone());
 
+    let span = tracing::info_span!(
+        target: TIMELINE_TARGET,
+        OPERATION_SPAN,
+        address = init.address(),
+        host = tracing::field::Empty,
+        result = tracing::field::Empty
+    );
+    if let Some(host) = tc.engine_handle().await.and_then(|eh| eh.host_name()) {
+        span.record("host", host.as_str());
+    }
+
+    let result = run_operation_extensions(tc, init)
+        .instrument(span.clone())
+        .await;
+    span.record("result", span_result(&result));
+    result
+}
+
+/// Calls each extension defined under the operation node,
+///
+async fn run_operation_extensions(tc: &mut ThunkContext, init: Operation) -> anyhow::Result<()> {
     info!(op = init.name, "Starting operation");
 
     if let Some(eh) = tc.engine_handle().await {
@@ -0,0 +1,601 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::VecDeque;
+use std::future::Future;
+
+use reality::prelude::*;
+use reality::CommaSeperatedStrings;
+use reality::SetIdentifiers;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::task::JoinSet;
+use tracing::debug;
+use tracing::error;
+use tracing::warn;
+
+use crate::prelude::Action;
+use crate::prelude::Ext;
+
+/// Node plugin that runs operations as a dependency graph,
+///
+/// Each step is an operation address that can declare the steps it needs. Steps that do not depend on each other
+/// run concurrently. If a step fails, every step downstream of it is skipped.
+///
+/// When called, a `PipelineReport` is put into transient storage.
+///
+/// ```md
+/// + .pipeline ci
+/// : .step fetch
+/// : .step lint
+/// |# needs = fetch
+/// : .step build
+/// |# needs = fetch
+/// : .step test
+/// |# needs = lint, build
+/// ```
+///
+#[derive(Reality, Default, Clone)]
+#[reality(call = execute_pipeline, plugin)]
+pub struct Pipeline {
+    /// Name of this pipeline,
+    ///
+    #[reality(ignore)]
+    pub name: String,
+    /// Tag of this pipeline,
+    ///
+    #[reality(ignore)]
+    pub tag: Option<String>,
+    /// Steps of the pipeline,
+    ///
+    /// Dependencies are declared w/ a `needs` decoration.
+    ///
+    #[reality(vec_of=Decorated<String>)]
+    pub step: Vec<Decorated<String>>,
+    /// Maximum number of steps that can run at the same time, (Default: unbounded)
+    ///
+    #[reality(option_of=usize)]
+    pub concurrency: Option<usize>,
+    /// Binding to an engine,
+    ///
+    #[reality(ignore)]
+    binding: Option<ThunkContext>,
+    /// Node resource key,
+    ///
+    #[reality(ignore)]
+    node: ResourceKey<reality::attributes::Node>,
+    /// Plugin resource key,
+    ///
+    #[reality(ignore)]
+    plugin: ResourceKey<reality::attributes::Attribute>,
+}
+
+async fn execute_pipeline(tc: &mut ThunkContext) -> anyhow::Result<()> {
+    let pipeline = Remote.create::<Pipeline>(tc).await;
+    let graph = pipeline.graph()?;
+
+    let eh = tc
+        .engine_handle()
+        .await
+        .ok_or(anyhow::anyhow!("Engine handle is not enabled"))?;
+
+    let report = match graph
+        .execute(pipeline.concurrency, move |step| {
+            let eh = eh.clone();
+            async move { eh.run(step).await.map(|_| ()) }
+        })
+        .await
+    {
+        Ok(report) => report,
+        Err(err) => {
+            if let Some(panicked) = err.downcast_ref::<StepPanicked>() {
+                tc.transient_mut().await.root().put(panicked.report.clone());
+            }
+            return Err(err);
+        }
+    };
+
+    tc.transient_mut().await.root().put(report.clone());
+
+    let failed = report.failed();
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "Pipeline {} failed at {}",
+            pipeline.name,
+            failed.join(", ")
+        ))
+    }
+}
+
+impl Pipeline {
+    /// Returns the dependency graph of this pipeline,
+    ///
+    /// **Note** The order of the graph is validated when the graph is executed, or w/ `PipelineGraph::topo_order`.
+    ///
+    pub fn graph(&self) -> anyhow::Result<PipelineGraph> {
+        let mut graph = PipelineGraph::default();
+
+        for step in self.step.iter() {
+            let name = step
+                .value()
+                .ok_or(anyhow::anyhow!("Pipeline {} has an empty step", self.name))?;
+
+            let needs = step
+                .property("needs")
+                .and_then(|n| CommaSeperatedStrings::from_str(&n).ok())
+                .map(|n| n.into_iter().collect())
+                .unwrap_or_default();
+
+            graph.add(name, needs)?;
+        }
+
+        Ok(graph)
+    }
+}
+
+impl SetIdentifiers for Pipeline {
+    fn set_identifiers(&mut self, name: &str, tag: Option<&String>) {
+        self.name = name.to_string();
+        self.tag = tag.cloned();
+    }
+}
+
+impl FromStr for Pipeline {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Pipeline {
+            name: s.to_string(),
+            ..Default::default()
+        })
+    }
+}
+
+impl std::fmt::Debug for Pipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("name", &self.name)
+            .field("tag", &self.tag)
+            .field("step", &self.step)
+            .field("concurrency", &self.concurrency)
+            .finish()
+    }
+}
+
+impl Action for Pipeline {
+    #[inline]
+    fn address(&self) -> String {
+        if let Some(tag) = self.tag.as_ref() {
+            format!("{}#{}", self.name, tag)
+        } else {
+            self.name.to_string()
+        }
+    }
+
+    #[inline]
+    fn context(&self) -> &ThunkContext {
+        self.binding.as_ref().expect("should be bound to an engine")
+    }
+
+    #[inline]
+    fn context_mut(&mut self) -> &mut ThunkContext {
+        self.binding.as_mut().expect("should be bound to an engine")
+    }
+
+    #[inline]
+    fn bind(&mut self, context: ThunkContext) {
+        self.binding = Some(context);
+    }
+
+    #[inline]
+    fn bind_node(&mut self, node: ResourceKey<reality::attributes::Node>) {
+        self.node = node;
+    }
+
+    #[inline]
+    fn node_rk(&self) -> ResourceKey<reality::attributes::Node> {
+        self.node
+    }
+
+    #[inline]
+    fn bind_plugin(&mut self, plugin: ResourceKey<reality::attributes::Attribute>) {
+        self.plugin = plugin;
+    }
+
+    #[inline]
+    fn plugin_rk(&self) -> ResourceKey<reality::attributes::Attribute> {
+        self.plugin
+    }
+}
+
+/// Dependency graph of pipeline steps,
+///
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct PipelineGraph {
+    /// Steps in the order they were declared,
+    ///
+    steps: Vec<String>,
+    /// Map of steps to the steps they need,
+    ///
+    needs: BTreeMap<String, Vec<String>>,
+}
+
+impl PipelineGraph {
+    /// Adds a step to the graph,
+    ///
+    pub fn add(&mut self, step: impl Into<String>, needs: Vec<String>) -> anyhow::Result<()> {
+        let step = step.into();
+        if self.needs.contains_key(&step) {
+            return Err(anyhow::anyhow!("Step {step} is declared more than once"));
+        }
+
+        self.steps.push(step.clone());
+        self.needs.insert(step, needs);
+        Ok(())
+    }
+
+    /// Returns the steps a step needs,
+    ///
+    pub fn needs(&self, step: impl AsRef<str>) -> &[String] {
+        self.needs
+            .get(step.as_ref())
+            .map(|n| n.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns the steps in an order where each step comes after the steps it needs,
+    ///
+    /// Returns an error if a step needs an undeclared step or if the graph has a cycle.
+    ///
+    pub fn topo_order(&self) -> anyhow::Result<Vec<String>> {
+        let mut remaining = BTreeMap::new();
+        for step in self.steps.iter() {
+            for need in self.needs(step) {
+                if !self.needs.contains_key(need) {
+                    return Err(anyhow::anyhow!(
+                        "Step {step} needs {need}, but {need} is not a step"
+                    ));
+                }
+            }
+            remaining.insert(step.as_str(), self.needs(step).len());
+        }
+
+        let mut ready = self
+            .steps
+            .iter()
+            .filter(|s| remaining[s.as_str()] == 0)
+            .map(|s| s.as_str())
+            .collect::<VecDeque<_>>();
+
+        let mut order = vec![];
+        while let Some(next) = ready.pop_front() {
+            order.push(next.to_string());
+            for dependent in self.dependents(next) {
+                if let Some(count) = remaining.get_mut(dependent) {
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.push_back(dependent);
+                    }
+                }
+            }
+        }
+
+        if order.len() != self.steps.len() {
+            let cycle = self
+                .steps
+                .iter()
+                .filter(|s| !order.contains(s))
+                .cloned()
+                .collect::<Vec<_>>();
+            return Err(anyhow::anyhow!(
+                "Pipeline has a dependency cycle between {}",
+                cycle.join(", ")
+            ));
+        }
+
+        Ok(order)
+    }
+
+    /// Returns the steps that directly need a step, in declaration order,
+    ///
+    fn dependents<'a>(&'a self, step: &str) -> impl Iterator<Item = &'a str> + 'a {
+        let step = step.to_string();
+        self.steps
+            .iter()
+            .filter(move |s| self.needs(s).contains(&step))
+            .map(|s| s.as_str())
+    }
+
+    /// Executes the graph, calling run for each step once all of the steps it needs have succeeded,
+    ///
+    /// If a step fails, all steps downstream of it are skipped.
+    ///
+    /// Returns an error if the graph is not valid, or a `StepPanicked` error w/ the report if a step panicked or was
+    /// cancelled.
+    ///
+    pub async fn execute<F, Fut>(
+        &self,
+        concurrency: Option<usize>,
+        run: F,
+    ) -> anyhow::Result<PipelineReport>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        let order = self.topo_order()?;
+
+        let concurrency = concurrency.unwrap_or(usize::MAX).max(1);
+        let mut report = PipelineReport::default();
+        let mut remaining = self
+            .steps
+            .iter()
+            .map(|s| (s.as_str(), self.needs(s).len()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut ready = order
+            .iter()
+            .filter(|s| remaining[s.as_str()] == 0)
+            .map(|s| s.as_str())
+            .collect::<VecDeque<_>>();
+
+        let mut running = JoinSet::new();
+        let mut tasks = BTreeMap::new();
+        let mut panicked = vec![];
+        loop {
+            while running.len() < concurrency {
+                if let Some(step) = ready.pop_front() {
+                    debug!(step, "Starting pipeline step");
+                    let name = step.to_string();
+                    let job = run(name.clone());
+                    let handle = running.spawn(async move { (name, job.await) });
+                    tasks.insert(handle.id(), step.to_string());
+                } else {
+                    break;
+                }
+            }
+
+            let Some(joined) = running.join_next().await else {
+                break;
+            };
+
+            let (step, result) = match joined {
+                Ok((step, result)) => (step, result),
+                Err(err) => {
+                    let Some(step) = tasks.remove(&err.id()) else {
+                        error!("Pipeline step could not be joined, {err}");
+                        continue;
+                    };
+                    error!(step, "Pipeline step could not be joined, {err}");
+                    panicked.push(step.clone());
+                    (step, Err(anyhow::anyhow!("Step panicked, {err}")))
+                }
+            };
+
+            match result {
+                Ok(()) => {
+                    report.insert(&step, StepStatus::Succeeded);
+                    for dependent in self.dependents(&step) {
+                        if let Some(count) = remaining.get_mut(dependent) {
+                            *count -= 1;
+                            if *count == 0 && !report.steps.contains_key(dependent) {
+                                ready.push_back(dependent);
+                            }
+                        }
+                    }
+                }
+                Err(err) => {
+                    error!(step, "Pipeline step failed, {err}");
+                    report.insert(&step, StepStatus::Failed(err.to_string()));
+
+                    for skipped in self.downstream(&step) {
+                        if !report.steps.contains_key(&skipped) {
+                            warn!(step = skipped, upstream = step, "Skipping pipeline step");
+                            report.insert(&skipped, StepStatus::Skipped);
+                        }
+                    }
+                }
+            }
+        }
+
+        if panicked.is_empty() {
+            Ok(report)
+        } else {
+            Err(StepPanicked {
+                steps: panicked,
+                report,
+            }
+            .into())
+        }
+    }
+
+    /// Returns every step that transitively needs a step,
+    ///
+    fn downstream(&self, step: &str) -> BTreeSet<String> {
+        let mut downstream = BTreeSet::new();
+        let mut queue = VecDeque::from([step.to_string()]);
+
+        while let Some(next) = queue.pop_front() {
+            for dependent in self.dependents(&next) {
+                if downstream.insert(dependent.to_string()) {
+                    queue.push_back(dependent.to_string());
+                }
+            }
+        }
+
+        downstream
+    }
+}
+
+/// Error returned when a pipeline step panicked or was cancelled,
+///
+#[derive(Debug, thiserror::Error)]
+#[error("Pipeline steps panicked, {}", steps.join(", "))]
+pub struct StepPanicked {
+    /// Steps that panicked,
+    ///
+    pub steps: Vec<String>,
+    /// Report of the pipeline execution,
+    ///
+    pub report: PipelineReport,
+}
+
+/// Final status of a pipeline step,
+///
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum StepStatus {
+    /// Step completed successfully,
+    ///
+    Succeeded,
+    /// Step returned an error,
+    ///
+    Failed(String),
+    /// Step was skipped because a step upstream failed,
+    ///
+    Skipped,
+}
+
+/// Report of a pipeline execution,
+///
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct PipelineReport {
+    /// Status of each step,
+    ///
+    pub steps: BTreeMap<String, StepStatus>,
+    /// Steps in the order they completed,
+    ///
+    pub order: Vec<String>,
+}
+
+impl PipelineReport {
+    /// Returns the status of a step,
+    ///
+    pub fn status(&self, step: impl AsRef<str>) -> Option<&StepStatus> {
+        self.steps.get(step.as_ref())
+    }
+
+    /// Returns the steps that failed,
+    ///
+    pub fn failed(&self) -> Vec<String> {
+        self.steps
+            .iter()
+            .filter(|(_, s)| matches!(s, StepStatus::Failed(_)))
+            .map(|(k, _)| k.to_string())
+            .collect()
+    }
+
+    /// Inserts the status of a step,
+    ///
+    fn insert(&mut self, step: &str, status: StepStatus) {
+        if status != StepStatus::Skipped {
+            self.order.push(step.to_string());
+        }
+        self.steps.insert(step.to_string(), status);
+    }
+}
+
+#[test]
+fn test_pipeline_graph() {
+    let mut graph = PipelineGraph::default();
+    graph
+        .add("test", vec!["lint".into(), "build".into()])
+        .unwrap();
+    graph.add("fetch", vec![]).unwrap();
+    graph.add("lint", vec!["fetch".into()]).unwrap();
+    graph.add("build", vec!["fetch".into()]).unwrap();
+    assert!(graph.add("build", vec![]).is_err());
+
+    assert_eq!(
+        vec!["fetch", "lint", "build", "test"],
+        graph.topo_order().unwrap()
+    );
+
+    let mut cyclic = graph.clone();
+    cyclic.add("a", vec!["b".into()]).unwrap();
+    cyclic.add("b", vec!["a".into()]).unwrap();
+    let err = cyclic.topo_order().unwrap_err().to_string();
+    assert!(err.contains("cycle between a, b"), "{err}");
+
+    let mut missing = PipelineGraph::default();
+    missing.add("a", vec!["missing".into()]).unwrap();
+    assert!(missing.topo_order().is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_pipeline_execute() {
+    let mut graph = PipelineGraph::default();
+    graph.add("fetch", vec![]).unwrap();
+    graph.add("lint", vec!["fetch".into()]).unwrap();
+    graph.add("build", vec!["fetch".into()]).unwrap();
+    graph
+        .add("test", vec!["lint".into(), "build".into()])
+        .unwrap();
+    graph.add("publish", vec!["test".into()]).unwrap();
+    graph.add("docs", vec![]).unwrap();
+
+    let run = |step: String| async move {
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        if step == "build" {
+            Err(anyhow::anyhow!("build failed"))
+        } else {
+            Ok(())
+        }
+    };
+
+    // Independent branches run concurrently
+    let started = tokio::time::Instant::now();
+    let report = graph.execute(None, run).await.unwrap();
+    assert_eq!(2, started.elapsed().as_secs());
+
+    assert_eq!(Some(&StepStatus::Succeeded), report.status("fetch"));
+    assert_eq!(Some(&StepStatus::Succeeded), report.status("lint"));
+    assert_eq!(Some(&StepStatus::Succeeded), report.status("docs"));
+    assert_eq!(
+        Some(&StepStatus::Failed(String::from("build failed"))),
+        report.status("build")
+    );
+    assert_eq!(Some(&StepStatus::Skipped), report.status("test"));
+    assert_eq!(Some(&StepStatus::Skipped), report.status("publish"));
+    assert_eq!(vec![String::from("build")], report.failed());
+
+    // Concurrency limits how many steps run at once
+    let started = tokio::time::Instant::now();
+    graph.execute(Some(1), run).await.unwrap();
+    assert_eq!(4, started.elapsed().as_secs());
+}
+
+#[tokio::test]
+async fn test_pipeline_execute_panic() {
+    let mut graph = PipelineGraph::default();
+    graph.add("fetch", vec![]).unwrap();
+    graph.add("build", vec!["fetch".into()]).unwrap();
+    graph.add("test", vec!["build".into()]).unwrap();
+    graph.add("docs", vec![]).unwrap();
+
+    let run = |step: String| async move {
+        if step == "build" {
+            panic!("build panicked");
+        }
+        Ok(())
+    };
+
+    let err = graph.execute(None, run).await.unwrap_err();
+    let panicked = err
+        .downcast_ref::<StepPanicked>()
+        .expect("should be a panicked step error");
+    assert_eq!(vec![String::from("build")], panicked.steps);
+
+    let report = &panicked.report;
+    assert_eq!(Some(&StepStatus::Succeeded), report.status("fetch"));
+    assert_eq!(Some(&StepStatus::Succeeded), report.status("docs"));
+    assert!(matches!(
+        report.status("build"),
+        Some(StepStatus::Failed(_))
+    ));
+    assert_eq!(Some(&StepStatus::Skipped), report.status("test"));
+
+    let mut cyclic = PipelineGraph::default();
+    cyclic.add("a", vec!["b".into()]).unwrap();
+    cyclic.add("b", vec!["a".into()]).unwrap();
+    assert!(cyclic.execute(None, run).await.is_err());
+}
@@ -19,11 +19,22 @@ pub use crate::engine::EngineHandle;
 pub use crate::engine::Published;
 pub use crate::ext::*;
 pub use crate::foreground::ForegroundEngine;
+pub use crate::graph::WorkspaceGraph;
+pub use crate::harness::TestHarness;
+pub use crate::harness::TestRun;
 pub use crate::host::Host;
+pub use crate::metrics::Metrics;
+pub use crate::metrics::MetricsSnapshot;
 pub use crate::operation::Operation;
+pub use crate::pipeline::Pipeline;
+pub use crate::schedule::Schedule;
 pub use crate::sequence::Sequence;
+pub use crate::timeline::Timeline;
+pub use crate::timeline::TimelineLayer;
+pub use crate::timeline::TimelineSpan;
 pub use crate::work::WorkState;
 
+pub use reality;
 pub use reality::prelude::*;
 
 /// Engine build middleware,
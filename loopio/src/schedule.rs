@@ -0,0 +1,661 @@
+use std::future::Future;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::Utc;
+use reality::prelude::*;
+use reality::SetIdentifiers;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::runtime::Handle;
+use tokio::select;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+use tracing::error;
+use tracing::info;
+
+use crate::prelude::Action;
+use crate::prelude::Address;
+use crate::prelude::Ext;
+
+/// Node plugin that runs an operation or sequence on a schedule,
+///
+/// Schedules are driven by the engine after `default_startup`,
+///
+/// ```md
+/// + .schedule nightly
+/// : .cron     0 2 * * *
+/// : .run      build
+///
+/// + .schedule heartbeat
+/// : .every    5m
+/// : .run      ping
+/// : .overlap  queue
+/// ```
+///
+#[derive(Reality, Default, Clone)]
+#[reality(call = execute_schedule, plugin)]
+pub struct Schedule {
+    /// Name of this schedule,
+    ///
+    #[reality(ignore)]
+    pub name: String,
+    /// Tag of this schedule,
+    ///
+    #[reality(ignore)]
+    pub tag: Option<String>,
+    /// Cron expression w/ 5 (or 6 w/ seconds) fields,
+    ///
+    #[reality(option_of=String)]
+    pub cron: Option<String>,
+    /// Fixed interval between runs, (Ex. 30s, 5m, 1h)
+    ///
+    #[reality(option_of=String)]
+    pub every: Option<String>,
+    /// Address of the operation or sequence to run,
+    ///
+    #[reality(option_of=Address)]
+    pub run: Option<Address>,
+    /// Policy to apply when a run is due while the previous run is still in progress,
+    ///
+    #[reality(option_of=OverlapPolicy)]
+    pub overlap: Option<OverlapPolicy>,
+    /// Binding to an engine,
+    ///
+    #[reality(ignore)]
+    binding: Option<ThunkContext>,
+    /// Node resource key,
+    ///
+    #[reality(ignore)]
+    node: ResourceKey<reality::attributes::Node>,
+    /// Plugin resource key,
+    ///
+    #[reality(ignore)]
+    plugin: ResourceKey<reality::attributes::Attribute>,
+}
+
+/// Calling a schedule directly runs its target immediately,
+///
+async fn execute_schedule(tc: &mut ThunkContext) -> anyhow::Result<()> {
+    let schedule = Remote.create::<Schedule>(tc).await;
+
+    let target = schedule
+        .run
+        .as_ref()
+        .ok_or(anyhow::anyhow!("Schedule does not have a target to run"))?;
+
+    if let Some(eh) = tc.engine_handle().await {
+        eh.run(target.to_string()).await?;
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("Engine handle is not enabled"))
+    }
+}
+
+impl Schedule {
+    /// Returns the trigger configured for this schedule,
+    ///
+    pub fn trigger(&self) -> anyhow::Result<Trigger> {
+        match (self.cron.as_ref(), self.every.as_ref()) {
+            (Some(cron), None) => cron.parse(),
+            (None, Some(every)) => Ok(Trigger::Every(humantime::parse_duration(every).map_err(
+                |err| {
+                    anyhow::anyhow!(
+                        "Invalid interval `{every}` for schedule {}, {err}",
+                        self.name
+                    )
+                },
+            )?)),
+            (Some(_), Some(_)) => Err(anyhow::anyhow!(
+                "Schedule {} can only have one of `cron` or `every`",
+                self.name
+            )),
+            (None, None) => Err(anyhow::anyhow!(
+                "Schedule {} requires either `cron` or `every`",
+                self.name
+            )),
+        }
+    }
+
+    /// Returns a scheduler for this schedule,
+    ///
+    pub fn scheduler(&self) -> anyhow::Result<Scheduler> {
+        let target = self.run.clone().ok_or(anyhow::anyhow!(
+            "Schedule {} does not have a target to run",
+            self.name
+        ))?;
+
+        Ok(Scheduler::new(
+            target,
+            self.trigger()?,
+            self.overlap.unwrap_or_default(),
+        ))
+    }
+}
+
+impl SetIdentifiers for Schedule {
+    fn set_identifiers(&mut self, name: &str, tag: Option<&String>) {
+        self.name = name.to_string();
+        self.tag = tag.cloned();
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Schedule {
+            name: s.to_string(),
+            ..Default::default()
+        })
+    }
+}
+
+impl std::fmt::Debug for Schedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Schedule")
+            .field("name", &self.name)
+            .field("tag", &self.tag)
+            .field("cron", &self.cron)
+            .field("every", &self.every)
+            .field("run", &self.run)
+            .field("overlap", &self.overlap)
+            .finish()
+    }
+}
+
+impl Action for Schedule {
+    #[inline]
+    fn address(&self) -> String {
+        if let Some(tag) = self.tag.as_ref() {
+            format!("{}#{}", self.name, tag)
+        } else {
+            self.name.to_string()
+        }
+    }
+
+    #[inline]
+    fn context(&self) -> &ThunkContext {
+        self.binding.as_ref().expect("should be bound to an engine")
+    }
+
+    #[inline]
+    fn context_mut(&mut self) -> &mut ThunkContext {
+        self.binding.as_mut().expect("should be bound to an engine")
+    }
+
+    #[inline]
+    fn bind(&mut self, context: ThunkContext) {
+        self.binding = Some(context);
+    }
+
+    #[inline]
+    fn bind_node(&mut self, node: ResourceKey<reality::attributes::Node>) {
+        self.node = node;
+    }
+
+    #[inline]
+    fn node_rk(&self) -> ResourceKey<reality::attributes::Node> {
+        self.node
+    }
+
+    #[inline]
+    fn bind_plugin(&mut self, plugin: ResourceKey<reality::attributes::Attribute>) {
+        self.plugin = plugin;
+    }
+
+    #[inline]
+    fn plugin_rk(&self) -> ResourceKey<reality::attributes::Attribute> {
+        self.plugin
+    }
+}
+
+/// Policy applied when a run is due while the previous run is still in progress,
+///
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    /// The run that is due is skipped,
+    ///
+    #[default]
+    Skip,
+    /// The run that is due is queued and starts after the previous runs complete,
+    ///
+    Queue,
+}
+
+impl FromStr for OverlapPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "skip" => Ok(OverlapPolicy::Skip),
+            "queue" => Ok(OverlapPolicy::Queue),
+            _ => Err(anyhow::anyhow!(
+                "Unknown overlap policy `{s}`, expected `skip` or `queue`"
+            )),
+        }
+    }
+}
+
+/// Determines when a schedule is due,
+///
+#[derive(Debug, Clone)]
+pub enum Trigger {
+    /// Due when the cron expression matches,
+    ///
+    Cron(Box<croner::Cron>),
+    /// Due at a fixed interval from when the scheduler started,
+    ///
+    Every(Duration),
+}
+
+impl Trigger {
+    /// Returns the next time this trigger is due strictly after now,
+    ///
+    /// **Note** Intervals are aligned to origin so that runs do not drift.
+    ///
+    pub fn next_after(
+        &self,
+        origin: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<DateTime<Utc>> {
+        match self {
+            Trigger::Cron(cron) => Ok(cron.find_next_occurrence(&now, false)?),
+            Trigger::Every(every) if every.is_zero() => {
+                Err(anyhow::anyhow!("Interval must be greater than zero"))
+            }
+            Trigger::Every(every) => {
+                let every = chrono::Duration::from_std(*every)?;
+                let elapsed = (now - origin).max(chrono::Duration::zero());
+                let intervals = elapsed.num_milliseconds() / every.num_milliseconds().max(1) + 1;
+                let intervals = i32::try_from(intervals).map_err(|_| {
+                    anyhow::anyhow!("Next run is too far from the start of the schedule")
+                })?;
+                Ok(origin + every * intervals)
+            }
+        }
+    }
+}
+
+impl FromStr for Trigger {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cron = croner::Cron::new(s.trim())
+            .with_seconds_optional()
+            .parse()
+            .map_err(|err| anyhow::anyhow!("Invalid cron expression `{s}`, {err}"))?;
+
+        Ok(Trigger::Cron(Box::new(cron)))
+    }
+}
+
+/// Status of a schedule,
+///
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleStatus {
+    /// Next time the schedule is due,
+    ///
+    pub next_run: Option<DateTime<Utc>>,
+    /// Last time a run was started,
+    ///
+    pub last_run: Option<DateTime<Utc>>,
+    /// Number of runs that completed,
+    ///
+    pub runs: u64,
+    /// Number of runs that returned an error,
+    ///
+    pub failures: u64,
+    /// Number of runs skipped due to overlap,
+    ///
+    pub skipped: u64,
+    /// Number of runs waiting in the queue,
+    ///
+    pub pending: u64,
+}
+
+/// Shared handle to the status of a schedule,
+///
+#[derive(Default, Debug, Clone)]
+pub struct ScheduleState(Arc<Mutex<ScheduleStatus>>);
+
+impl ScheduleState {
+    /// Returns a copy of the current status,
+    ///
+    pub fn status(&self) -> ScheduleStatus {
+        self.0.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    /// Updates the current status,
+    ///
+    fn update(&self, update: impl FnOnce(&mut ScheduleStatus)) {
+        if let Ok(mut status) = self.0.lock() {
+            update(&mut status);
+        }
+    }
+
+    /// Records the result of a run,
+    ///
+    fn record(&self, result: &anyhow::Result<()>) {
+        self.update(|s| {
+            s.runs += 1;
+            if result.is_err() {
+                s.failures += 1;
+            }
+        });
+    }
+}
+
+/// Drives a target on a trigger,
+///
+pub struct Scheduler {
+    /// Address of the target to run,
+    ///
+    pub target: Address,
+    /// Trigger that determines when the target is run,
+    ///
+    pub trigger: Trigger,
+    /// Overlap policy,
+    ///
+    pub overlap: OverlapPolicy,
+    /// Wall-clock time the scheduler is started at,
+    ///
+    origin: Option<DateTime<Utc>>,
+    /// Runtime runs are spawned on,
+    ///
+    runtime: Option<Handle>,
+    /// Status of this scheduler,
+    ///
+    state: ScheduleState,
+}
+
+impl Scheduler {
+    /// Creates a new scheduler,
+    ///
+    pub fn new(target: Address, trigger: Trigger, overlap: OverlapPolicy) -> Self {
+        Self {
+            target,
+            trigger,
+            overlap,
+            origin: None,
+            runtime: None,
+            state: ScheduleState::default(),
+        }
+    }
+
+    /// Sets the wall-clock time the scheduler considers itself started at,
+    ///
+    /// By default the current time is used when the scheduler is started. Elapsed time is measured w/ tokio's clock
+    /// relative to origin, which allows the scheduler to be tested w/ a paused clock.
+    ///
+    pub fn starting_at(mut self, origin: DateTime<Utc>) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    /// Sets the runtime runs are spawned on,
+    ///
+    /// By default runs are spawned on the runtime driving the scheduler.
+    ///
+    pub fn on_runtime(mut self, runtime: Handle) -> Self {
+        self.runtime = Some(runtime);
+        self
+    }
+
+    /// Returns a handle to the status of this scheduler,
+    ///
+    pub fn state(&self) -> ScheduleState {
+        self.state.clone()
+    }
+
+    /// Drives the scheduler until cancelled, calling run each time the trigger is due,
+    ///
+    /// **Note** Runs that are in progress when the scheduler is cancelled are also cancelled.
+    ///
+    pub async fn drive<F, Fut>(self, cancel: CancellationToken, run: F) -> anyhow::Result<()>
+    where
+        F: Fn(Address) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        let origin = self.origin.unwrap_or_else(Utc::now);
+        let runtime = self.runtime.clone().unwrap_or_else(Handle::current);
+        let started = Instant::now();
+        let run = Arc::new(run);
+        let running = Arc::new(AtomicBool::new(false));
+
+        // Queued runs are handled one at a time in the order they were due
+        let (queue, mut queued) = tokio::sync::mpsc::unbounded_channel::<()>();
+        let worker = {
+            let run = run.clone();
+            let state = self.state.clone();
+            let target = self.target.clone();
+            let cancel = cancel.child_token();
+            runtime.spawn(async move {
+                while queued.recv().await.is_some() {
+                    state.update(|s| s.pending = s.pending.saturating_sub(1));
+                    select! {
+                        result = run(target.clone()) => state.record(&result),
+                        _ = cancel.cancelled() => break,
+                    }
+                }
+            })
+        };
+
+        loop {
+            let now = origin + chrono::Duration::from_std(started.elapsed())?;
+            let next = self.trigger.next_after(origin, now)?;
+            self.state.update(|s| s.next_run = Some(next));
+            debug!(
+                target = self.target.to_string(),
+                next = next.to_rfc3339(),
+                "Next run"
+            );
+
+            let deadline = started + (next - origin).to_std()?;
+            select! {
+                _ = tokio::time::sleep_until(deadline) => {}
+                _ = cancel.cancelled() => {
+                    break;
+                }
+            }
+
+            self.state.update(|s| s.last_run = Some(next));
+            match self.overlap {
+                OverlapPolicy::Skip if running.swap(true, Ordering::SeqCst) => {
+                    info!(
+                        target = self.target.to_string(),
+                        "Previous run in progress, skipping"
+                    );
+                    self.state.update(|s| s.skipped += 1);
+                }
+                OverlapPolicy::Skip => {
+                    let run = run.clone();
+                    let state = self.state.clone();
+                    let running = running.clone();
+                    let target = self.target.clone();
+                    let cancel = cancel.child_token();
+                    runtime.spawn(async move {
+                        select! {
+                            result = run(target) => {
+                                if let Err(err) = result.as_ref() {
+                                    error!("Scheduled run failed, {err}");
+                                }
+                                state.record(&result);
+                            }
+                            _ = cancel.cancelled() => {
+                                debug!("Scheduler was cancelled, cancelling run in progress");
+                            }
+                        }
+                        running.store(false, Ordering::SeqCst);
+                    });
+                }
+                OverlapPolicy::Queue => {
+                    self.state.update(|s| s.pending += 1);
+                    queue.send(())?;
+                }
+            }
+        }
+
+        worker.abort();
+        Ok(())
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_scheduler_every() {
+    let runs = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let scheduler = Scheduler::new(
+        Address::from_str("test-scheduler-every").unwrap(),
+        Trigger::Every(Duration::from_secs(300)),
+        OverlapPolicy::Skip,
+    );
+    let state = scheduler.state();
+    let cancel = CancellationToken::new();
+
+    let _runs = runs.clone();
+    let driver = tokio::spawn(scheduler.drive(cancel.clone(), move |_| {
+        let runs = _runs.clone();
+        async move {
+            runs.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }));
+
+    tokio::time::sleep(Duration::from_secs(16 * 60)).await;
+    assert_eq!(3, runs.load(Ordering::SeqCst));
+    assert_eq!(3, state.status().runs);
+    assert!(state.status().next_run.is_some());
+
+    cancel.cancel();
+    driver.await.unwrap().unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_scheduler_overlap() {
+    let target = Address::from_str("test-scheduler-overlap").unwrap();
+
+    // Each run takes 7 minutes, so every other run overlaps w/ a 5 minute interval
+    let run = |_| async {
+        tokio::time::sleep(Duration::from_secs(7 * 60)).await;
+        Ok(())
+    };
+
+    let skip = Scheduler::new(
+        target.clone(),
+        Trigger::Every(Duration::from_secs(300)),
+        OverlapPolicy::Skip,
+    );
+    let queue = Scheduler::new(
+        target,
+        Trigger::Every(Duration::from_secs(300)),
+        OverlapPolicy::Queue,
+    );
+    let (skip_state, queue_state) = (skip.state(), queue.state());
+
+    let cancel = CancellationToken::new();
+    tokio::spawn(skip.drive(cancel.clone(), run));
+    tokio::spawn(queue.drive(cancel.clone(), run));
+
+    // Due at 5, 10, 15, 20
+    tokio::time::sleep(Duration::from_secs(21 * 60)).await;
+    cancel.cancel();
+
+    // Skip: starts at 5 (done 12), skips 10, starts at 15 (done 22), skips 20
+    let skip = skip_state.status();
+    assert_eq!(1, skip.runs);
+    assert_eq!(2, skip.skipped);
+
+    // Queue: starts at 5 (done 12), then 12 (done 19), then 19 (running), one pending
+    let queue = queue_state.status();
+    assert_eq!(2, queue.runs);
+    assert_eq!(0, queue.skipped);
+    assert_eq!(1, queue.pending);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_scheduler_cancel() {
+    let runs = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let scheduler = Scheduler::new(
+        Address::from_str("test-scheduler-cancel").unwrap(),
+        Trigger::Every(Duration::from_secs(300)),
+        OverlapPolicy::Skip,
+    );
+    let state = scheduler.state();
+    let cancel = CancellationToken::new();
+
+    let _runs = runs.clone();
+    let driver = tokio::spawn(scheduler.drive(cancel.clone(), move |_| {
+        let runs = _runs.clone();
+        async move {
+            tokio::time::sleep(Duration::from_secs(10 * 60)).await;
+            runs.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }));
+
+    // The run started at 5 is still in progress when the scheduler is cancelled
+    tokio::time::sleep(Duration::from_secs(6 * 60)).await;
+    cancel.cancel();
+    driver.await.unwrap().unwrap();
+
+    tokio::time::sleep(Duration::from_secs(20 * 60)).await;
+    assert_eq!(0, runs.load(Ordering::SeqCst));
+    assert_eq!(0, state.status().runs);
+}
+
+#[test]
+fn test_trigger_every_overflow() {
+    let origin = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    let now = DateTime::parse_from_rfc3339("2124-01-01T00:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+
+    let trigger = Trigger::Every(Duration::from_millis(1));
+    assert!(trigger.next_after(origin, now).is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_scheduler_cron() {
+    let origin = DateTime::parse_from_rfc3339("2024-01-01T01:59:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+
+    let trigger = Trigger::from_str("0 2 * * *").unwrap();
+    assert_eq!(
+        DateTime::parse_from_rfc3339("2024-01-01T02:00:00Z").unwrap(),
+        trigger.next_after(origin, origin).unwrap()
+    );
+    assert!(Trigger::from_str("not a cron").is_err());
+
+    let scheduler = Scheduler::new(
+        Address::from_str("test-scheduler-cron").unwrap(),
+        trigger,
+        OverlapPolicy::Skip,
+    )
+    .starting_at(origin);
+    let state = scheduler.state();
+
+    let cancel = CancellationToken::new();
+    tokio::spawn(scheduler.drive(cancel.clone(), |_| async { Ok(()) }));
+
+    tokio::time::sleep(Duration::from_secs(2 * 60)).await;
+    cancel.cancel();
+
+    let status = state.status();
+    assert_eq!(1, status.runs);
+    assert_eq!(
+        Some(
+            DateTime::parse_from_rfc3339("2024-01-02T02:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc)
+        ),
+        status.next_run
+    );
+}
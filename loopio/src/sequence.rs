@@ -9,7 +9,11 @@ use tokio::task::JoinHandle;
 use tokio::task::JoinSet;
 use tracing::error;
 use tracing::trace;
+use tracing::Instrument;
 
+use crate::timeline::span_result;
+use crate::timeline::SEQUENCE_STEP_SPAN;
+use crate::timeline::TIMELINE_TARGET;
 use crate::{ext::Ext, prelude::Action};
 
 /// Struct containing steps of a sequence of operations,
@@ -132,6 +136,14 @@ impl Sequence {
         }
     }
 
+    /// Returns every step of the sequence in order w/o advancing the current step list,
+    ///
+    /// Each item contains the operations of a step that execute all at once.
+    ///
+    pub fn steps(&self) -> Vec<Vec<Step>> {
+        StepList(self.step.clone()).collect()
+    }
+
     /// Returns the next operation to run,
     ///
     /// If None is returned, it signals the end of the sequence.
@@ -184,39 +196,19 @@ impl std::future::Future for Sequence {
             return Poll::Ready(Err(anyhow::anyhow!("Shutting down")));
         }
 
-        fn address(step: Step) -> String {
-            trace!("{:?}", step);
-            step.0
-        }
-
         match (self.binding.clone(), self.current.take()) {
             (Some(binding), None) => match self.next_step() {
                 Some(step) => {
                     trace!("Starting sequence");
-                    let _binding = binding.clone();
-                    self.current = Some(binding.node.clone().runtime.unwrap().spawn(async move {
-                        let mut set = JoinSet::new();
-
-                        let _binding = _binding.clone();
-                        for _step in step {
-                            let _binding = _binding.clone();
-                            set.spawn(async move {
-                                trace!("Starting {:?}", _step);
-                                if let Some(handle) = _binding.engine_handle().await {
-                                    handle.run(address(_step)).await
-                                } else {
-                                    Err(anyhow::anyhow!("Engine handle is not enabled"))
-                                }
-                            });
-                        }
-
-                        let mut last = Err(anyhow::anyhow!("Not started"));
-                        while let Some(result) = set.join_next().await {
-                            last = result?;
-                        }
-
-                        last
-                    }));
+                    let name = self.address();
+                    self.current = Some(
+                        binding
+                            .node
+                            .clone()
+                            .runtime
+                            .unwrap()
+                            .spawn(run_step(binding, name, step).in_current_span()),
+                    );
                 }
                 None => {
                     trace!("Done");
@@ -227,31 +219,15 @@ impl std::future::Future for Sequence {
                 Poll::Ready(Ok(result)) => match self.next_step() {
                     Some(next) => {
                         trace!("Starting sequence");
-                        let _binding = binding.clone();
-                        self.current =
-                            Some(binding.node.clone().runtime.unwrap().spawn(async move {
-                                let mut set = JoinSet::new();
-
-                                let _binding = _binding.clone();
-                                for _step in next {
-                                    let _binding = _binding.clone();
-                                    set.spawn(async move {
-                                        trace!("Starting {:?}", _step);
-                                        if let Some(handle) = _binding.engine_handle().await {
-                                            handle.run(address(_step)).await
-                                        } else {
-                                            Err(anyhow::anyhow!("Engine handle is not enabled"))
-                                        }
-                                    });
-                                }
-
-                                let mut last = Err(anyhow::anyhow!("Not started"));
-                                while let Some(result) = set.join_next().await {
-                                    last = result?;
-                                }
-
-                                last
-                            }));
+                        let name = self.address();
+                        self.current = Some(
+                            binding
+                                .node
+                                .clone()
+                                .runtime
+                                .unwrap()
+                                .spawn(run_step(binding, name, next).in_current_span()),
+                        );
                     }
                     None => return Poll::Ready(result),
                 },
@@ -276,6 +252,53 @@ impl std::future::Future for Sequence {
     }
 }
 
+/// Runs all operations in a step concurrently and returns the result of the last operation to complete,
+///
+async fn run_step(
+    binding: ThunkContext,
+    sequence: String,
+    step: Vec<Step>,
+) -> anyhow::Result<ThunkContext> {
+    let span = tracing::info_span!(
+        target: TIMELINE_TARGET,
+        SEQUENCE_STEP_SPAN,
+        sequence,
+        step = step.iter().map(|s| s.0.as_str()).collect::<Vec<_>>().join(", "),
+        result = tracing::field::Empty
+    );
+
+    let result = async move {
+        let mut set = JoinSet::new();
+
+        for _step in step {
+            let _binding = binding.clone();
+            set.spawn(
+                async move {
+                    trace!("Starting {:?}", _step);
+                    if let Some(handle) = _binding.engine_handle().await {
+                        handle.run(_step.0).await
+                    } else {
+                        Err(anyhow::anyhow!("Engine handle is not enabled"))
+                    }
+                }
+                .in_current_span(),
+            );
+        }
+
+        let mut last = Err(anyhow::anyhow!("Not started"));
+        while let Some(result) = set.join_next().await {
+            last = result?;
+        }
+
+        last
+    }
+    .instrument(span.clone())
+    .await;
+
+    span.record("result", span_result(&result));
+    result
+}
+
 /// A step is an operation address to execute on an engine,
 ///
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
@@ -0,0 +1,460 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use tracing::field::Field;
+use tracing::field::Visit;
+use tracing::span::Attributes;
+use tracing::span::Id;
+use tracing::span::Record;
+use tracing::Subscriber;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Target used by spans that are recorded by the timeline,
+///
+pub const TIMELINE_TARGET: &str = "loopio::timeline";
+
+/// Name of the span entered when an engine handle calls an address,
+///
+pub const ENGINE_CALL_SPAN: &str = "engine.call";
+
+/// Name of the span entered when a sequence runs a step,
+///
+pub const SEQUENCE_STEP_SPAN: &str = "sequence.step";
+
+/// Name of the span entered when an operation runs,
+///
+pub const OPERATION_SPAN: &str = "operation";
+
+/// Span that was recorded by a timeline,
+///
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TimelineSpan {
+    /// Id of this span,
+    ///
+    pub id: u64,
+    /// Id of the parent span if the parent is also recorded by the timeline,
+    ///
+    pub parent: Option<u64>,
+    /// Trace id shared by all spans descending from the same root span,
+    ///
+    pub trace_id: u128,
+    /// Name of the span,
+    ///
+    pub name: String,
+    /// Start of the span in microseconds since the unix epoch,
+    ///
+    pub start_us: u64,
+    /// Duration of the span in microseconds,
+    ///
+    pub duration_us: u64,
+    /// Recorded fields, (address, host, sequence, result, etc)
+    ///
+    pub fields: BTreeMap<String, String>,
+}
+
+impl TimelineSpan {
+    /// Returns the value of a recorded field,
+    ///
+    #[inline]
+    pub fn field(&self, name: impl AsRef<str>) -> Option<&str> {
+        self.fields.get(name.as_ref()).map(|f| f.as_str())
+    }
+
+    /// Returns true if the recorded result of this span is an error,
+    ///
+    #[inline]
+    pub fn is_error(&self) -> bool {
+        self.field("result")
+            .map(|r| r.starts_with("error"))
+            .unwrap_or_default()
+    }
+}
+
+/// Collects spans emitted by the engine so that they can be exported after a run,
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let timeline = Timeline::default();
+///
+/// tracing_subscriber::registry().with(timeline.layer()).init();
+///
+/// // .. Run the engine
+///
+/// timeline.write_chrome_trace("trace.json")?;
+/// ```
+///
+#[derive(Default, Clone)]
+pub struct Timeline {
+    /// Spans that have been closed,
+    ///
+    spans: Arc<Mutex<Vec<TimelineSpan>>>,
+}
+
+impl Timeline {
+    /// Returns a tracing layer that records engine spans into this timeline,
+    ///
+    pub fn layer(&self) -> TimelineLayer {
+        TimelineLayer {
+            timeline: self.clone(),
+        }
+    }
+
+    /// Returns a copy of all recorded spans ordered by start time,
+    ///
+    pub fn spans(&self) -> Vec<TimelineSpan> {
+        let mut spans = self.spans.lock().expect("should be able to lock").clone();
+        spans.sort_by_key(|s| (s.start_us, s.id));
+        spans
+    }
+
+    /// Clears all recorded spans,
+    ///
+    pub fn clear(&self) {
+        self.spans.lock().expect("should be able to lock").clear();
+    }
+
+    /// Returns the timeline in the Chrome trace event format,
+    ///
+    /// **Note** This format can be loaded by Perfetto and chrome://tracing. Each call chain is assigned
+    /// its own thread lane so that concurrent steps do not overlap.
+    ///
+    pub fn to_chrome_trace(&self) -> serde_json::Value {
+        let spans = self.spans();
+
+        let events = spans
+            .iter()
+            .map(|s| {
+                json!({
+                    "name": s.field("address").map(|a| format!("{} {a}", s.name)).unwrap_or(s.name.clone()),
+                    "cat": s.name,
+                    "ph": "X",
+                    "ts": s.start_us,
+                    "dur": s.duration_us,
+                    "pid": 1,
+                    "tid": lane(&spans, s),
+                    "args": s.fields,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "traceEvents": events,
+            "displayTimeUnit": "ms",
+        })
+    }
+
+    /// Writes the timeline in the Chrome trace event format to a file,
+    ///
+    pub fn write_chrome_trace(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_vec_pretty(&self.to_chrome_trace())?)?;
+        Ok(())
+    }
+
+    /// Returns the timeline as an OTLP/JSON trace export request,
+    ///
+    pub fn to_otlp_json(&self, service_name: impl AsRef<str>) -> serde_json::Value {
+        let spans = self
+            .spans()
+            .iter()
+            .map(|s| {
+                let mut span = json!({
+                    "traceId": format!("{:032x}", s.trace_id),
+                    "spanId": format!("{:016x}", s.id),
+                    "name": s.name,
+                    "kind": 1,
+                    "startTimeUnixNano": (s.start_us as u128 * 1000).to_string(),
+                    "endTimeUnixNano": ((s.start_us + s.duration_us) as u128 * 1000).to_string(),
+                    "attributes": s.fields.iter().map(|(k, v)| json!({
+                        "key": format!("loopio.{k}"),
+                        "value": { "stringValue": v }
+                    })).collect::<Vec<_>>(),
+                    "status": if s.is_error() {
+                        json!({ "code": 2, "message": s.field("result").unwrap_or_default() })
+                    } else {
+                        json!({ "code": 1 })
+                    },
+                });
+
+                if let Some(parent) = s.parent {
+                    span["parentSpanId"] = json!(format!("{:016x}", parent));
+                }
+
+                span
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "resourceSpans": [{
+                "resource": {
+                    "attributes": [{
+                        "key": "service.name",
+                        "value": { "stringValue": service_name.as_ref() }
+                    }]
+                },
+                "scopeSpans": [{
+                    "scope": { "name": "loopio" },
+                    "spans": spans,
+                }]
+            }]
+        })
+    }
+
+    /// Exports the timeline to an OpenTelemetry collector w/ OTLP/HTTP,
+    ///
+    /// **Note** A local collector listens on `http://localhost:4318/v1/traces` by default.
+    ///
+    #[cfg(feature = "hyper-ext")]
+    pub async fn export_otlp(
+        &self,
+        endpoint: impl AsRef<str>,
+        service_name: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        let body = serde_json::to_vec(&self.to_otlp_json(service_name))?;
+
+        let request = hyper::Request::post(endpoint.as_ref())
+            .header("content-type", "application/json")
+            .body(hyper::Body::from(body))?;
+
+        let response = crate::prelude::local_client().request(request).await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Collector responded with {}",
+                response.status()
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the lane a span should be drawn on,
+///
+/// Spans are drawn on the lane of their closest engine call, otherwise they use their own id.
+///
+fn lane(spans: &[TimelineSpan], span: &TimelineSpan) -> u64 {
+    let mut current = Some(span);
+    while let Some(s) = current {
+        if s.name == ENGINE_CALL_SPAN {
+            return s.id;
+        }
+        current = s
+            .parent
+            .and_then(|p| spans.iter().find(|parent| parent.id == p));
+    }
+    span.id
+}
+
+/// Span ids assigned by the timeline,
+///
+/// **Note** Span ids assigned by tracing can be re-used after a span closes, so the timeline assigns its own.
+///
+static NEXT_SPAN_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Tracing layer that records engine spans into a timeline,
+///
+pub struct TimelineLayer {
+    timeline: Timeline,
+}
+
+/// State stored in span extensions while a span is open,
+///
+struct OpenSpan {
+    id: u64,
+    parent: Option<u64>,
+    trace_id: u128,
+    start: SystemTime,
+    fields: BTreeMap<String, String>,
+}
+
+/// Visits span fields and formats values,
+///
+struct FieldVisitor<'a>(&'a mut BTreeMap<String, String>);
+
+impl<'a> Visit for FieldVisitor<'a> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+impl<S> Layer<S> for TimelineLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().target() != TIMELINE_TARGET {
+            return;
+        }
+
+        if let Some(span) = ctx.span(id) {
+            let mut fields = BTreeMap::new();
+            attrs.record(&mut FieldVisitor(&mut fields));
+
+            // Find the closest parent that is also recorded by the timeline
+            let parent = span.scope().skip(1).find_map(|p| {
+                p.extensions()
+                    .get::<OpenSpan>()
+                    .map(|o| (o.id, o.trace_id, o.fields.get("sequence").cloned()))
+            });
+
+            if let Some(sequence) = parent.as_ref().and_then(|p| p.2.clone()) {
+                fields.entry("sequence".to_string()).or_insert(sequence);
+            }
+
+            span.extensions_mut().insert(OpenSpan {
+                id: NEXT_SPAN_ID.fetch_add(1, Ordering::Relaxed),
+                parent: parent.as_ref().map(|p| p.0),
+                trace_id: parent
+                    .map(|p| p.1)
+                    .unwrap_or(uuid::Uuid::new_v4().as_u128()),
+                start: SystemTime::now(),
+                fields,
+            });
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(open) = span.extensions_mut().get_mut::<OpenSpan>() {
+                values.record(&mut FieldVisitor(&mut open.fields));
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id) {
+            if let Some(open) = span.extensions_mut().remove::<OpenSpan>() {
+                let start_us = open
+                    .start
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_micros() as u64)
+                    .unwrap_or_default();
+                let duration_us = open
+                    .start
+                    .elapsed()
+                    .map(|d| d.as_micros() as u64)
+                    .unwrap_or_default();
+
+                self.timeline
+                    .spans
+                    .lock()
+                    .expect("should be able to lock")
+                    .push(TimelineSpan {
+                        id: open.id,
+                        parent: open.parent,
+                        trace_id: open.trace_id,
+                        name: span.name().to_string(),
+                        start_us,
+                        duration_us,
+                        fields: open.fields,
+                    });
+            }
+        }
+    }
+}
+
+/// Formats the result of a span for recording,
+///
+pub(crate) fn span_result<T>(result: &anyhow::Result<T>) -> String {
+    match result {
+        Ok(_) => String::from("ok"),
+        Err(err) => format!("error: {err}"),
+    }
+}
+
+#[tokio::test]
+async fn test_timeline() {
+    use crate::prelude::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let timeline = Timeline::default();
+    let dispatch = tracing::Dispatch::new(tracing_subscriber::registry().with(timeline.layer()));
+    let _guard = tracing::dispatcher::set_default(&dispatch);
+
+    // Engine runtime threads need the same dispatcher to record spans created by the engine
+    let mut runtime = tokio::runtime::Builder::new_multi_thread();
+    runtime.enable_all().on_thread_start(move || {
+        std::mem::forget(tracing::dispatcher::set_default(&dispatch));
+    });
+
+    let mut workspace = Workspace::new();
+    workspace.add_buffer(
+        "demo.md",
+        r#"
+```runmd
++ .operation timeline-a
+<builtin.println>                   Hello World a
+
++ .operation timeline-b
+<builtin.println>                   Hello World b
+
++ .sequence timeline
+: .step timeline-a, timeline-b
+: .step timeline-a
+: .loop false
+```
+"#,
+    );
+
+    let engine = EngineBuilder::new(runtime).build();
+    let engine = engine.compile(workspace).await.unwrap();
+    let (eh, _) = engine.default_startup().await.unwrap();
+
+    eh.run("engine://timeline").await.unwrap();
+
+    let spans = timeline.spans();
+    let calls = spans
+        .iter()
+        .filter(|s| s.name == ENGINE_CALL_SPAN)
+        .collect::<Vec<_>>();
+    assert_eq!(4, calls.len());
+
+    let steps = spans
+        .iter()
+        .filter(|s| s.name == SEQUENCE_STEP_SPAN)
+        .collect::<Vec<_>>();
+    assert_eq!(2, steps.len());
+    assert_eq!(Some("timeline-a, timeline-b"), steps[0].field("step"));
+
+    let operations = spans
+        .iter()
+        .filter(|s| s.name == OPERATION_SPAN)
+        .collect::<Vec<_>>();
+    assert_eq!(3, operations.len());
+    for op in operations {
+        assert_eq!(Some("ok"), op.field("result"));
+        assert_eq!(Some("timeline"), op.field("sequence"));
+
+        let parent = spans.iter().find(|s| Some(s.id) == op.parent).unwrap();
+        assert_eq!(ENGINE_CALL_SPAN, parent.name);
+        assert_eq!(op.field("address"), parent.field("address"));
+    }
+
+    let chrome = timeline.to_chrome_trace();
+    assert_eq!(spans.len(), chrome["traceEvents"].as_array().unwrap().len());
+
+    let otlp = timeline.to_otlp_json("test");
+    let exported = otlp["resourceSpans"][0]["scopeSpans"][0]["spans"]
+        .as_array()
+        .unwrap();
+    assert_eq!(spans.len(), exported.len());
+    assert!(exported
+        .iter()
+        .all(|s| s["traceId"] == exported[0]["traceId"]));
+}
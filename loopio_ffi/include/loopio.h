@@ -0,0 +1,161 @@
+// Generated from loopio_ffi/src/abi.rs, do not edit.
+
+#ifndef LOOPIO_H
+#define LOOPIO_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/// Status code returned by fallible functions,
+///
+typedef enum LoopioStatus {
+    /// The call completed successfully,
+    ///
+    LOOPIO_STATUS_OK = 0,
+    /// A pointer argument was null, a string argument was not valid UTF-8, or a field value could not be parsed,
+    ///
+    LOOPIO_STATUS_INVALID_ARGUMENT = 1,
+    /// The engine has not been compiled yet,
+    ///
+    LOOPIO_STATUS_NOT_COMPILED = 2,
+    /// The engine has already been compiled,
+    ///
+    LOOPIO_STATUS_ALREADY_COMPILED = 3,
+    /// The address or field could not be found,
+    ///
+    LOOPIO_STATUS_NOT_FOUND = 4,
+    /// The call failed, the reason can be read w/ `loopio_last_error`,
+    ///
+    LOOPIO_STATUS_ERROR = 5,
+} LoopioStatus;
+
+/// Opaque handle to a loopio engine,
+///
+/// **Note** Created w/ `loopio_engine_new` and released w/ `loopio_engine_free`.
+///
+typedef struct LoopioEngine LoopioEngine;
+
+/// Creates a new engine w/ the default plugins enabled, or null if the engine could not be created,
+///
+/// **Note** The engine must be released w/ `loopio_engine_free`.
+///
+LoopioEngine* loopio_engine_new(void);
+
+/// Releases an engine,
+///
+/// # Safety
+///
+/// `engine` must be null or a pointer returned by `loopio_engine_new`.
+///
+void loopio_engine_free(LoopioEngine* engine);
+
+/// Adds a runmd source to the engine's workspace,
+///
+/// # Safety
+///
+/// `engine` must be a valid engine, `relative` and `source` must be valid nul-terminated strings.
+///
+LoopioStatus loopio_engine_add_source(LoopioEngine* engine, const char* relative, const char* source);
+
+/// Adds a local file to the engine's workspace,
+///
+/// # Safety
+///
+/// `engine` must be a valid engine, `path` must be a valid nul-terminated string.
+///
+LoopioStatus loopio_engine_add_local(LoopioEngine* engine, const char* path);
+
+/// Compiles the engine's workspace and starts the engine,
+///
+/// # Safety
+///
+/// `engine` must be a valid engine.
+///
+LoopioStatus loopio_engine_compile(LoopioEngine* engine);
+
+/// Returns the number of plugins hosted by a compiled engine,
+///
+/// # Safety
+///
+/// `engine` must be null or a valid engine.
+///
+size_t loopio_plugin_count(const LoopioEngine* engine);
+
+/// Returns the address a plugin is hosted at, or null if the plugin does not exist,
+///
+/// # Safety
+///
+/// `engine` must be null or a valid engine. The returned string must be released w/ `loopio_string_free`.
+///
+char* loopio_plugin_address(const LoopioEngine* engine, size_t plugin);
+
+/// Returns the symbol of a plugin, or null if the plugin does not exist,
+///
+/// # Safety
+///
+/// `engine` must be null or a valid engine. The returned string must be released w/ `loopio_string_free`.
+///
+char* loopio_plugin_symbol(const LoopioEngine* engine, size_t plugin);
+
+/// Returns the number of ffi fields of a plugin,
+///
+/// # Safety
+///
+/// `engine` must be null or a valid engine.
+///
+size_t loopio_plugin_field_count(const LoopioEngine* engine, size_t plugin);
+
+/// Returns the name of an ffi field of a plugin, or null if the field does not exist,
+///
+/// # Safety
+///
+/// `engine` must be null or a valid engine. The returned string must be released w/ `loopio_string_free`.
+///
+char* loopio_plugin_field_name(const LoopioEngine* engine, size_t plugin, size_t field_idx);
+
+/// Returns the ffi type name of a field of a plugin, or null if the field does not exist,
+///
+/// # Safety
+///
+/// `engine` must be null or a valid engine. The returned string must be released w/ `loopio_string_free`.
+///
+char* loopio_plugin_field_ffi_type(const LoopioEngine* engine, size_t plugin, size_t field_idx);
+
+/// Sets the value of an ffi field for the next call of an address,
+///
+/// # Safety
+///
+/// `engine` must be a valid engine, `address`, `field` and `value` must be valid nul-terminated strings.
+///
+LoopioStatus loopio_set_field(LoopioEngine* engine, const char* address, const char* field, const char* value);
+
+/// Calls a hosted address and waits for it to complete,
+///
+/// # Safety
+///
+/// `engine` must be a valid engine, `address` must be a valid nul-terminated string.
+///
+LoopioStatus loopio_call(LoopioEngine* engine, const char* address);
+
+/// Returns the last error raised on the current thread, or null if no error was raised,
+///
+/// **Note** The returned string must be released w/ `loopio_string_free`.
+///
+char* loopio_last_error(void);
+
+/// Releases a string returned by this library,
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by this library that has not been released.
+///
+void loopio_string_free(char* s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif // LOOPIO_H
@@ -0,0 +1,857 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::ffi::c_char;
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::panic::catch_unwind;
+use std::panic::AssertUnwindSafe;
+
+use anyhow::anyhow;
+use clap::builder::Resettable;
+use loopio::engine::EngineBuilder;
+use loopio::prelude::runir::prelude::Repr;
+use loopio::prelude::*;
+use tracing::debug;
+
+thread_local! {
+    /// Last error raised on the current thread,
+    ///
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Status code returned by fallible functions,
+///
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopioStatus {
+    /// The call completed successfully,
+    ///
+    Ok = 0,
+    /// A pointer argument was null, a string argument was not valid UTF-8, or a field value could not be parsed,
+    ///
+    InvalidArgument = 1,
+    /// The engine has not been compiled yet,
+    ///
+    NotCompiled = 2,
+    /// The engine has already been compiled,
+    ///
+    AlreadyCompiled = 3,
+    /// The address or field could not be found,
+    ///
+    NotFound = 4,
+    /// The call failed, the reason can be read w/ `loopio_last_error`,
+    ///
+    Error = 5,
+}
+
+/// Error returned w/ a status code other than `LoopioStatus::Error`,
+///
+#[derive(Debug)]
+struct StatusError {
+    /// Status code returned to the caller,
+    ///
+    status: LoopioStatus,
+    /// Error message,
+    ///
+    message: String,
+}
+
+impl std::fmt::Display for StatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+/// Opaque handle to a loopio engine,
+///
+/// **Note** Created w/ `loopio_engine_new` and released w/ `loopio_engine_free`.
+///
+pub struct LoopioEngine {
+    /// Engine builder, taken when the engine is compiled,
+    ///
+    builder: Option<EngineBuilder>,
+    /// Workspace the engine will be compiled from,
+    ///
+    workspace: Workspace,
+    /// Foreground engine, set when the engine is compiled,
+    ///
+    engine: Option<ForegroundEngine>,
+    /// Plugins hosted by the compiled engine,
+    ///
+    plugins: Vec<PluginEntry>,
+    /// Field updates to apply on the next call of an address,
+    ///
+    updates: BTreeMap<String, FrameUpdates>,
+}
+
+/// Plugin hosted at an address of a compiled engine,
+///
+struct PluginEntry {
+    /// Address the plugin is hosted at,
+    ///
+    address: CString,
+    /// Plugin symbol,
+    ///
+    symbol: CString,
+    /// Fields of the plugin w/ an ffi type,
+    ///
+    fields: Vec<FieldEntry>,
+}
+
+/// Field of a plugin w/ an ffi type,
+///
+struct FieldEntry {
+    /// Field name,
+    ///
+    name: CString,
+    /// Name of the ffi type,
+    ///
+    ffi_type: CString,
+    /// Field representation,
+    ///
+    repr: Repr,
+}
+
+impl LoopioEngine {
+    /// Creates a new engine handle from an engine builder,
+    ///
+    /// **Note** Plugins enabled on the builder are enumerated once the engine is compiled.
+    ///
+    pub fn new(builder: EngineBuilder) -> Self {
+        Self {
+            builder: Some(builder),
+            workspace: Workspace::new(),
+            engine: None,
+            plugins: vec![],
+            updates: BTreeMap::new(),
+        }
+    }
+
+    /// Returns a mutable reference to the workspace the engine will be compiled from,
+    ///
+    pub fn workspace_mut(&mut self) -> &mut Workspace {
+        &mut self.workspace
+    }
+
+    /// Compiles the workspace and starts the engine,
+    ///
+    pub fn compile(&mut self) -> anyhow::Result<()> {
+        let mut builder = self
+            .builder
+            .take()
+            .ok_or(anyhow!("Engine has already been compiled"))?;
+        builder.set_workspace(self.workspace.clone());
+        let parser = builder.parser();
+
+        let engine = catch_unwind(AssertUnwindSafe(|| ForegroundEngine::new(builder)))
+            .map_err(|_| anyhow!("Could not compile engine"))?;
+
+        self.plugins = plugins(&engine.package, &parser);
+        self.engine = Some(engine);
+        Ok(())
+    }
+
+    /// Sets the value of an ffi field for the next call of an address,
+    ///
+    /// **Errors** Returns an error if the address or field cannot be found, or if the value cannot be parsed by the
+    /// field's ffi value parser.
+    ///
+    pub fn set_field(
+        &mut self,
+        address: impl AsRef<str>,
+        field: impl AsRef<str>,
+        value: impl Into<String>,
+    ) -> anyhow::Result<()> {
+        let address = address.as_ref();
+        let field = field.as_ref();
+        let value = value.into();
+
+        let repr = self
+            .plugins
+            .iter()
+            .find(|p| p.address.to_bytes() == address.as_bytes())
+            .ok_or_else(|| {
+                status_error(
+                    LoopioStatus::NotFound,
+                    format!("Could not find a plugin at `{address}`"),
+                )
+            })?
+            .fields
+            .iter()
+            .find(|f| f.name.to_bytes() == field.as_bytes())
+            .map(|f| f.repr)
+            .ok_or_else(|| {
+                status_error(
+                    LoopioStatus::NotFound,
+                    format!("`{address}` does not have an ffi field `{field}`"),
+                )
+            })?;
+
+        validate(field, &repr, &value)?;
+
+        let packet = ResourceKey::<Property>::with_repr(repr)
+            .field_packet()
+            .ok_or(anyhow!("Could not create a field packet for `{field}`"))?;
+
+        debug!("Setting field `{field}` for `{address}`");
+        self.updates
+            .entry(address.to_string())
+            .or_default()
+            .frame
+            .fields
+            .push(packet.parse(value));
+        Ok(())
+    }
+
+    /// Calls an address and waits for it to complete,
+    ///
+    /// **Note** Field values set for the address are applied to this call and then cleared.
+    ///
+    pub fn call(&mut self, address: impl AsRef<str>) -> anyhow::Result<ThunkContext> {
+        let address = address.as_ref();
+        let engine = self
+            .engine
+            .as_ref()
+            .ok_or(anyhow!("Engine has not been compiled"))?;
+
+        let mut eh = engine.engine_handle();
+
+        // Addresses are resolved when the call is spawned, so unknown addresses are found before calling
+        engine
+            .handle()
+            .block_on(eh.hosted_resource(address))
+            .map_err(|err| status_error(LoopioStatus::NotFound, err.to_string()))?;
+
+        let bg = eh
+            .background()
+            .ok_or(anyhow!("Background work is not enabled"))?;
+
+        let mut bgf = bg
+            .call(address)
+            .map_err(|err| anyhow!("Could not call {address}: {err}"))?;
+
+        match self.updates.remove(address) {
+            Some(updates) => bgf.spawn_with_updates(updates),
+            None => bgf.spawn(),
+        };
+
+        bgf.into_foreground()
+            .map_err(|err| anyhow!("{address} failed: {err}"))
+    }
+}
+
+/// Returns the plugins hosted in a package w/ their ffi fields,
+///
+/// Fields defined in the plugin's runmd block are linked to the plugin's receiver, the rest of the plugin's ffi fields
+/// are linked from the fields registered w/ the parser.
+///
+/// **Note** Must be called after the package is compiled, since compiling does not keep reprs linked beforehand.
+///
+fn plugins(package: &Package, parser: &AttributeParser<Shared>) -> Vec<PluginEntry> {
+    let mut plugins = vec![];
+
+    for m in package.search("*") {
+        let Some(address) = m.host.address() else {
+            continue;
+        };
+        let Some(recv) = m
+            .program
+            .context()
+            .ok()
+            .and_then(|tc| tc.attribute.repr())
+            .and_then(|r| r.as_recv())
+        else {
+            continue;
+        };
+
+        let symbol = recv.name();
+        let defined = recv.fields().map(|f| f.to_vec()).unwrap_or_default();
+        let registered = symbol
+            .as_ref()
+            .map(|s| parser.object_type_fields(s))
+            .unwrap_or_default();
+
+        let mut fields: Vec<FieldEntry> = vec![];
+        for f in defined.into_iter().chain(registered) {
+            let Some(entry) = field_entry(f) else {
+                continue;
+            };
+
+            // Fields defined in runmd come first so that their repr is used for the field
+            if fields.iter().all(|e| e.name != entry.name) {
+                fields.push(entry);
+            }
+        }
+
+        if let (Ok(address), Some(Ok(symbol))) = (
+            CString::new(address.as_str()),
+            symbol.map(|n| CString::new(n.as_str())),
+        ) {
+            plugins.push(PluginEntry {
+                address,
+                symbol,
+                fields,
+            });
+        }
+    }
+
+    plugins
+}
+
+/// Returns the entry of a field w/ an ffi type,
+///
+fn field_entry(repr: Repr) -> Option<FieldEntry> {
+    // Fields w/o `#[reality(ffi)]` have the unit ffi type
+    if repr.as_resource()?.is_ffi_type::<()>() {
+        return None;
+    }
+
+    Some(FieldEntry {
+        name: CString::new(repr.as_field()?.name()?).ok()?,
+        ffi_type: CString::new(repr.ffi_type()?).ok()?,
+        repr,
+    })
+}
+
+/// Returns an error that is reported to the caller w/ a status code,
+///
+fn status_error(status: LoopioStatus, message: impl Into<String>) -> anyhow::Error {
+    StatusError {
+        status,
+        message: message.into(),
+    }
+    .into()
+}
+
+/// Validates a value w/ the ffi value parser of a field,
+///
+fn validate(field: &str, repr: &Repr, value: &str) -> anyhow::Result<()> {
+    let Some(Resettable::Value(parser)) = repr.field_value_parser() else {
+        return Ok(());
+    };
+
+    clap::Command::new("loopio")
+        .no_binary_name(true)
+        .arg(
+            clap::Arg::new(field.to_string())
+                .long(field.to_string())
+                .value_parser(parser),
+        )
+        .try_get_matches_from([format!("--{field}={value}")])
+        .map(|_| ())
+        .map_err(|err| {
+            let reason = err.to_string();
+            status_error(
+                LoopioStatus::InvalidArgument,
+                reason
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .trim_start_matches("error: "),
+            )
+        })
+}
+
+/// Sets the last error of the current thread,
+///
+fn set_last_error(err: impl std::fmt::Display) {
+    let msg = CString::new(err.to_string().replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(msg));
+}
+
+/// Converts a result into a status code, saving the error if one occurred,
+///
+/// **Note** Errors w/ a status code are returned w/ that status instead of `on_err`.
+///
+fn status(result: anyhow::Result<()>, on_err: LoopioStatus) -> LoopioStatus {
+    match result {
+        Ok(_) => LoopioStatus::Ok,
+        Err(err) => {
+            let status = err
+                .downcast_ref::<StatusError>()
+                .map(|e| e.status)
+                .unwrap_or(on_err);
+            set_last_error(err);
+            status
+        }
+    }
+}
+
+/// Runs a function, converting a panic into an error status,
+///
+fn guard(f: impl FnOnce() -> LoopioStatus) -> LoopioStatus {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|_| {
+        set_last_error("Unexpected panic");
+        LoopioStatus::Error
+    })
+}
+
+/// Reads a string argument,
+///
+unsafe fn arg<'a>(s: *const c_char) -> Option<&'a str> {
+    if s.is_null() {
+        set_last_error("Argument was null");
+        None
+    } else {
+        CStr::from_ptr(s).to_str().map_err(set_last_error).ok()
+    }
+}
+
+/// Returns a copy of a string that must be released w/ `loopio_string_free`,
+///
+fn owned(s: &CStr) -> *mut c_char {
+    s.to_owned().into_raw()
+}
+
+/// Returns a field of a plugin,
+///
+unsafe fn field<'a>(
+    engine: *const LoopioEngine,
+    plugin: usize,
+    field: usize,
+) -> Option<&'a FieldEntry> {
+    engine.as_ref()?.plugins.get(plugin)?.fields.get(field)
+}
+
+/// Creates a new engine w/ the default plugins enabled, or null if the engine could not be created,
+///
+/// **Note** The engine must be released w/ `loopio_engine_free`.
+///
+#[no_mangle]
+pub extern "C" fn loopio_engine_new() -> *mut LoopioEngine {
+    catch_unwind(|| Box::into_raw(Box::new(LoopioEngine::new(Engine::builder())))).unwrap_or_else(
+        |_| {
+            set_last_error("Unexpected panic");
+            std::ptr::null_mut()
+        },
+    )
+}
+
+/// Releases an engine,
+///
+/// # Safety
+///
+/// `engine` must be null or a pointer returned by `loopio_engine_new`.
+///
+#[no_mangle]
+pub unsafe extern "C" fn loopio_engine_free(engine: *mut LoopioEngine) {
+    if !engine.is_null() {
+        drop(Box::from_raw(engine));
+    }
+}
+
+/// Adds a runmd source to the engine's workspace,
+///
+/// # Safety
+///
+/// `engine` must be a valid engine, `relative` and `source` must be valid nul-terminated strings.
+///
+#[no_mangle]
+pub unsafe extern "C" fn loopio_engine_add_source(
+    engine: *mut LoopioEngine,
+    relative: *const c_char,
+    source: *const c_char,
+) -> LoopioStatus {
+    guard(|| {
+        let (Some(engine), Some(relative), Some(source)) =
+            (engine.as_mut(), arg(relative), arg(source))
+        else {
+            return LoopioStatus::InvalidArgument;
+        };
+
+        if engine.engine.is_some() {
+            set_last_error("Engine has already been compiled");
+            return LoopioStatus::AlreadyCompiled;
+        }
+
+        engine.workspace.add_buffer(relative, source);
+        LoopioStatus::Ok
+    })
+}
+
+/// Adds a local file to the engine's workspace,
+///
+/// # Safety
+///
+/// `engine` must be a valid engine, `path` must be a valid nul-terminated string.
+///
+#[no_mangle]
+pub unsafe extern "C" fn loopio_engine_add_local(
+    engine: *mut LoopioEngine,
+    path: *const c_char,
+) -> LoopioStatus {
+    guard(|| {
+        let (Some(engine), Some(path)) = (engine.as_mut(), arg(path)) else {
+            return LoopioStatus::InvalidArgument;
+        };
+
+        if engine.engine.is_some() {
+            set_last_error("Engine has already been compiled");
+            return LoopioStatus::AlreadyCompiled;
+        }
+
+        engine.workspace.add_local(path);
+        LoopioStatus::Ok
+    })
+}
+
+/// Compiles the engine's workspace and starts the engine,
+///
+/// # Safety
+///
+/// `engine` must be a valid engine.
+///
+#[no_mangle]
+pub unsafe extern "C" fn loopio_engine_compile(engine: *mut LoopioEngine) -> LoopioStatus {
+    guard(|| {
+        let Some(engine) = engine.as_mut() else {
+            return LoopioStatus::InvalidArgument;
+        };
+
+        if engine.engine.is_some() {
+            set_last_error("Engine has already been compiled");
+            return LoopioStatus::AlreadyCompiled;
+        }
+
+        status(engine.compile(), LoopioStatus::Error)
+    })
+}
+
+/// Returns the number of plugins hosted by a compiled engine,
+///
+/// # Safety
+///
+/// `engine` must be null or a valid engine.
+///
+#[no_mangle]
+pub unsafe extern "C" fn loopio_plugin_count(engine: *const LoopioEngine) -> usize {
+    engine.as_ref().map(|e| e.plugins.len()).unwrap_or_default()
+}
+
+/// Returns the address a plugin is hosted at, or null if the plugin does not exist,
+///
+/// # Safety
+///
+/// `engine` must be null or a valid engine. The returned string must be released w/ `loopio_string_free`.
+///
+#[no_mangle]
+pub unsafe extern "C" fn loopio_plugin_address(
+    engine: *const LoopioEngine,
+    plugin: usize,
+) -> *mut c_char {
+    engine
+        .as_ref()
+        .and_then(|e| e.plugins.get(plugin))
+        .map(|p| owned(&p.address))
+        .unwrap_or(std::ptr::null_mut())
+}
+
+/// Returns the symbol of a plugin, or null if the plugin does not exist,
+///
+/// # Safety
+///
+/// `engine` must be null or a valid engine. The returned string must be released w/ `loopio_string_free`.
+///
+#[no_mangle]
+pub unsafe extern "C" fn loopio_plugin_symbol(
+    engine: *const LoopioEngine,
+    plugin: usize,
+) -> *mut c_char {
+    engine
+        .as_ref()
+        .and_then(|e| e.plugins.get(plugin))
+        .map(|p| owned(&p.symbol))
+        .unwrap_or(std::ptr::null_mut())
+}
+
+/// Returns the number of ffi fields of a plugin,
+///
+/// # Safety
+///
+/// `engine` must be null or a valid engine.
+///
+#[no_mangle]
+pub unsafe extern "C" fn loopio_plugin_field_count(
+    engine: *const LoopioEngine,
+    plugin: usize,
+) -> usize {
+    engine
+        .as_ref()
+        .and_then(|e| e.plugins.get(plugin))
+        .map(|p| p.fields.len())
+        .unwrap_or_default()
+}
+
+/// Returns the name of an ffi field of a plugin, or null if the field does not exist,
+///
+/// # Safety
+///
+/// `engine` must be null or a valid engine. The returned string must be released w/ `loopio_string_free`.
+///
+#[no_mangle]
+pub unsafe extern "C" fn loopio_plugin_field_name(
+    engine: *const LoopioEngine,
+    plugin: usize,
+    field_idx: usize,
+) -> *mut c_char {
+    field(engine, plugin, field_idx)
+        .map(|f| owned(&f.name))
+        .unwrap_or(std::ptr::null_mut())
+}
+
+/// Returns the ffi type name of a field of a plugin, or null if the field does not exist,
+///
+/// # Safety
+///
+/// `engine` must be null or a valid engine. The returned string must be released w/ `loopio_string_free`.
+///
+#[no_mangle]
+pub unsafe extern "C" fn loopio_plugin_field_ffi_type(
+    engine: *const LoopioEngine,
+    plugin: usize,
+    field_idx: usize,
+) -> *mut c_char {
+    field(engine, plugin, field_idx)
+        .map(|f| owned(&f.ffi_type))
+        .unwrap_or(std::ptr::null_mut())
+}
+
+/// Sets the value of an ffi field for the next call of an address,
+///
+/// # Safety
+///
+/// `engine` must be a valid engine, `address`, `field` and `value` must be valid nul-terminated strings.
+///
+#[no_mangle]
+pub unsafe extern "C" fn loopio_set_field(
+    engine: *mut LoopioEngine,
+    address: *const c_char,
+    field: *const c_char,
+    value: *const c_char,
+) -> LoopioStatus {
+    guard(|| {
+        let (Some(engine), Some(address), Some(field), Some(value)) =
+            (engine.as_mut(), arg(address), arg(field), arg(value))
+        else {
+            return LoopioStatus::InvalidArgument;
+        };
+
+        if engine.engine.is_none() {
+            set_last_error("Engine has not been compiled");
+            return LoopioStatus::NotCompiled;
+        }
+
+        status(engine.set_field(address, field, value), LoopioStatus::Error)
+    })
+}
+
+/// Calls a hosted address and waits for it to complete,
+///
+/// # Safety
+///
+/// `engine` must be a valid engine, `address` must be a valid nul-terminated string.
+///
+#[no_mangle]
+pub unsafe extern "C" fn loopio_call(
+    engine: *mut LoopioEngine,
+    address: *const c_char,
+) -> LoopioStatus {
+    guard(|| {
+        let (Some(engine), Some(address)) = (engine.as_mut(), arg(address)) else {
+            return LoopioStatus::InvalidArgument;
+        };
+
+        if engine.engine.is_none() {
+            set_last_error("Engine has not been compiled");
+            return LoopioStatus::NotCompiled;
+        }
+
+        status(engine.call(address).map(|_| ()), LoopioStatus::Error)
+    })
+}
+
+/// Returns the last error raised on the current thread, or null if no error was raised,
+///
+/// **Note** The returned string must be released w/ `loopio_string_free`.
+///
+#[no_mangle]
+pub extern "C" fn loopio_last_error() -> *mut c_char {
+    LAST_ERROR.with(|e| {
+        e.borrow()
+            .as_deref()
+            .map(owned)
+            .unwrap_or(std::ptr::null_mut())
+    })
+}
+
+/// Releases a string returned by this library,
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by this library that has not been released.
+///
+#[no_mangle]
+pub unsafe extern "C" fn loopio_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[test]
+#[allow(clippy::clone_on_copy)]
+fn test_plugin_abi() {
+    use reality::derive::*;
+    use serde::Deserialize;
+    use serde::Serialize;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    /// Values recorded by the test plugin,
+    ///
+    static RECORDED: Mutex<Vec<String>> = Mutex::new(vec![]);
+
+    #[derive(Reality, Serialize, Deserialize, PartialEq, Clone, Default)]
+    #[reality(plugin, group = "test")]
+    struct Record {
+        #[reality(derive_fromstr)]
+        name: String,
+        #[reality(ffi)]
+        value: String,
+        #[reality(ffi)]
+        path: PathBuf,
+        #[reality(ffi)]
+        count: u32,
+        /// Ffi field w/o a default value in runmd,
+        ///
+        #[reality(ffi)]
+        label: String,
+    }
+
+    #[async_trait::async_trait]
+    impl CallAsync for Record {
+        async fn call(tc: &mut ThunkContext) -> anyhow::Result<()> {
+            let init = tc.as_remote_plugin::<Record>().await;
+            RECORDED.lock().unwrap().push(format!(
+                "{} {} {} {} {}",
+                init.name,
+                init.value,
+                init.path.display(),
+                init.count,
+                init.label
+            ));
+            Ok(())
+        }
+    }
+
+    fn take(s: *mut c_char) -> String {
+        assert!(!s.is_null());
+        let value = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string();
+        unsafe { loopio_string_free(s) };
+        value
+    }
+
+    let mut builder = Engine::builder();
+    builder.enable::<Record>();
+    let engine = Box::into_raw(Box::new(LoopioEngine::new(builder)));
+
+    let relative = CString::new("demo.md").unwrap();
+    let source = CString::new(
+        r#"
+```runmd
++ .operation a
+<test.record> hello
+: .value    default
+: .path     default.txt
+: .count    1
+```
+"#,
+    )
+    .unwrap();
+
+    unsafe {
+        assert_eq!(
+            LoopioStatus::NotCompiled,
+            loopio_call(engine, relative.as_ptr())
+        );
+        assert!(take(loopio_last_error()).contains("not been compiled"));
+
+        assert_eq!(
+            LoopioStatus::Ok,
+            loopio_engine_add_source(engine, relative.as_ptr(), source.as_ptr())
+        );
+        assert_eq!(LoopioStatus::Ok, loopio_engine_compile(engine));
+        assert_eq!(LoopioStatus::AlreadyCompiled, loopio_engine_compile(engine));
+
+        // Find the test plugin and its ffi fields
+        let plugin = (0..loopio_plugin_count(engine))
+            .find(|p| take(loopio_plugin_symbol(engine, *p)) == "test.record")
+            .expect("should have the test plugin");
+        let address = take(loopio_plugin_address(engine, plugin));
+        assert_eq!("a/test.record?b=0&n=1", address);
+
+        let fields = (0..loopio_plugin_field_count(engine, plugin))
+            .map(|f| {
+                (
+                    take(loopio_plugin_field_name(engine, plugin, f)),
+                    take(loopio_plugin_field_ffi_type(engine, plugin, f)),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("value".to_string(), "string".to_string()),
+                ("path".to_string(), "path_buf".to_string()),
+                ("count".to_string(), "u32".to_string()),
+                ("label".to_string(), "string".to_string())
+            ],
+            fields
+        );
+        assert!(loopio_plugin_field_name(engine, plugin, fields.len()).is_null());
+
+        // Set fields and call the address
+        let address = CString::new(address).unwrap();
+        let set = |field: &str, value: &str| {
+            let field = CString::new(field).unwrap();
+            let value = CString::new(value).unwrap();
+            loopio_set_field(engine, address.as_ptr(), field.as_ptr(), value.as_ptr())
+        };
+        assert_eq!(LoopioStatus::Ok, set("value", "world"));
+        assert_eq!(LoopioStatus::Ok, set("path", "world.txt"));
+        assert_eq!(LoopioStatus::Ok, set("count", "2"));
+        assert_eq!(LoopioStatus::Ok, set("label", "set"));
+        assert_eq!(LoopioStatus::NotFound, set("name", "other"));
+        assert!(take(loopio_last_error()).contains("does not have an ffi field `name`"));
+
+        // Values are validated w/ the field's value parser
+        assert_eq!(LoopioStatus::InvalidArgument, set("count", "two"));
+        assert!(take(loopio_last_error()).contains("invalid value 'two'"));
+
+        assert_eq!(LoopioStatus::Ok, loopio_call(engine, address.as_ptr()));
+
+        // Field values only apply to the next call
+        assert_eq!(LoopioStatus::Ok, loopio_call(engine, address.as_ptr()));
+
+        let unknown = CString::new("b/test.record").unwrap();
+        assert_eq!(
+            LoopioStatus::NotFound,
+            loopio_call(engine, unknown.as_ptr())
+        );
+        let field = CString::new("value").unwrap();
+        assert_eq!(
+            LoopioStatus::NotFound,
+            loopio_set_field(engine, unknown.as_ptr(), field.as_ptr(), field.as_ptr())
+        );
+
+        loopio_engine_free(engine);
+    }
+
+    assert_eq!(
+        vec![
+            "hello world world.txt 2 set",
+            "hello default default.txt 1 "
+        ],
+        *RECORDED.lock().unwrap()
+    );
+}
@@ -0,0 +1,6 @@
+/// Prints the C header for the loopio plugin ABI,
+///
+fn main() -> anyhow::Result<()> {
+    print!("{}", loopio_ffi::header::generate()?);
+    Ok(())
+}
@@ -0,0 +1,218 @@
+use anyhow::anyhow;
+
+/// Source of the C ABI the header is generated from,
+///
+const ABI_SOURCE: &str = include_str!("abi.rs");
+
+/// Name of the include guard of the header,
+///
+const INCLUDE_GUARD: &str = "LOOPIO_H";
+
+/// Generates the C header for the plugin ABI,
+///
+/// **Note** Types, functions and doc comments are read from `abi.rs`, so the header is kept in sync w/ the ABI
+/// by regenerating it w/ `cargo run -p loopio_ffi --bin loopio-ffi-header > loopio_ffi/include/loopio.h`.
+///
+/// **Errors** Returns an error if a function signature cannot be mapped to a C declaration.
+///
+pub fn generate() -> anyhow::Result<String> {
+    let mut header = String::new();
+    header.push_str("// Generated from loopio_ffi/src/abi.rs, do not edit.\n\n");
+    header.push_str(&format!(
+        "#ifndef {INCLUDE_GUARD}\n#define {INCLUDE_GUARD}\n\n"
+    ));
+    header.push_str("#include <stddef.h>\n\n");
+    header.push_str("#ifdef __cplusplus\nextern \"C\" {\n#endif\n");
+
+    let mut docs = vec![];
+    let mut lines = ABI_SOURCE.lines();
+    while let Some(line) = lines.next() {
+        // Items in the source are not indented, nested doc comments belong to fields or variants
+        if line.starts_with("///") {
+            docs.push(line);
+            continue;
+        }
+
+        if line.starts_with("#[") {
+            continue;
+        }
+
+        if let Some(name) = line
+            .strip_prefix("pub enum ")
+            .and_then(|l| l.strip_suffix(" {"))
+        {
+            header.push('\n');
+            push_docs(&mut header, &docs);
+            header.push_str(&format!("typedef enum {name} {{\n"));
+            let prefix = screaming_snake_case(name);
+            for line in lines.by_ref().take_while(|l| *l != "}") {
+                let line = line.trim();
+                if line.starts_with("///") {
+                    header.push_str(&format!("    {line}\n"));
+                } else if let Some((variant, value)) =
+                    line.strip_suffix(',').and_then(|l| l.split_once(" = "))
+                {
+                    header.push_str(&format!(
+                        "    {prefix}_{} = {value},\n",
+                        screaming_snake_case(variant)
+                    ));
+                }
+            }
+            header.push_str(&format!("}} {name};\n"));
+        } else if let Some(name) = line
+            .strip_prefix("pub struct ")
+            .and_then(|l| l.strip_suffix(" {"))
+        {
+            header.push('\n');
+            push_docs(&mut header, &docs);
+            header.push_str(&format!("typedef struct {name} {name};\n"));
+        } else if line.starts_with("pub extern \"C\" fn ")
+            || line.starts_with("pub unsafe extern \"C\" fn ")
+        {
+            // Signatures may be wrapped over several lines
+            let mut signature = line.to_string();
+            while !signature.ends_with('{') {
+                match lines.next() {
+                    Some(next) => signature.push_str(next.trim()),
+                    None => break,
+                }
+            }
+
+            let decl = declaration(&signature)?;
+            header.push('\n');
+            push_docs(&mut header, &docs);
+            header.push_str(&decl);
+            header.push('\n');
+        }
+
+        docs.clear();
+    }
+
+    header.push_str("\n#ifdef __cplusplus\n}\n#endif\n\n");
+    header.push_str(&format!("#endif // {INCLUDE_GUARD}\n"));
+    Ok(header)
+}
+
+/// Pushes doc comments to the header,
+///
+/// **Note** `# Safety` sections are kept since they describe the contract of each pointer argument.
+///
+fn push_docs(header: &mut String, docs: &[&str]) {
+    for doc in docs {
+        header.push_str(doc);
+        header.push('\n');
+    }
+}
+
+/// Returns the C declaration of an `extern "C"` function signature,
+///
+fn declaration(signature: &str) -> anyhow::Result<String> {
+    let signature = signature.trim_end_matches('{').trim();
+    let unmapped = |reason: &str| anyhow!("Could not map `{signature}` to C, {reason}");
+
+    let (name, rest) = signature
+        .split_once("fn ")
+        .and_then(|(_, rest)| rest.split_once('('))
+        .ok_or_else(|| unmapped("expected a function name"))?;
+    let (params, ret) = rest
+        .rsplit_once(')')
+        .ok_or_else(|| unmapped("expected a parameter list"))?;
+
+    let c_type = |ty: &str| c_type(ty).ok_or_else(|| unmapped(&format!("unsupported type `{ty}`")));
+
+    let ret = match ret.trim().strip_prefix("->") {
+        Some(ret) => c_type(ret.trim())?,
+        None => "void".to_string(),
+    };
+
+    let params = params
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (name, ty) = p
+                .split_once(':')
+                .ok_or_else(|| unmapped(&format!("expected a typed parameter, found `{p}`")))?;
+            Ok(format!("{} {}", c_type(ty.trim())?, name.trim()))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let params = if params.is_empty() {
+        "void".to_string()
+    } else {
+        params.join(", ")
+    };
+
+    Ok(format!("{ret} {name}({params});"))
+}
+
+/// Maps a Rust type used in the ABI to a C type,
+///
+fn c_type(ty: &str) -> Option<String> {
+    if let Some(inner) = ty.strip_prefix("*const ") {
+        return Some(format!("const {}*", c_type(inner)?));
+    }
+
+    if let Some(inner) = ty.strip_prefix("*mut ") {
+        return Some(format!("{}*", c_type(inner)?));
+    }
+
+    let ty = match ty {
+        "c_char" => "char",
+        "usize" => "size_t",
+        "()" => "void",
+        ty if ty.starts_with("Loopio") => ty,
+        _ => return None,
+    };
+
+    Some(ty.to_string())
+}
+
+/// Converts a camel case identifier to screaming snake case,
+///
+fn screaming_snake_case(ident: &str) -> String {
+    let mut out = String::new();
+    for (idx, c) in ident.chars().enumerate() {
+        if c.is_uppercase() && idx > 0 {
+            out.push('_');
+        }
+        out.push(c.to_ascii_uppercase());
+    }
+    out
+}
+
+#[test]
+fn test_header_is_up_to_date() {
+    let header = generate().unwrap();
+
+    // Every exported function is declared
+    let mut lines = ABI_SOURCE.lines();
+    while let Some(line) = lines.next() {
+        if line == "#[no_mangle]" {
+            let name = lines
+                .next()
+                .and_then(|l| l.split_once("fn "))
+                .and_then(|(_, rest)| rest.split_once('('))
+                .map(|(name, _)| name)
+                .expect("should be followed by a function");
+            assert!(
+                header.contains(&format!(" {name}(")),
+                "{name} is not declared in the header"
+            );
+        }
+    }
+
+    assert!(header.contains("typedef struct LoopioEngine LoopioEngine;"));
+    assert!(header.contains("    LOOPIO_STATUS_INVALID_ARGUMENT = 1,"));
+    assert!(header.contains("LoopioEngine* loopio_engine_new(void);"));
+    assert!(header.contains(
+        "LoopioStatus loopio_set_field(LoopioEngine* engine, const char* address, const char* field, const char* value);"
+    ));
+    assert!(header.contains("void loopio_string_free(char* s);"));
+
+    assert_eq!(
+        include_str!("../include/loopio.h"),
+        header,
+        "include/loopio.h is out of date, regenerate it w/ `cargo run -p loopio_ffi --bin loopio-ffi-header`"
+    );
+}
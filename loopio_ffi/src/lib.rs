@@ -0,0 +1,4 @@
+mod abi;
+pub mod header;
+
+pub use abi::*;
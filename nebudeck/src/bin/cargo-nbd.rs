@@ -2,6 +2,7 @@ use std::path::PathBuf;
 
 use clap::Parser;
 use clap::Subcommand;
+use loopio::prelude::runir::prelude::Repr;
 use nebudeck::set_nbd_boot_only;
 use nebudeck::set_nbd_boot_prog;
 use nebudeck::Nebudeck;
@@ -88,15 +89,72 @@ fn main() -> anyhow::Result<()> {
             set_nbd_boot_prog(format!("nbd_boot add-project {project_args} {args}"));
             deck.start_cli()?;
         }
-        Commands::Run => {
+        Commands::Run { dir, address } => {
             // Only initialzies .config/nbd if not already initialized, skips rust project check
             set_nbd_boot_only();
-            let _deck =
-                Nebudeck::init(cli.home.unwrap_or_else(|| std::env::current_dir().unwrap()))?;
+            let deck = Nebudeck::init(
+                dir.clone()
+                    .or(cli.home)
+                    .unwrap_or_else(|| std::env::current_dir().unwrap()),
+            )?;
+
+            // Pass in args after "--"
+            let rest = std::env::args()
+                .skip_while(|a| a != "--")
+                .skip(1)
+                .collect::<Vec<_>>();
+
+            // **Note** If the operation fails, the error is returned from main and the process exits w/ a non-zero status
+            deck.run(address, rest)?;
+        }
+        Commands::Docs { dir, out } => {
+            set_nbd_boot_only();
+            let deck = Nebudeck::init(
+                dir.clone()
+                    .or(cli.home)
+                    .unwrap_or_else(|| std::env::current_dir().unwrap()),
+            )?;
+
+            for page in deck.docs(out)? {
+                println!("{}", page.display());
+            }
+        }
+        Commands::Graph { dir, format, embed } => {
+            set_nbd_boot_only();
+            let home = dir
+                .clone()
+                .or(cli.home)
+                .unwrap_or_else(|| std::env::current_dir().unwrap());
+            let deck = Nebudeck::init(home.clone())?;
+            let graph = deck.graph()?;
+
+            if let Some(embed) = embed {
+                // Mermaid is embedded so that the graph renders next to the runmd it was generated from
+                let path = home.join(embed);
+                let markdown = match std::fs::read_to_string(&path) {
+                    Ok(markdown) => markdown,
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+                    Err(err) => return Err(err.into()),
+                };
+                std::fs::write(&path, graph.embed(&markdown))?;
+                println!("{}", path.display());
+            } else if format == "dot" {
+                print!("{}", graph.to_dot());
+            } else {
+                print!("{}", graph.to_mermaid());
+            }
+        }
+        Commands::Query { dir, query } => {
+            set_nbd_boot_only();
+            let deck = Nebudeck::init(
+                dir.clone()
+                    .or(cli.home)
+                    .unwrap_or_else(|| std::env::current_dir().unwrap()),
+            )?;
 
-            // TODO -- Build and Run?
-            // set_nbd_boot_prog("nbd_boot build");
-            // deck.start_cli()?;
+            for repr in deck.query(query.join(" "))? {
+                println!("{}", describe(&repr));
+            }
         }
     }
 
@@ -157,6 +215,84 @@ enum Commands {
         #[arg(long)]
         dir: Option<PathBuf>,
     },
-    /// Runs the engine in the current context, sets NBD_BOOT_ONLY implicitly.
-    Run,
+    /// Compiles and runs the project engine specified by NBD_HOME/run.md, sets NBD_BOOT_ONLY implicitly.
+    ///
+    /// If an address is set, the address is called w/ args after "--" applied as properties, i.e. `--name value`.
+    ///
+    /// Otherwise, args after "--" are interpreted as a command by the project's controller.
+    ///
+    /// **Note** Exits w/ a non-zero status if the operation fails.
+    ///
+    Run {
+        /// Target directory to run, overrides NBD_HOME.
+        #[arg(long)]
+        dir: Option<PathBuf>,
+        /// Engine address to run, i.e. `engine://hello-world`.
+        address: Option<String>,
+    },
+    /// Generates markdown reference pages for the plugins registered w/ the project engine.
+    ///
+    /// Writes a page for each plugin group, i.e. `builtin.md`, and prints the path of each page.
+    ///
+    Docs {
+        /// Target directory of the project, overrides NBD_HOME.
+        #[arg(long)]
+        dir: Option<PathBuf>,
+        /// Output directory of the reference pages, relative to the project directory.
+        #[arg(long, default_value = "docs/runmd")]
+        out: PathBuf,
+    },
+    /// Prints a graph of how the hosts, operations, sequences, extensions and events of the project relate, sets
+    /// NBD_BOOT_ONLY implicitly.
+    ///
+    Graph {
+        /// Target directory of the project, overrides NBD_HOME.
+        #[arg(long)]
+        dir: Option<PathBuf>,
+        /// Output format of the graph.
+        #[arg(long, default_value = "mermaid", value_parser = ["mermaid", "dot"])]
+        format: String,
+        /// Markdown file to embed a Mermaid graph in instead of printing, relative to the project directory.
+        ///
+        /// A graph previously embedded in the file is replaced.
+        #[arg(long)]
+        embed: Option<PathBuf>,
+    },
+    /// Queries the representations of the package compiled from the project, sets NBD_BOOT_ONLY implicitly.
+    ///
+    /// Queries have the form `<kind> [where <key> <op> <value> (and ...)*]`, i.e. `fields where type = PathBuf` or
+    /// `nodes where host ~ demo and annotations contains notify`.
+    ///
+    /// Prints a line for each matching representation.
+    ///
+    Query {
+        /// Target directory of the project, overrides NBD_HOME.
+        #[arg(long)]
+        dir: Option<PathBuf>,
+        /// Query to run.
+        #[arg(required = true, trailing_var_arg = true)]
+        query: Vec<String>,
+    },
+}
+
+/// Returns a single line describing a representation,
+///
+fn describe(repr: &Repr) -> String {
+    let mut line = repr.to_string();
+
+    if let Some(name) = repr.field_name() {
+        line = format!("{line} .{name}");
+    }
+
+    if let Some(node) = repr.as_node() {
+        if let Some(input) = node.input() {
+            line = format!("{line} {input}");
+        }
+    }
+
+    if let Some(address) = repr.as_host().and_then(|h| h.address()) {
+        line = format!("{line} @ {address}");
+    }
+
+    line
 }
@@ -5,12 +5,14 @@ use std::path::PathBuf;
 use anyhow::anyhow;
 use clap::Subcommand;
 use loopio::action::HostAction;
+use loopio::prelude::runir::prelude::Repr;
 use loopio::prelude::*;
 
 use tracing::error;
 use tracing::{debug, info};
 
 use crate::base64::decode_field_packet;
+use crate::project::compile_project_workspace;
 use crate::terminal::Terminal;
 use crate::terminal::TerminalApp;
 use crate::ControlBus;
@@ -18,6 +20,9 @@ use crate::ControlBus;
 /// Entrypoint for interacting with a nebudeck project workspace,
 ///
 pub struct Nebudeck {
+    /// Home directory (NBD_HOME) of the project workspace,
+    ///
+    home: PathBuf,
     /// Boot workspace,
     ///
     boot: Workspace,
@@ -110,6 +115,7 @@ impl Nebudeck {
         boot.set_name("nbd_boot");
 
         Ok(Self {
+            home: home_dir,
             boot,
             boot_package: OnceCell::new(),
             engine: OnceCell::new(),
@@ -151,7 +157,10 @@ impl Nebudeck {
 
     /// Boots nebudeck in cli mode w/ engine builder config,
     ///
-    pub fn start_cli_with(self, engine_builder: EngineBuilder) -> anyhow::Result<()> {
+    pub fn start_cli_with(self, mut engine_builder: EngineBuilder) -> anyhow::Result<()> {
+        // Arguments matched from the command line can be interpolated into the workspace w/ `${arg:name}`
+        engine_builder.set_command_args(boot_prog());
+
         let mut booted = self.boot_with(engine_builder)?;
 
         let fg = booted.fg.take().unwrap();
@@ -159,6 +168,169 @@ impl Nebudeck {
         Ok(())
     }
 
+    /// Compiles and runs the project workspace,
+    ///
+    /// If an address is set, the address is called and args are applied as properties. Otherwise, control is
+    /// delegated to the project's desktop or terminal controller w/ args as the command to interpret.
+    ///
+    /// Returns an error if the operation fails.
+    ///
+    pub fn run(self, address: Option<String>, args: Vec<String>) -> anyhow::Result<()> {
+        self.run_with(Engine::builder(), address, args)
+    }
+
+    /// Compiles and runs the project workspace w/ engine builder config,
+    ///
+    pub fn run_with(
+        mut self,
+        engine_builder: EngineBuilder,
+        address: Option<String>,
+        args: Vec<String>,
+    ) -> anyhow::Result<()> {
+        self.compile_project()?;
+
+        if let Some(address) = address {
+            let mut booted = self.boot_with(engine_builder)?;
+            let fg = booted.fg.take().unwrap();
+
+            let mut eh = fg.engine_handle();
+            let bg = eh
+                .background()
+                .ok_or(anyhow!("Background work is not enabled"))?;
+
+            let mut bgf = bg
+                .call(&address)
+                .map_err(|err| anyhow!("Could not call {address}: {err}"))?;
+
+            if args.is_empty() {
+                bgf.spawn();
+            } else {
+                bgf.spawn_with_updates(parse_property_args(&args)?);
+            }
+
+            bgf.into_foreground()
+                .map_err(|err| anyhow!("{address} failed: {err}"))?;
+            info!("Finished running {address}");
+            Ok(())
+        } else {
+            #[cfg(feature = "desktop")]
+            #[cfg(feature = "desktop-imgui")]
+            if args.is_empty() {
+                return self.open();
+            }
+
+            // Interpret args as the command w/ the project package
+            let args = shlex::try_join(args.iter().map(|a| a.as_str()))
+                .map_err(|err| anyhow!("Could not quote command arguments, {err}"))?;
+            set_nbd_boot_prog(format!("{} {args}", self.boot.name));
+
+            self.start_cli_with(engine_builder)
+        }
+    }
+
+    /// Compiles the project workspace and runs a query over the representations of the compiled package,
+    ///
+    /// **Example**
+    ///
+    /// ```rs no_run
+    /// // All nodes in host `demo` whose annotations contain `notify`
+    /// let nodes = deck.query("nodes where host ~ demo and annotations contains notify")?;
+    /// ```
+    ///
+    pub fn query(self, query: impl AsRef<str>) -> anyhow::Result<Vec<Repr>> {
+        self.query_with(Engine::builder(), query)
+    }
+
+    /// Compiles the project workspace w/ engine builder config and runs a query over the compiled package,
+    ///
+    pub fn query_with(
+        mut self,
+        engine_builder: EngineBuilder,
+        query: impl AsRef<str>,
+    ) -> anyhow::Result<Vec<Repr>> {
+        self.compile_project()?;
+
+        let mut booted = self.boot_with(engine_builder)?;
+        let package = booted.boot_package.take().expect("should be compiled");
+
+        package.query(query)
+    }
+
+    /// Compiles the project workspace and returns a graph of how its hosts, operations, sequences, extensions and
+    /// events relate,
+    ///
+    pub fn graph(self) -> anyhow::Result<WorkspaceGraph> {
+        self.graph_with(Engine::builder())
+    }
+
+    /// Compiles the project workspace w/ engine builder config and returns a graph of the compiled package,
+    ///
+    pub fn graph_with(mut self, engine_builder: EngineBuilder) -> anyhow::Result<WorkspaceGraph> {
+        self.compile_project()?;
+
+        let mut booted = self.boot_with(engine_builder)?;
+        let package = booted.boot_package.take().expect("should be compiled");
+        let fg = booted.fg.take().expect("should be booted");
+
+        fg.runtime()
+            .block_on(WorkspaceGraph::from_package(&package))
+    }
+
+    /// Writes markdown reference pages of the plugins registered w/ the project engine to a directory,
+    ///
+    /// Each plugin group is written to `<group>.md`. Returns the paths of the pages that were written.
+    ///
+    pub fn docs(&self, out_dir: impl AsRef<Path>) -> anyhow::Result<Vec<PathBuf>> {
+        self.docs_with(Engine::builder(), out_dir)
+    }
+
+    /// Writes markdown reference pages of the plugins registered w/ an engine builder to a directory,
+    ///
+    pub fn docs_with(
+        &self,
+        mut engine_builder: EngineBuilder,
+        out_dir: impl AsRef<Path>,
+    ) -> anyhow::Result<Vec<PathBuf>> {
+        engine_builder.enable::<ProjectTypes>();
+        let engine = engine_builder.build();
+
+        let out_dir = self.home.join(out_dir.as_ref());
+        std::fs::create_dir_all(&out_dir)?;
+
+        let mut pages = vec![];
+        for (group, page) in engine.reference_docs() {
+            let path = out_dir.join(format!("{group}.md"));
+            info!("Writing {:?}", path);
+            std::fs::write(&path, page)?;
+            pages.push(path);
+        }
+
+        Ok(pages)
+    }
+
+    /// Compiles the project workspace and sets it as the boot workspace,
+    ///
+    fn compile_project(&mut self) -> anyhow::Result<()> {
+        // **Note** A foreground engine cannot be created inside of another tokio runtime
+        let mut workspace = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?
+            .block_on(compile_project_workspace(&self.home))?;
+
+        if workspace.name.is_empty() {
+            workspace.set_name(
+                self.home
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("nbd_project"),
+            );
+        }
+
+        debug!("Compiled project workspace {:#?}", workspace);
+        self.boot = workspace;
+        Ok(())
+    }
+
     /// Boots nebudeck with engine builder
     ///
     fn boot_with(self, mut engine_builder: EngineBuilder) -> anyhow::Result<Self> {
@@ -180,6 +352,29 @@ impl Nebudeck {
     }
 }
 
+/// Parses `--name value` and `--name=value` args into property updates,
+///
+fn parse_property_args(args: &[String]) -> anyhow::Result<FrameUpdates> {
+    let mut updates = FrameUpdates::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let name = arg
+            .strip_prefix("--")
+            .ok_or(anyhow!("Expected an argument name w/ `--`, found `{arg}`"))?;
+
+        if let Some((name, value)) = name.split_once('=') {
+            updates.set_property(name, value);
+        } else if let Some(value) = args.next() {
+            updates.set_property(name, value);
+        } else {
+            Err(anyhow!("Missing value for argument `{arg}`"))?;
+        }
+    }
+
+    Ok(updates)
+}
+
 /// Restrict init to initialized Rust packages,
 ///
 /// **Note** If NBD_BOOT_ONLY is set, this check will be skipped
@@ -228,13 +423,7 @@ impl TerminalApp for Nebudeck {
         let name = command.get_name().to_string();
         debug!("Found host `{}`", name);
 
-        let matches = if let Ok(prog) = std::env::var(NBD_BOOT_PROG) {
-            let prog = shlex::split(&prog).expect("should be valid cli arguments");
-            info!("`NBD_PROG` env var is set, interpreting command {:?}", prog);
-            command.clone().get_matches_from(prog)
-        } else {
-            command.clone().get_matches()
-        };
+        let matches = command.clone().get_matches_from(boot_prog());
 
         let mut frame_updates = FrameUpdates::default();
 
@@ -325,7 +514,8 @@ impl TerminalApp for Nebudeck {
                         }
 
                         // TODO: When Repl mode is added, skip this part
-                        bgf.into_foreground().expect("should be able to call");
+                        bgf.into_foreground()
+                            .map_err(|err| anyhow!("Could not process command: {err}"))?;
                     }
                     Err(err) => Err(anyhow!("Could not process command: {err}"))?,
                 }
@@ -351,6 +541,20 @@ const NBD_BOOT_PROG: &str = "NBD_BOOT_PROG";
 
 const NBD_BOOT_ONLY: &str = "NBD_BOOT_ONLY";
 
+/// Returns the command line to interpret, NBD_BOOT_PROG if set or the arguments of the current process,
+///
+fn boot_prog() -> Vec<String> {
+    if let Ok(prog) = std::env::var(NBD_BOOT_PROG) {
+        let prog = shlex::split(&prog).expect("should be valid cli arguments");
+        info!("`NBD_PROG` env var is set, interpreting command {:?}", prog);
+        prog
+    } else {
+        std::env::args_os()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect()
+    }
+}
+
 /// Sets NBD_BOOT_PROG w/ arguments to use w/ nbd_boot,
 ///
 pub fn set_nbd_boot_prog(prog: impl AsRef<str>) {
@@ -473,3 +677,99 @@ fn test_init() {
     deck.start_cli().expect("should be able to process command");
     ()
 }
+
+#[test]
+fn test_parse_property_args() {
+    let args = ["--name", "test", "--title=hello world"].map(String::from);
+    assert!(parse_property_args(&args).is_ok());
+
+    assert!(parse_property_args(&["name".to_string()]).is_err());
+    assert!(parse_property_args(&["--name".to_string()]).is_err());
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn test_run() {
+    let tmp = std::env::temp_dir().join("test_nbd_run");
+    if tmp.exists() {
+        std::fs::remove_dir_all(&tmp).unwrap()
+    }
+    std::fs::create_dir_all(tmp.join("lib/runmd")).unwrap();
+    std::fs::write(tmp.join("Cargo.toml"), "[package]").unwrap();
+    std::fs::write(
+        tmp.join("lib/runmd/hello.md"),
+        r#"
+```runmd
++ .operation nbd-run-hello
+<loopio.std.io.println> hello world
+```
+"#,
+    )
+    .unwrap();
+
+    let deck = Nebudeck::init(tmp.clone()).unwrap();
+    deck.run(Some("engine://nbd-run-hello".to_string()), vec![])
+        .expect("should be able to run operation");
+
+    let deck = Nebudeck::init(tmp).unwrap();
+    assert!(deck
+        .run(Some("engine://nbd-run-missing".to_string()), vec![])
+        .is_err());
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn test_start_cli_args() {
+    let tmp = std::env::temp_dir().join("test_nbd_cli_args");
+    if tmp.exists() {
+        std::fs::remove_dir_all(&tmp).unwrap()
+    }
+    std::fs::create_dir_all(tmp.join("lib/runmd")).unwrap();
+    std::fs::write(tmp.join("Cargo.toml"), "[package]").unwrap();
+    std::fs::write(
+        tmp.join("lib/runmd/touch.md"),
+        r#"
+```runmd
++ .operation nbd-args
+
+# -- Touches a file
+<file/builtin.process> touch
+|# arg.file = default.txt
+: .arg ${arg:file}
+```
+"#,
+    )
+    .unwrap();
+
+    let file = tmp.join("from_arg.txt");
+    let deck = Nebudeck::init(tmp.clone()).unwrap();
+    let args = ["nbd-args", "file", "--file", file.to_str().unwrap()].map(String::from);
+    deck.run(None, args.to_vec())
+        .expect("should be able to process command");
+
+    assert!(file.exists());
+
+    // Arguments that cannot be quoted are rejected instead of being passed incorrectly
+    let deck = Nebudeck::init(tmp.clone()).unwrap();
+    let args = ["nbd-args", "file", "--file", "from\0arg.txt"].map(String::from);
+    let err = deck.run(None, args.to_vec()).unwrap_err();
+    assert!(err.to_string().contains("Could not quote"), "{err}");
+}
+
+#[test]
+fn test_docs() {
+    let tmp = std::env::temp_dir().join("test_nbd_docs");
+    if tmp.exists() {
+        std::fs::remove_dir_all(&tmp).unwrap()
+    }
+    std::fs::create_dir_all(&tmp).unwrap();
+    std::fs::write(tmp.join("Cargo.toml"), "[package]").unwrap();
+
+    let deck = Nebudeck::init(tmp.clone()).unwrap();
+    let pages = deck.docs("docs").unwrap();
+    assert!(pages.contains(&tmp.canonicalize().unwrap().join("docs/builtin.md")));
+
+    let builtin = std::fs::read_to_string(tmp.join("docs/builtin.md")).unwrap();
+    assert!(builtin.contains("## builtin.process"));
+    assert!(builtin.contains("<builtin.process> example"));
+}
@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::path::PathBuf;
 
 use anyhow::anyhow;
@@ -124,10 +125,9 @@ async fn prepare_workspace(tc: &mut ThunkContext) -> anyhow::Result<()> {
 //     Ok(())
 // }
 
-#[tokio::test]
-async fn test_project() {
-    // TODO: Add Nebudeck struct type to facilitate this
-    let mut project = loopio::prelude::Project::<Shared>::new(Shared::default());
+/// Adds the `project` node plugin used to load a nebudeck project manifest,
+///
+pub(crate) fn add_project_node_plugin(project: &mut loopio::prelude::Project<Shared>) {
     project.add_node_plugin("project", |input, _, parser| {
         Project::parse(parser, input.unwrap_or(""));
 
@@ -153,6 +153,72 @@ async fn test_project() {
         parser.parsed_node.attributes.push(nk.transmute());
         parser.push_link_recv::<Project>();
     });
+}
+
+/// Compiles the project workspace found in a nebudeck home directory,
+///
+/// Includes all runmd files under `lib/runmd/` and the files/sources from each `.project` declared in `run.md`.
+///
+pub(crate) async fn compile_project_workspace(
+    home_dir: &Path,
+) -> anyhow::Result<loopio::prelude::Workspace> {
+    let manifest = home_dir.join("run.md");
+    if !manifest.exists() {
+        Err(anyhow!(
+            "Could not find project manifest {:?}, run `cargo nbd init` to create one",
+            manifest
+        ))?;
+    }
+
+    let lib_runmd = home_dir.join("lib/runmd");
+    let mut workspace = if lib_runmd.is_dir() {
+        Dir(lib_runmd).workspace()
+    } else {
+        EmptyWorkspace.workspace()
+    };
+
+    // Compile the manifest to find each declared project
+    let mut project = loopio::prelude::Project::<Shared>::new(Shared::default());
+    add_project_node_plugin(&mut project);
+
+    let mut manifest_workspace = EmptyWorkspace.workspace();
+    manifest_workspace.add_local(manifest);
+
+    let compiled = manifest_workspace.compile(project).await?;
+    let package = compiled
+        .project
+        .ok_or(anyhow!("Project manifest did not compile a project"))?
+        .package()
+        .await?;
+
+    for m in package.search("*") {
+        if let Ok(tc) = m.program.context() {
+            if let Some(result) = tc.call().await? {
+                let prepared = result
+                    .node
+                    .storage
+                    .write()
+                    .await
+                    .root()
+                    .take::<loopio::prelude::Workspace>();
+
+                if let Some(prepared) = prepared {
+                    if workspace.name.is_empty() {
+                        workspace.set_name(prepared.name.clone());
+                    }
+                    workspace.sources.extend(prepared.sources.iter().cloned());
+                }
+            }
+        }
+    }
+
+    Ok(workspace)
+}
+
+#[tokio::test]
+async fn test_project() {
+    let mut project = loopio::prelude::Project::<Shared>::new(Shared::default());
+    add_project_node_plugin(&mut project);
 
     let mut test_workspace = EmptyWorkspace.workspace();
     test_workspace.add_buffer(
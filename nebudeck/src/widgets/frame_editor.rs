@@ -7,6 +7,7 @@ use imgui::TableColumnSetup;
 use imgui::TableFlags;
 use imgui::TreeNodeFlags;
 use loopio::prelude::*;
+use tracing::error;
 use tracing::info;
 use tracing::trace;
 
@@ -95,6 +96,7 @@ async fn enable_frame_editor(tc: &mut ThunkContext) -> anyhow::Result<()> {
                         defined_properties_section(tc.get().unwrap(), ui);
 
                         let mut queue_update = false;
+                        let mut queue_persist = false;
                         if let Some(queued) = tc.get().unwrap().cached_ref::<FrameUpdates>() {
                             let mut render = vec![];
                             for (idx, q) in queued.frame.fields.iter().enumerate() {
@@ -109,6 +111,7 @@ async fn enable_frame_editor(tc: &mut ThunkContext) -> anyhow::Result<()> {
                                     ..
                                 } = q;
 
+                                let secret = is_secret_field(attr, q);
                                 render.push(move || {
                                     ui.text(format!("Field Packet {idx}:"));
                                     ui.label_text("field_name", field_name);
@@ -121,7 +124,10 @@ async fn enable_frame_editor(tc: &mut ThunkContext) -> anyhow::Result<()> {
                                         format!("{:?}", attribute_hash),
                                     );
 
-                                    if let Some(bin) = wire_data {
+                                    // Only the reference of a secret is sent w/ a packet, but it is still not shown
+                                    if secret {
+                                        ui.text("Secret value is redacted");
+                                    } else if let Some(bin) = wire_data {
                                         ui.text("Has binary data");
                                         if ui.button("Deserialize") {
                                             if let Ok(s) = bincode::deserialize::<String>(&bin) {
@@ -137,6 +143,13 @@ async fn enable_frame_editor(tc: &mut ThunkContext) -> anyhow::Result<()> {
                                     queue_update = true;
                                 }
 
+                                if !render.is_empty() {
+                                    ui.same_line();
+                                    if ui.button("Persist") {
+                                        queue_persist = true;
+                                    }
+                                }
+
                                 for r in render.drain(..) {
                                     r();
                                 }
@@ -230,6 +243,35 @@ async fn enable_frame_editor(tc: &mut ThunkContext) -> anyhow::Result<()> {
                             }
                         }
 
+                        // Writes the queued changes back to the runmd sources that defined the fields
+                        if queue_persist {
+                            let tc = tc.get().unwrap();
+                            let mut persist = Persist::new();
+                            let edited = match (
+                                tc.cached::<ParsedNode>(),
+                                tc.cached_ref::<FrameUpdates>(),
+                            ) {
+                                (Some(node), Some(queued)) => {
+                                    persist.edit_frame(&node, &queued.frame)
+                                }
+                                _ => Err(anyhow::anyhow!("Parsed node is not available")),
+                            };
+
+                            match edited {
+                                Ok(_) => {
+                                    tc.spawn(move |tc| async move {
+                                        for path in persist.write().await? {
+                                            info!("Persisted changes to {:?}", path);
+                                        }
+                                        Ok(tc)
+                                    });
+                                }
+                                Err(err) => {
+                                    error!("Could not persist changes, {err}");
+                                }
+                            }
+                        }
+
                         if queue_update {
                             trace!("Queued frame update");
                             let rk = tc.get().unwrap().cached::<ResourceKey<Attribute>>();
@@ -351,6 +393,7 @@ impl EditorWidgetTable {
 
                 // Iterate through each discovered packet,
                 for p in field.iter() {
+                    let secret = is_secret_field(tc.attribute, p);
                     if let Some((f, mut field)) =
                         tc.fetch_mut_kv::<(String, String, FieldPacket)>(&p.field_name)
                     {
@@ -359,8 +402,10 @@ impl EditorWidgetTable {
                             ui.text(d);
                         }
 
-                        // Enables text editing input
-                        ui.input_text(&widget.title, &mut field.1).build();
+                        // Enables text editing input, the reference of a secret is masked
+                        ui.input_text(&widget.title, &mut field.1)
+                            .password(secret)
+                            .build();
 
                         // Modification actions
                         if field.0 != field.1 {
@@ -525,6 +570,18 @@ impl FieldWidget {
     }
 }
 
+/// Returns true if the field of a packet is a secret field of the receiver,
+///
+fn is_secret_field<T: Send + Sync + 'static>(rk: ResourceKey<T>, packet: &FieldPacket) -> bool {
+    rk.recv().and_then(|r| r.fields()).is_some_and(|fields| {
+        fields.iter().filter_map(|f| f.as_field()).any(|f| {
+            f.owner_name() == Some(packet.owner_name.as_str())
+                && f.offset() == Some(packet.field_offset)
+                && f.is_secret()
+        })
+    })
+}
+
 fn defined_properties_section(tc: &ThunkContext, ui: &imgui::Ui) {
     let mut render_properties = vec![];
     let rk = tc.attribute;
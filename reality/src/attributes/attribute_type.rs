@@ -18,6 +18,9 @@ use crate::ResourceKey;
 use crate::Shared;
 
 use super::attribute::Property;
+use super::diagnostic::parse_error_message;
+use super::diagnostic::Diagnostic;
+use super::parser::NestedScope;
 use super::visit::Field;
 use super::visit::FieldMut;
 use super::AttributeParser;
@@ -196,6 +199,26 @@ impl AttributeTypeParser<Shared> {
         parser.field = Some(FieldLevel::new::<IDX, Owner>());
         parser
     }
+
+    /// Returns an attribute parser for a nested field,
+    ///
+    pub fn parseable_nested_field<const IDX: usize, Owner>() -> Self
+    where
+        Owner: Recv + OnParseField<IDX> + Send + Sync + 'static,
+        Owner::ParseType: AttributeType<Shared>,
+    {
+        let mut resource = ResourceLevel::new::<Owner::ProjectedType>();
+        if std::any::TypeId::of::<Owner::ParseType>()
+            != std::any::TypeId::of::<Owner::ProjectedType>()
+        {
+            resource.set_parse_type::<Owner::ParseType>();
+        }
+        resource.set_ffi::<Owner::FFIType>();
+
+        let mut parser = Self::new::<ParsableNestedField<IDX, Owner>>(resource);
+        parser.field = Some(FieldLevel::new::<IDX, Owner>());
+        parser
+    }
 }
 
 impl<S: StorageTarget> Clone for AttributeTypeParser<S> {
@@ -263,6 +286,20 @@ where
     _owner: PhantomData<Owner>,
 }
 
+/// Parseable nested field,
+///
+/// Applies the nested type's parse fn and then enters a scope where properties one level deeper define fields of the nested type.
+/// When the scope is left, the nested value is transferred to the owning type,
+///
+#[derive(Default)]
+pub struct ParsableNestedField<const FIELD_OFFSET: usize, Owner>
+where
+    Owner: OnParseField<FIELD_OFFSET> + Send + Sync + 'static,
+    Owner::ParseType: AttributeType<Shared> + Send + Sync + 'static,
+{
+    _owner: PhantomData<Owner>,
+}
+
 impl<const FIELD_OFFSET: usize, Owner> Recv for ParsableField<FIELD_OFFSET, Owner>
 where
     Owner: Recv + OnParseField<FIELD_OFFSET> + Send + Sync + 'static,
@@ -325,6 +362,7 @@ where
             .unwrap_or(ResourceKey::root());
 
         let mut properties = None;
+        let mut violation = None;
         match (parser.storage_mut(), parsed) {
             (
                 Some(mut storage),
@@ -333,12 +371,17 @@ where
                     error: None,
                     ..
                 },
-            ) => {
-                borrow_mut!(storage, Owner, key, |owner| => {
-                    let property = owner.on_parse(value, input, tag.as_ref());
-                    properties = Some((property, Owner::empty_packet()));
-                });
-            }
+            ) => match Owner::validate(&value, input) {
+                Ok(()) => {
+                    borrow_mut!(storage, Owner, key, |owner| => {
+                        let property = owner.on_parse(value, input, tag.as_ref());
+                        properties = Some((property, Owner::empty_packet()));
+                    });
+                }
+                Err(err) => {
+                    violation = Some(err.to_string());
+                }
+            },
             (
                 Some(storage),
                 ParsableField {
@@ -358,6 +401,10 @@ where
                     label.try_into().unwrap_or(ResourceKey::root()),
                 ) {
                     storage.lazy_callback(cb, error)
+                } else {
+                    violation = Some(parse_error_message::<Owner::ParseType>(
+                        Owner::describe_parse_error(&error),
+                    ));
                 }
             }
             _ => {}
@@ -366,6 +413,19 @@ where
         if let Some((prop, _)) = properties.take() {
             parser.parsed_node.define_property(prop);
         }
+
+        // Unhandled parse errors and validation failures are reported as diagnostics of the source
+        if let Some(message) = violation {
+            let diagnostic = Diagnostic::new(
+                std::any::type_name::<Owner>(),
+                Owner::field_name(),
+                input,
+                message,
+            )
+            .with_node(parser.nodes.last());
+
+            parser.report(diagnostic);
+        }
     }
 }
 
@@ -507,6 +567,124 @@ where
     }
 }
 
+impl<const FIELD_OFFSET: usize, Owner> runir::prelude::Recv
+    for ParsableNestedField<FIELD_OFFSET, Owner>
+where
+    Owner: Recv + OnParseField<FIELD_OFFSET> + Send + Sync + 'static,
+    Owner::ParseType: AttributeType<Shared> + Send + Sync + 'static,
+{
+    fn symbol() -> &'static str {
+        Owner::field_name()
+    }
+
+    /// Links a node level to a receiver and returns a new Repr,
+    ///
+    fn link_recv(node: NodeLevel, fields: Vec<Repr>) -> anyhow::Result<Repr>
+    where
+        Self: Sized + Send + Sync + 'static,
+    {
+        Owner::link_recv(node, fields)
+    }
+
+    /// Links a node level to a field level and returns a new Repr,
+    ///
+    fn link_field(
+        resource: ResourceLevel,
+        field: FieldLevel,
+        node: NodeLevel,
+    ) -> anyhow::Result<Repr> {
+        Owner::link_field(resource, field, node)
+    }
+}
+
+impl<const FIELD_OFFSET: usize, Owner> AttributeType<Shared>
+    for ParsableNestedField<FIELD_OFFSET, Owner>
+where
+    Owner: Recv + OnParseField<FIELD_OFFSET> + Send + Sync + 'static,
+    Owner::ParseType: AttributeType<Shared> + Send + Sync + 'static,
+{
+    fn parse(parser: &mut AttributeParser<Shared>, content: impl AsRef<str>) {
+        let input = content.as_ref();
+
+        // Fields of the enclosing scope are restored when the scope is left
+        let attribute_types = std::mem::take(&mut parser.attribute_types);
+        let required = parser.required.len();
+        let parsed = parser.parsed_node.len();
+
+        // Adds the nested type's fields to the now empty table of attribute types
+        Owner::ParseType::parse(parser, input);
+
+        // If the input could not be parsed, properties defined in the scope are ignored
+        let nested = if parser.parsed_node.len() > parsed {
+            parser.parsed_node.last().copied()
+        } else {
+            None
+        };
+
+        let scope = NestedScope {
+            field: Owner::field_name(),
+            input: input.to_string(),
+            tag: parser.tag().cloned(),
+            nested,
+            attribute_types,
+            required,
+            repr: None,
+            leave: Self::leave,
+        };
+        parser.enter_scope(scope);
+    }
+}
+
+impl<const FIELD_OFFSET: usize, Owner> ParsableNestedField<FIELD_OFFSET, Owner>
+where
+    Owner: Recv + OnParseField<FIELD_OFFSET> + Send + Sync + 'static,
+    Owner::ParseType: AttributeType<Shared> + Send + Sync + 'static,
+{
+    /// Transfers the nested value to the owning type when the scope is left,
+    ///
+    fn leave(parser: &mut AttributeParser<Shared>, scope: &NestedScope<Shared>) {
+        let Some(nested) = scope.nested else {
+            return;
+        };
+
+        // The nested value is owned by the field, so it should not be treated as an attribute of the node
+        parser
+            .parsed_node
+            .attributes
+            .retain(|a| a.key() != nested.key());
+
+        let owner = parser
+            .parsed_node
+            .last()
+            .copied()
+            .unwrap_or(ResourceKey::root());
+        for (_, rk) in parser.parsed_node.paths.iter_mut() {
+            if rk.key() == nested.key() {
+                *rk = owner;
+            }
+        }
+
+        let key = owner.transmute::<Owner>();
+        let mut property = None;
+        if let Some(mut storage) = parser.storage_mut() {
+            let resource = storage.take_resource::<Owner::ParseType>(nested.transmute());
+
+            if let Some(resource) = resource {
+                borrow_mut!(storage, Owner, key, |owner| => {
+                    property = Some(owner.on_parse(*resource, &scope.input, scope.tag.as_ref()));
+                });
+            }
+        }
+
+        if let Some(mut property) = property {
+            if let Some(repr) = scope.repr {
+                property.set_repr(repr);
+            }
+            parser.parsed_node.define_property(property);
+        }
+    }
+}
+
 /// Helper trait for constructing concrete callback types,
 ///
 pub trait Handler<S: StorageTarget, Arg: Send + Sync + 'static> {
@@ -525,6 +703,22 @@ pub trait OnParseField<const FIELD_OFFSET: usize>
 where
     Self: runir::prelude::Field<FIELD_OFFSET> + Send + Sync + Sized + 'static,
 {
+    /// Function called to validate a parsed value before it is applied to the field,
+    ///
+    /// Returns an error describing the violation if the value should not be applied.
+    ///
+    fn validate(_value: &Self::ParseType, _input: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Function called to describe an error returned when parsing the field fails,
+    ///
+    /// Returns None if the error cannot be described.
+    ///
+    fn describe_parse_error(_err: &<Self::ParseType as FromStr>::Err) -> Option<String> {
+        None
+    }
+
     /// Function called when a value is parsed correctly,
     ///
     fn on_parse(
@@ -218,6 +218,7 @@ impl<const DELIM: char, T: FromStr + Send + Sync + 'static> Iterator for Delimit
 //     }
 // }
 
+#[cfg(test)]
 #[allow(unused)]
 mod tests {
     use runir::prelude::CrcInterner;
@@ -226,18 +227,11 @@ mod tests {
 
     use crate::prelude::*;
 
+    use crate::project::fixture::test_project;
     use crate::Decorated;
 
     #[tokio::test]
     async fn test_decorated() {
-        struct PsuedoTest;
-
-        impl Recv for PsuedoTest {
-            fn symbol() -> &'static str {
-                "test"
-            }
-        }
-
         #[derive(Reality, Clone, Default, Debug)]
         #[reality(call=test, plugin)]
         struct DecoratedTest {
@@ -256,11 +250,8 @@ mod tests {
             Ok(())
         }
 
-        let mut project = Project::new(Shared::default());
-
-        project.add_node_plugin("test", |_, _, parser| {
+        let project = test_project(|parser| {
             parser.with_object_type::<Thunk<DecoratedTest>>();
-            parser.push_link_recv::<PsuedoTest>();
         });
 
         let project = project
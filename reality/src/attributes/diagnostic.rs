@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::anyhow;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use runir::prelude::NodeLevel;
+use runir::prelude::SourceSpan;
+
+/// Diagnostic reported while parsing a field from runmd source,
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Relative path of the source the field was parsed from,
+    ///
+    pub relative: Option<PathBuf>,
+    /// Position in source of the offending line,
+    ///
+    pub span: Option<SourceSpan>,
+    /// Line and column of the span, set once the diagnostic is located in source,
+    ///
+    pub location: Option<(usize, usize)>,
+    /// Type name of the owner of the field,
+    ///
+    pub owner: String,
+    /// Name of the field, empty if the owner itself could not be parsed,
+    ///
+    pub field: String,
+    /// Input that was being parsed,
+    ///
+    pub input: String,
+    /// Description of the violation,
+    ///
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Returns a new diagnostic for a field,
+    ///
+    pub fn new(
+        owner: impl Into<String>,
+        field: impl Into<String>,
+        input: impl Into<String>,
+        message: impl Display,
+    ) -> Self {
+        Self {
+            owner: owner.into(),
+            field: field.into(),
+            input: input.into(),
+            message: message.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the diagnostic w/ the span and relative path of the node level that was being parsed,
+    ///
+    pub fn with_node(mut self, node: Option<&NodeLevel>) -> Self {
+        if let Some(node) = node {
+            self.span = node.source_span().map(|s| s.as_ref().clone());
+            self.relative = node.source_relative().map(|r| r.as_ref().clone());
+        }
+        self
+    }
+
+    /// Sets the line and column of the diagnostic from the source the span refers to,
+    ///
+    pub fn locate(&mut self, source: &str) {
+        if let Some(span) = self.span.as_ref() {
+            let start = span.start.min(source.len());
+            let preceding = &source[..start];
+            let line = preceding.matches('\n').count() + 1;
+            let column = start - preceding.rfind('\n').map(|i| i + 1).unwrap_or(0) + 1;
+            self.location = Some((line, column));
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(relative) = self.relative.as_ref() {
+            write!(f, "{}", relative.display())?;
+        }
+
+        match (self.location, self.span.as_ref()) {
+            (Some((line, column)), _) => write!(f, ":{line}:{column}")?,
+            (None, Some(span)) => write!(f, ":{}..{}", span.start, span.end)?,
+            _ => {}
+        }
+
+        write!(f, " -- {}", self.owner)?;
+
+        if !self.field.is_empty() {
+            write!(f, ".{}", self.field)?;
+        }
+
+        write!(f, ": {}", self.message)?;
+
+        if !self.input.is_empty() {
+            write!(f, ", input: `{}`", self.input)?;
+        }
+        Ok(())
+    }
+}
+
+/// Wraps an error so that derived code can describe it w/o requiring the error to implement Display,
+///
+/// When `E` implements Display, `(&DescribeError(&err)).describe()` resolves to `DescribeDisplayError` and returns the
+/// error's message, otherwise it resolves to `DescribeAnyError` and returns None.
+///
+#[doc(hidden)]
+pub struct DescribeError<'a, E>(pub &'a E);
+
+/// Describes errors that implement Display,
+///
+#[doc(hidden)]
+pub trait DescribeDisplayError {
+    fn describe(&self) -> Option<String>;
+}
+
+impl<E: Display> DescribeDisplayError for DescribeError<'_, E> {
+    fn describe(&self) -> Option<String> {
+        Some(self.0.to_string())
+    }
+}
+
+/// Fallback for errors that do not implement Display,
+///
+#[doc(hidden)]
+pub trait DescribeAnyError {
+    fn describe(&self) -> Option<String>;
+}
+
+impl<E> DescribeAnyError for &DescribeError<'_, E> {
+    fn describe(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Returns the message of a diagnostic for input that could not be parsed as T,
+///
+pub(crate) fn parse_error_message<T>(description: Option<String>) -> String {
+    match description {
+        Some(description) => format!(
+            "could not parse as {}, {description}",
+            std::any::type_name::<T>()
+        ),
+        None => format!("could not parse as {}", std::any::type_name::<T>()),
+    }
+}
+
+/// Collection of diagnostics reported while compiling runmd source,
+///
+/// Returned as the error of a workspace compilation so that callers can downcast and inspect each diagnostic.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Diagnostics {
+    /// Diagnostics in the order they were reported,
+    ///
+    pub items: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    /// Adds a diagnostic to the collection,
+    ///
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.items.push(diagnostic);
+    }
+
+    /// Returns true if no diagnostics have been reported,
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns an iterator over reported diagnostics,
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.items.iter()
+    }
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Compiling runmd reported {} diagnostic(s)",
+            self.items.len()
+        )?;
+        for diagnostic in self.items.iter() {
+            writeln!(f, "  {diagnostic}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostics {}
+
+/// Cache of patterns compiled by `validate_regex`,
+///
+static PATTERNS: Lazy<Mutex<BTreeMap<&'static str, Regex>>> = Lazy::new(Default::default);
+
+/// Validates that input matches a regex pattern, used by fields w/ `#[reality(regex = "..")]`,
+///
+/// **Note** The pattern is not anchored, use `^` and `$` to match the entire input.
+///
+pub fn validate_regex(pattern: &'static str, input: &str) -> anyhow::Result<()> {
+    let mut patterns = PATTERNS.lock().unwrap_or_else(|e| e.into_inner());
+
+    if !patterns.contains_key(pattern) {
+        let regex =
+            Regex::new(pattern).map_err(|err| anyhow!("invalid pattern `{pattern}`, {err}"))?;
+        patterns.insert(pattern, regex);
+    }
+
+    match patterns.get(pattern) {
+        Some(regex) if regex.is_match(input) => Ok(()),
+        _ => Err(anyhow!("value does not match pattern `{pattern}`")),
+    }
+}
+
+#[test]
+fn test_diagnostic_locate() {
+    let source = "```runmd\n+ .operation a\n: .count 11\n```";
+
+    let mut diagnostic = Diagnostic::new("Test", "count", "11", "out of range");
+    diagnostic.span = Some(24..25);
+    diagnostic.relative = Some(PathBuf::from("test.md"));
+    diagnostic.locate(source);
+
+    assert_eq!(Some((3, 1)), diagnostic.location);
+    assert_eq!(
+        "test.md:3:1 -- Test.count: out of range, input: `11`",
+        diagnostic.to_string()
+    );
+}
+
+#[test]
+fn test_validate_regex() {
+    assert!(validate_regex("^[a-z]+$", "hello").is_ok());
+    assert!(validate_regex("^[a-z]+$", "Hello").is_err());
+    assert!(validate_regex("[", "hello")
+        .unwrap_err()
+        .to_string()
+        .starts_with("invalid pattern"));
+}
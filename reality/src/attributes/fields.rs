@@ -137,11 +137,11 @@ pub enum FieldCondition {
     ///
     #[default]
     Default,
-    /// (TODO) -- To implement this, need to somehow map the defined from the ParsedBlock back to the virtual ref.
-    ///
     /// Initial means that the field has been configured by a runmd instruction and is in it's
     /// initial state.
     ///
+    /// This condition is set by the wire server for each field defined by a property of the parsed node.
+    ///
     Initial,
     /// Pending means that the value has changed from the initial value but hasn't been committed
     /// by the owner yet.
@@ -398,6 +398,20 @@ where
         !changed
     }
 
+    /// Sets the condition of the field,
+    ///
+    #[inline]
+    pub fn set_condition(&mut self, condition: FieldCondition) {
+        self.condition = condition;
+    }
+
+    /// Returns the current condition of the field,
+    ///
+    #[inline]
+    pub fn condition(&self) -> FieldCondition {
+        self.condition
+    }
+
     /// Returns true if the field condition is currently FieldCondition::Committed,
     ///
     #[inline]
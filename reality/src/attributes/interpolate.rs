@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use anyhow::anyhow;
+
+/// Variables that can be interpolated into the input of a runmd line,
+///
+/// | Syntax         | Value                                                                      |
+/// | -------------- | -------------------------------------------------------------------------- |
+/// | `${env:NAME}`  | Environment variable of the current process                                |
+/// | `${prop:name}` | Input of a property defined earlier in the same block                      |
+/// | `${arg:name}`  | CLI argument, or the default value of an `arg.name` annotation in the node |
+///
+/// **Note** Each `$$` before a `{` escapes a `$`, i.e. `$${env:HOME}` is parsed as the literal `${env:HOME}` and
+/// `$$${env:HOME}` is parsed as `$` followed by the value of `HOME`.
+///
+#[derive(Clone, Debug, Default)]
+pub struct Variables {
+    /// Inputs of properties defined in the current block, shared by each parser of the block,
+    ///
+    props: Arc<Mutex<BTreeMap<String, String>>>,
+    /// CLI arguments set on the workspace,
+    ///
+    args: Arc<BTreeMap<String, String>>,
+    /// Default values of CLI arguments declared by `arg.*` annotations,
+    ///
+    arg_defaults: BTreeMap<String, String>,
+}
+
+impl Variables {
+    /// Returns new variables for a block w/ CLI arguments,
+    ///
+    pub fn new(args: Arc<BTreeMap<String, String>>) -> Self {
+        Self {
+            args,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the input of a property defined in the current block,
+    ///
+    pub fn set_prop(&self, name: impl Into<String>, input: impl Into<String>) {
+        if let Ok(mut props) = self.props.lock() {
+            props.insert(name.into(), input.into());
+        }
+    }
+
+    /// Sets the default values of CLI arguments from `arg.*` annotations,
+    ///
+    pub fn set_arg_defaults(&mut self, annotations: &BTreeMap<String, String>) {
+        for (k, v) in annotations.iter() {
+            if let Some(name) = k.strip_prefix("arg.") {
+                self.arg_defaults.insert(name.to_string(), v.to_string());
+            }
+        }
+    }
+
+    /// Returns the value of a variable,
+    ///
+    fn resolve(&self, kind: &str, name: &str) -> anyhow::Result<Option<String>> {
+        match kind {
+            "env" => Ok(std::env::var(name).ok()),
+            "prop" => Ok(self
+                .props
+                .lock()
+                .ok()
+                .and_then(|p| p.get(name).cloned())),
+            "arg" => Ok(self
+                .args
+                .get(name)
+                .or(self.arg_defaults.get(name))
+                .cloned()),
+            _ => Err(anyhow!(
+                "unknown variable kind `{kind}` in `${{{kind}:{name}}}`, expected `env`, `prop` or `arg`"
+            )),
+        }
+    }
+
+    /// Returns the input w/ each variable replaced by its value,
+    ///
+    /// Returns an error if a variable is not defined or is not terminated.
+    ///
+    pub fn interpolate(&self, input: &str) -> anyhow::Result<String> {
+        if !input.contains("${") {
+            return Ok(input.to_string());
+        }
+
+        let mut output = String::with_capacity(input.len());
+        let mut rest = input;
+
+        while let Some(start) = rest.find('$') {
+            output.push_str(&rest[..start]);
+
+            let dollars = &rest[start..];
+            let after = dollars.trim_start_matches('$');
+            let run = dollars.len() - after.len();
+
+            // A run of `$` that is not followed by `{` is not escaped
+            if !after.starts_with('{') {
+                output.push_str(&dollars[..run]);
+                rest = after;
+                continue;
+            }
+
+            // Each `$$` is an escaped `$`, if a `$` remains it starts a variable
+            output.push_str(&"$".repeat(run / 2));
+            if run.is_multiple_of(2) {
+                output.push('{');
+                rest = &after[1..];
+                continue;
+            }
+
+            let end = after
+                .find('}')
+                .ok_or(anyhow!("unterminated variable `${}`", after))?;
+            let variable = &after[1..end];
+
+            let (kind, name) = variable.split_once(':').ok_or(anyhow!(
+                "variable `${{{variable}}}` is missing a kind, i.e. `${{env:{variable}}}`"
+            ))?;
+
+            let value = self
+                .resolve(kind.trim(), name.trim())?
+                .ok_or(anyhow!("undefined variable `${{{variable}}}`"))?;
+            output.push_str(&value);
+
+            rest = &after[end + 1..];
+        }
+
+        output.push_str(rest);
+        Ok(output)
+    }
+}
+
+#[test]
+fn test_interpolate() {
+    std::env::set_var("REALITY_TEST_INTERPOLATE", "x86_64");
+
+    let mut variables = Variables::new(Arc::new(BTreeMap::from_iter([(
+        "profile".to_string(),
+        "release".to_string(),
+    )])));
+    variables.set_arg_defaults(&BTreeMap::from_iter([
+        ("arg.jobs".to_string(), "4".to_string()),
+        ("help".to_string(), "ignored".to_string()),
+    ]));
+    variables.set_prop("name", "demo");
+
+    assert_eq!(
+        "--target x86_64 --profile release -j 4 demo",
+        variables
+            .interpolate("--target ${env:REALITY_TEST_INTERPOLATE} --profile ${arg:profile} -j ${arg:jobs} ${prop:name}")
+            .unwrap()
+    );
+    assert_eq!(
+        "literal ${env:HOME}",
+        variables.interpolate("literal $${env:HOME}").unwrap()
+    );
+    assert_eq!(
+        "$demo $${prop:name} $$ $",
+        variables
+            .interpolate("$$${prop:name} $$$${prop:name} $$ $")
+            .unwrap()
+    );
+    assert_eq!(
+        "no variables",
+        variables.interpolate("no variables").unwrap()
+    );
+
+    let err = variables.interpolate("${prop:missing}").unwrap_err();
+    assert_eq!("undefined variable `${prop:missing}`", err.to_string());
+    assert!(variables
+        .interpolate("${var:name}")
+        .unwrap_err()
+        .to_string()
+        .contains("unknown variable kind"));
+    assert!(variables
+        .interpolate("${env:HOME")
+        .unwrap_err()
+        .to_string()
+        .contains("unterminated"));
+
+    // Props are shared between clones of the same block
+    let cloned = variables.clone();
+    cloned.set_prop("name", "updated");
+    assert_eq!("updated", variables.interpolate("${prop:name}").unwrap());
+}
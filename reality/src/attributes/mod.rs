@@ -1,7 +1,9 @@
 mod attribute;
 mod attribute_type;
 mod decorated;
+mod diagnostic;
 mod fields;
+mod interpolate;
 mod parser;
 mod storage_target;
 mod visit;
@@ -16,7 +18,14 @@ pub mod prelude {
     pub use super::decorated::CommaSeperatedStrings;
     pub use super::decorated::Decorated;
     pub use super::decorated::Delimitted;
+    pub use super::diagnostic::validate_regex;
+    pub use super::diagnostic::DescribeAnyError;
+    pub use super::diagnostic::DescribeDisplayError;
+    pub use super::diagnostic::DescribeError;
+    pub use super::diagnostic::Diagnostic;
+    pub use super::diagnostic::Diagnostics;
     pub use super::fields::*;
+    pub use super::interpolate::Variables;
     pub use super::parser::AttributeParser;
     pub use super::parser::HostedResource;
     pub use super::parser::ParsedNode;
@@ -16,6 +16,10 @@ use super::attribute::Property;
 use super::attribute_type::OnParseField;
 use super::attribute_type::ParsableAttributeTypeField;
 use super::attribute_type::ParsableField;
+use super::diagnostic::parse_error_message;
+use super::diagnostic::Diagnostic;
+use super::diagnostic::Diagnostics;
+use super::interpolate::Variables;
 use super::AttributeTypeParser;
 use super::StorageTarget;
 use crate::block::BlockObjectHandler;
@@ -27,10 +31,13 @@ use crate::CallAsync;
 use crate::LinkFieldFn;
 use crate::LinkRecvFn;
 use crate::PluginLevel;
+use crate::PluginSchema;
+use crate::Profile;
 use crate::ResourceKey;
 use crate::SetIdentifiers;
 use crate::Shared;
 use crate::StorageTargetEntry;
+use crate::StorageTargetEntryMut;
 use crate::ThunkContext;
 
 /// Represents a resource that has been assigned a path,
@@ -267,6 +274,50 @@ impl ParsedNode {
     }
 }
 
+/// Scope entered when a nested field is defined,
+///
+pub(crate) struct NestedScope<Storage: StorageTarget + 'static> {
+    /// Name of the nested field,
+    ///
+    pub(crate) field: &'static str,
+    /// Input the nested field was defined with,
+    ///
+    pub(crate) input: String,
+    /// Tag the nested field was defined with,
+    ///
+    pub(crate) tag: Option<String>,
+    /// Attribute key of the nested value, None if the input could not be parsed,
+    ///
+    pub(crate) nested: Option<ResourceKey<Attribute>>,
+    /// Table of attribute type parsers of the enclosing scope,
+    ///
+    pub(crate) attribute_types: BTreeMap<String, AttributeTypeParser<Storage>>,
+    /// Number of required field diagnostics before the scope was entered,
+    ///
+    pub(crate) required: usize,
+    /// Field repr of the nested field,
+    ///
+    pub(crate) repr: Option<Repr>,
+    /// Transfers the nested value to the owner of the field,
+    ///
+    pub(crate) leave: fn(&mut AttributeParser<Storage>, &NestedScope<Storage>),
+}
+
+impl<S: StorageTarget + 'static> Clone for NestedScope<S> {
+    fn clone(&self) -> Self {
+        Self {
+            field: self.field,
+            input: self.input.clone(),
+            tag: self.tag.clone(),
+            nested: self.nested,
+            attribute_types: self.attribute_types.clone(),
+            required: self.required,
+            repr: self.repr,
+            leave: self.leave,
+        }
+    }
+}
+
 /// Maintains attribute types and matches runmd nodes to the corresponding attribute type parser,
 ///
 pub struct AttributeParser<Storage: StorageTarget + 'static> {
@@ -281,7 +332,7 @@ pub struct AttributeParser<Storage: StorageTarget + 'static> {
     block_object_types: BTreeMap<String, BlockObjectType>,
     /// Table of attribute type parsers,
     ///
-    attribute_types: BTreeMap<String, AttributeTypeParser<Storage>>,
+    pub(crate) attribute_types: BTreeMap<String, AttributeTypeParser<Storage>>,
     /// Stack of block object handlers to call on specific events,
     ///
     handlers: Vec<BlockObjectHandler>,
@@ -303,6 +354,24 @@ pub struct AttributeParser<Storage: StorageTarget + 'static> {
     /// Stack of link recv fns,
     ///
     pub(crate) link_recv: Vec<LinkRecvFn>,
+    /// Diagnostics for required fields that have not been defined yet, keyed by the attribute that owns the field,
+    ///
+    pub(crate) required: Vec<(Option<ResourceKey<Attribute>>, Diagnostic)>,
+    /// Stack of nested field scopes that have been entered,
+    ///
+    pub(crate) scopes: Vec<NestedScope<Storage>>,
+    /// Depth of the property being defined,
+    ///
+    pub(crate) depth: usize,
+    /// Variables that can be interpolated into inputs,
+    ///
+    pub(crate) variables: Variables,
+    /// Profile of property overrides,
+    ///
+    pub(crate) profile: Option<Arc<Profile>>,
+    /// Address of the resource whose properties are being defined,
+    ///
+    pub(crate) address: Option<String>,
 }
 
 impl<S: StorageTarget + 'static> Default for AttributeParser<S> {
@@ -319,6 +388,12 @@ impl<S: StorageTarget + 'static> Default for AttributeParser<S> {
             nodes: vec![],
             fields: vec![],
             link_recv: vec![],
+            required: vec![],
+            scopes: vec![],
+            depth: 0,
+            variables: Variables::default(),
+            profile: None,
+            address: None,
         }
     }
 }
@@ -337,6 +412,12 @@ impl<S: StorageTarget + 'static> Clone for AttributeParser<S> {
             nodes: self.nodes.clone(),
             fields: self.fields.clone(),
             link_recv: self.link_recv.clone(),
+            required: self.required.clone(),
+            scopes: self.scopes.clone(),
+            depth: self.depth,
+            variables: self.variables.clone(),
+            profile: self.profile.clone(),
+            address: self.address.clone(),
         }
     }
 }
@@ -360,6 +441,19 @@ impl AttributeParser<Shared> {
     pub fn parse_attribute<T: FromStr + Send + Sync + 'static>(
         &mut self,
         source: impl AsRef<str>,
+    ) -> anyhow::Result<ResourceKey<T>> {
+        self.parse_attribute_with::<T>(source, |_| None)
+    }
+
+    /// Parses an attribute and if successful returns the resource key used,
+    ///
+    /// If the attribute cannot be parsed, describe is called w/ the parse error and the description is included in the
+    /// diagnostic that is reported.
+    ///
+    pub fn parse_attribute_with<T: FromStr + Send + Sync + 'static>(
+        &mut self,
+        source: impl AsRef<str>,
+        describe: impl FnOnce(&<T as FromStr>::Err) -> Option<String>,
     ) -> anyhow::Result<ResourceKey<T>> {
         let tag = self.tag().cloned();
         if let Some(last) = self.nodes.last_mut() {
@@ -389,22 +483,74 @@ impl AttributeParser<Shared> {
         );
 
         // Storage target must be enabled,
+        let mut error = None;
         if let Some(mut storage) = self.storage_mut() {
             // Initialize attribute type,
-            let init = source.as_ref().parse::<T>().map_err(|_| {
-                anyhow::anyhow!(
-                    "Could not parse {} from {}",
-                    std::any::type_name::<T>(),
-                    source.as_ref()
-                )
-            })?;
-            storage.put_resource(init, key.transmute());
+            match source.as_ref().parse::<T>() {
+                Ok(init) => storage.put_resource(init, key.transmute()),
+                Err(err) => error = Some(parse_error_message::<T>(describe(&err))),
+            }
+        }
+
+        if let Some(err) = error {
+            let diagnostic = Diagnostic::new(std::any::type_name::<T>(), "", source.as_ref(), &err)
+                .with_node(self.nodes.last());
+            self.report(diagnostic);
+
+            return Err(anyhow::anyhow!(
+                "Could not parse {} from {}, {err}",
+                std::any::type_name::<T>(),
+                source.as_ref()
+            ));
         }
         self.parsed_node.push(key);
 
         Ok(key.transmute())
     }
 
+    /// Returns the schema of each object type added to the parser,
+    ///
+    pub fn schemas(&self) -> Vec<PluginSchema> {
+        self.block_object_types
+            .values()
+            .map(|o| (o.schema)())
+            .collect()
+    }
+
+    /// Returns the field reprs of an object type added to the parser,
+    ///
+    /// **Note** Includes fields that are not defined by a runmd block. Each field is linked to a node that only has the
+    /// field's symbol and a source naming the object type, so the reprs only describe the field and the resource it
+    /// parses.
+    ///
+    pub fn object_type_fields(&self, symbol: &str) -> Vec<Repr> {
+        let Some(object_ty) = self.block_object_types.get(symbol) else {
+            return vec![];
+        };
+
+        // Parsing w/o a storage target only registers the attribute types of each field
+        let mut parser = AttributeParser::<Shared>::default();
+        object_ty.attribute_type.parse(&mut parser, "");
+
+        // Reprs are interned by node, so each node must be unique to the field and object type
+        parser
+            .attribute_types
+            .iter()
+            .filter_map(|(name, a)| {
+                let node = NodeLevel::new()
+                    .with_symbol(name)
+                    .with_source(format!("<{symbol}> : .{name}"));
+                a.link_field(node).ok()
+            })
+            .collect()
+    }
+
+    /// Returns a JSON Schema document describing each object type added to the parser,
+    ///
+    pub fn json_schema(&self) -> serde_json::Value {
+        PluginSchema::document(self.schemas().iter())
+    }
+
     /// Adds an object type to the parser,
     ///
     pub fn with_object_type<O: BlockObject>(&mut self) -> &mut Self {
@@ -573,6 +719,19 @@ impl AttributeParser<Shared> {
         >());
     }
 
+    /// Adds a nested field whose fields are defined by properties in the field's scope,
+    ///
+    pub fn add_parseable_nested_field<const FIELD_OFFSET: usize, Owner>(&mut self)
+    where
+        Owner: Recv + OnParseField<FIELD_OFFSET> + Send + Sync + 'static,
+        Owner::ParseType: AttributeType<Shared> + Send + Sync + 'static,
+    {
+        self.add_type(AttributeTypeParser::parseable_nested_field::<
+            FIELD_OFFSET,
+            Owner,
+        >());
+    }
+
     /// Returns attribute parser with a parseable type, registered to ident, chainable
     ///
     pub fn with_parseable_attribute_type_field_as<const FIELD_OFFSET: usize, Owner>(
@@ -647,6 +806,158 @@ impl AttributeParser<Shared> {
         Option::take(&mut self.name);
     }
 
+    /// Reports a diagnostic to the root of centralized storage,
+    ///
+    /// **Note** Diagnostics are collected when the workspace is compiled, if storage is not available the diagnostic is logged instead.
+    ///
+    pub fn report(&mut self, diagnostic: Diagnostic) {
+        if let Some(mut storage) = self.storage_mut() {
+            storage
+                .root()
+                .maybe_put(Diagnostics::default)
+                .push(diagnostic);
+        } else {
+            error!("{diagnostic}");
+        }
+    }
+
+    /// Returns the input w/ variables interpolated,
+    ///
+    /// If a variable cannot be resolved, a diagnostic is reported at the line currently being parsed.
+    ///
+    pub(crate) fn interpolate(
+        &mut self,
+        name: &str,
+        input: Option<&str>,
+    ) -> Result<Option<String>, ()> {
+        match input.map(|i| self.variables.interpolate(i)).transpose() {
+            Ok(input) => Ok(input),
+            Err(err) => {
+                let diagnostic = Diagnostic::new("runmd", name, input.unwrap_or_default(), err)
+                    .with_node(self.nodes.last());
+                self.report(diagnostic);
+                Err(())
+            }
+        }
+    }
+
+    /// Returns the input of a property w/ the override of the current profile applied,
+    ///
+    /// If the profile overrides the property, the name of the profile is added to the annotations of the property as
+    /// `profile` so that it can be found w/ `Decorated::property("profile")`.
+    ///
+    fn apply_profile(&mut self, name: &str, input: Option<&str>) -> Option<String> {
+        let field = match self.scope_path() {
+            Some(scope) => format!("{scope}.{name}"),
+            None => name.to_string(),
+        };
+
+        let (Some(profile), Some(address)) = (self.profile.as_ref(), self.address.as_ref()) else {
+            return input.map(str::to_string);
+        };
+
+        match profile.get(address, &field) {
+            Some(value) => {
+                trace!(
+                    profile = profile.name,
+                    address,
+                    field,
+                    "Overriding property"
+                );
+                let value = value.to_string();
+                let profile = profile.name.to_string();
+
+                if let Some(last) = self.nodes.last_mut() {
+                    let mut annotations = last
+                        .mount()
+                        .5
+                        .map(|a| a.as_ref().clone())
+                        .unwrap_or_default();
+                    annotations.insert("profile".to_string(), profile);
+                    last.set_annotations(annotations);
+                }
+
+                Some(value)
+            }
+            None => input.map(str::to_string),
+        }
+    }
+
+    /// Removes diagnostics for a required field once the field has been defined,
+    ///
+    fn satisfy_required(&mut self, name: &str) {
+        // Only the required field of the attribute the property is being defined on can be satisfied
+        let owner = self.parsed_node.last().copied();
+        self.required
+            .retain(|(key, required)| *key != owner || required.field != name);
+    }
+
+    /// Requires that a field of Owner is defined before the current node is unloaded,
+    ///
+    pub fn require_field<Owner>(&mut self, field: &'static str) {
+        let diagnostic = Diagnostic::new(
+            std::any::type_name::<Owner>(),
+            field,
+            "",
+            "required field is not defined",
+        )
+        .with_node(self.nodes.last());
+
+        self.required
+            .push((self.parsed_node.last().copied(), diagnostic));
+    }
+
+    /// Enters the scope of a nested field,
+    ///
+    /// Until the scope is left, properties one level deeper define fields of the nested type.
+    ///
+    pub(crate) fn enter_scope(&mut self, scope: NestedScope<Shared>) {
+        trace!(
+            field = scope.field,
+            depth = self.scopes.len(),
+            "Entering scope"
+        );
+        self.scopes.push(scope);
+    }
+
+    /// Leaves the current nested scope and restores the attribute types of the enclosing scope,
+    ///
+    pub(crate) fn leave_scope(&mut self) {
+        if let Some(scope) = self.scopes.pop() {
+            trace!(
+                field = scope.field,
+                depth = self.scopes.len(),
+                "Leaving scope"
+            );
+
+            // Report any required fields of the nested type that were not defined
+            let required = scope.required.min(self.required.len());
+            for (_, diagnostic) in self.required.split_off(required) {
+                self.report(diagnostic);
+            }
+
+            (scope.leave)(self, &scope);
+            self.attribute_types = scope.attribute_types;
+        }
+    }
+
+    /// Returns the path of the nested field scope the current property is defined in, i.e. `server.tls`,
+    ///
+    pub fn scope_path(&self) -> Option<String> {
+        if self.depth == 0 || self.scopes.is_empty() {
+            None
+        } else {
+            Some(
+                self.scopes
+                    .iter()
+                    .take(self.depth)
+                    .map(|s| s.field)
+                    .collect::<Vec<_>>()
+                    .join("."),
+            )
+        }
+    }
+
     /// Returns a clone of storage,
     ///
     pub fn clone_storage(&self) -> Option<Arc<tokio::sync::RwLock<Shared>>> {
@@ -710,6 +1021,28 @@ impl AttributeParser<Shared> {
 #[async_trait(?Send)]
 impl Node for super::AttributeParser<Shared> {
     fn assign_path(&mut self, path: String) {
+        // Properties of a nested field are addressed by the path of the field
+        let path = match (self.scope_path(), path.strip_prefix("?prop=")) {
+            (Some(scope), Some(prop)) => format!("?prop={scope}.{prop}"),
+            _ => path,
+        };
+
+        // Profile overrides are addressed by the host address of the node or extension
+        if !path.starts_with("?prop=") {
+            if let Some((_, Some(input), tag, ..)) = self.nodes.first().map(NodeLevel::mount) {
+                let address = if path.is_empty() {
+                    input.to_string()
+                } else {
+                    format!("{input}/{path}")
+                };
+
+                self.address = Some(match tag {
+                    Some(tag) => format!("{address}#{tag}"),
+                    None => address,
+                });
+            }
+        }
+
         if let Some(node) = self.nodes.last_mut() {
             trace!("Setting path -- {} -- {:?}", path.as_str(), node.mount());
             node.set_path(path.as_str());
@@ -720,6 +1053,10 @@ impl Node for super::AttributeParser<Shared> {
 
     fn set_info(&mut self, _node_info: NodeInfo, _block_info: BlockInfo) {
         trace!("{:#?}", _node_info);
+        self.depth = _node_info.line.depth;
+        self.variables
+            .set_arg_defaults(&_node_info.line.comment_properties);
+
         if _node_info.parent_idx.is_none() {
             let last = self.parsed_node.attributes.last();
             trace!("Add node {:?} {:?}", _node_info, last);
@@ -746,6 +1083,19 @@ impl Node for super::AttributeParser<Shared> {
     async fn define_property(&mut self, name: &str, tag: Option<&str>, input: Option<&str>) {
         self.reset();
 
+        // Leave any nested scopes that are deeper than the property
+        while self.scopes.len() > self.depth {
+            self.leave_scope();
+        }
+
+        let input = self.apply_profile(name, input);
+        let Ok(input) = self.interpolate(name, input.as_deref()) else {
+            // The property was defined, so only the interpolation error is reported
+            self.satisfy_required(name);
+            return;
+        };
+        let input = input.as_deref();
+
         // Configure the current node
         if let Some(last) = self.nodes[..].last_mut() {
             last.set_symbol(name);
@@ -757,6 +1107,22 @@ impl Node for super::AttributeParser<Shared> {
             }
         }
 
+        if self.depth > self.scopes.len() {
+            let diagnostic = Diagnostic::new(
+                "runmd",
+                name,
+                input.unwrap_or_default(),
+                format!(
+                    "property is nested {} level(s) deep, but only {} nested field scope(s) have been entered",
+                    self.depth,
+                    self.scopes.len()
+                ),
+            )
+            .with_node(self.nodes.last());
+            self.report(diagnostic);
+            return;
+        }
+
         if let Some(tag) = tag.as_ref() {
             self.set_tag(tag);
             self.set_name(name);
@@ -765,7 +1131,22 @@ impl Node for super::AttributeParser<Shared> {
         }
 
         match self.attribute_types.get(name).cloned() {
-            Some(cattr) => {
+            Some(mut cattr) => {
+                self.satisfy_required(name);
+
+                if let (Some(scope), Some(field)) = (self.scope_path(), cattr.field.as_mut()) {
+                    field.set_path(format!("{scope}.{name}"));
+                }
+
+                // Properties defined earlier in the block can be interpolated w/ `${prop:name}`
+                let prop = match self.scope_path() {
+                    Some(scope) => format!("{scope}.{name}"),
+                    None => name.to_string(),
+                };
+                self.variables.set_prop(prop, input.unwrap_or_default());
+
+                let scopes = self.scopes.len();
+                let properties = self.parsed_node.properties.len();
                 cattr.parse(self, input.unwrap_or_default());
 
                 if let Some(last) = self.nodes.last() {
@@ -773,16 +1154,40 @@ impl Node for super::AttributeParser<Shared> {
                         Ok(field_repr) => {
                             self.fields.push(field_repr);
 
-                            if let Some(last) = self.parsed_node.properties.last_mut() {
-                                last.set_repr(field_repr);
+                            if self.scopes.len() > scopes {
+                                // The property of a nested field is defined when the scope is left
+                                if let Some(scope) = self.scopes.last_mut() {
+                                    scope.repr = Some(field_repr);
+                                }
+                            } else if self.parsed_node.properties.len() > properties {
+                                if let Some(last) = self.parsed_node.properties.last_mut() {
+                                    last.set_repr(field_repr);
+                                }
                             }
                         }
-                        Err(err) => {
-                            error!("{err}");
-                        }
+                        Err(err) => match err.downcast_ref::<InternCollision>() {
+                            // The collision renders both sets of tag values that share the handle
+                            Some(collision) => {
+                                let diagnostic = Diagnostic::new(
+                                    "runir",
+                                    name,
+                                    input.unwrap_or_default(),
+                                    collision,
+                                )
+                                .with_node(self.nodes.last());
+                                self.report(diagnostic);
+                            }
+                            None => {
+                                error!("{err}");
+                            }
+                        },
                     }
                 }
-                self.link_recv.push(cattr.link_recv);
+
+                // Fields of nested types are linked to the receiver of the node's owner
+                if self.depth == 0 {
+                    self.link_recv.push(cattr.link_recv);
+                }
             }
             None => {
                 trace!(attr_ty = name, "Did not have attribute");
@@ -819,6 +1224,11 @@ impl ExtensionLoader for super::AttributeParser<Shared> {
             let _ = parser.unload().await;
         }
 
+        let Ok(input) = parser.interpolate(extension, input) else {
+            return Some(Box::pin(parser));
+        };
+        let input = input.as_deref();
+
         // Clear any pre-existing attribute types
         parser.attribute_types.clear();
 
@@ -873,6 +1283,16 @@ impl ExtensionLoader for super::AttributeParser<Shared> {
     }
 
     async fn unload(&mut self) {
+        // Leave any nested scopes so that nested values are transferred to their owners
+        while !self.scopes.is_empty() {
+            self.leave_scope();
+        }
+
+        // Report any required fields that were not defined by the node being unloaded
+        for (_, diagnostic) in std::mem::take(&mut self.required) {
+            self.report(diagnostic);
+        }
+
         // Drain any dispatches before trying to load the rest of the resources
         if let Some(mut storage) = self.storage_mut() {
             storage.drain_dispatch_queues();
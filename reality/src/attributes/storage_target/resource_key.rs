@@ -261,6 +261,8 @@ impl<T: Send + Sync + 'static> ResourceKey<T> {
                 if let Some(offset) = field.offset() {
                     packet.field_offset = offset;
                 }
+
+                packet.field_path = field.path().map(|p| p.to_string());
             }
 
             packet.attribute_hash = Some(self.data);
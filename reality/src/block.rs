@@ -9,6 +9,7 @@ use crate::AttributeType;
 use crate::AttributeTypeParser;
 use crate::FieldPacket;
 use crate::LinkRecvFn;
+use crate::PluginSchema;
 use crate::ResourceKey;
 use crate::SetField;
 use crate::Shared;
@@ -26,6 +27,9 @@ pub struct BlockObjectType {
     /// Object event handlers,
     ///
     pub handler: BlockObjectHandler,
+    /// Returns the schema of the block object,
+    ///
+    pub schema: fn() -> PluginSchema,
 }
 
 impl BlockObjectType {
@@ -36,6 +40,7 @@ impl BlockObjectType {
             ident: B::symbol(),
             attribute_type: B::attribute_type(),
             handler: B::handler(),
+            schema: B::schema,
         }
     }
 
@@ -44,6 +49,7 @@ impl BlockObjectType {
             ident: B::symbol(),
             attribute_type: B::attribute_type_as::<As>(),
             handler: B::handler(),
+            schema: B::schema,
         }
     }
 }
@@ -54,6 +60,7 @@ impl Clone for BlockObjectType {
             ident: self.ident,
             attribute_type: self.attribute_type.clone(),
             handler: self.handler.clone(),
+            schema: self.schema,
         }
     }
 }
@@ -91,6 +98,18 @@ where
         BlockObjectHandler::new::<Self>()
     }
 
+    /// Returns the schema describing the runmd surface of this block object,
+    ///
+    /// **Note** If the derive macro is used, the schema will include each field parsed by the block object.
+    ///
+    fn schema() -> PluginSchema {
+        PluginSchema {
+            symbol: Self::symbol().to_string(),
+            ty: std::any::type_name::<Self>().to_string(),
+            ..Default::default()
+        }
+    }
+
     /// Called when the block object is being loaded into it's namespace,
     ///
     async fn on_load(
@@ -1,3 +1,6 @@
+// Lets code generated by `#[derive(Reality)]` refer to `reality::` from within this crate
+extern crate self as reality;
+
 #[macro_use]
 pub mod macros;
 
@@ -10,19 +13,40 @@ pub use block::BlockObjectHandler;
 pub use block::BlockObjectType;
 pub use block::SetIdentifiers;
 
+mod schema;
+pub use schema::AnySchemaBound;
+pub use schema::FieldKind;
+pub use schema::FieldSchema;
+pub use schema::NumericSchemaBound;
+pub use schema::PluginSchema;
+pub use schema::SchemaBound;
+pub use schema::JSON_SCHEMA_DIALECT;
+
 mod project;
 pub use project::BlockPlugin;
 pub use project::CurrentDir;
 pub use project::Dir;
+pub use project::Directive;
 pub use project::EmptyWorkspace;
 pub use project::Node;
 pub use project::NodePlugin;
+pub use project::Persist;
+pub use project::Profile;
 pub use project::Project;
+pub use project::PropertyEdit;
 pub use project::RegisterWith;
 pub use project::Source;
 pub use project::Transform;
 pub use project::Workspace;
 
+mod secret;
+pub use secret::set_secret_cipher;
+pub use secret::AnyFieldFlag;
+pub use secret::Secret;
+pub use secret::SecretCipher;
+pub use secret::SecretFieldFlag;
+pub use secret::SecretFlag;
+
 mod thunk;
 pub use thunk::*;
 
@@ -4,45 +4,68 @@ pub use crate::derive::RealityEnum;
 pub use crate::derive::RealityTest;
 pub use crate::project::Package;
 pub use crate::project::Program;
+pub use crate::AnyFieldFlag;
+pub use crate::AnySchemaBound;
+pub use crate::ApplyOp;
 pub use crate::AsyncStorageTarget;
 pub use crate::Attribute;
 pub use crate::AttributeParser;
 pub use crate::AttributeType;
 pub use crate::BlockObject;
 pub use crate::CacheExt;
+pub use crate::Code;
 pub use crate::CurrentDir;
 pub use crate::Decorated;
 pub use crate::Delimitted;
+pub use crate::DescribeAnyError;
+pub use crate::DescribeDisplayError;
+pub use crate::DescribeError;
+pub use crate::Diagnostic;
+pub use crate::Diagnostics;
 pub use crate::Dir;
 pub use crate::Dispatcher;
 pub use crate::EmptyWorkspace;
 pub use crate::Field;
+pub use crate::FieldCondition;
+pub use crate::FieldKind;
 pub use crate::FieldMut;
 pub use crate::FieldOwned;
 pub use crate::FieldPacket;
 pub use crate::FieldPacketType;
 pub use crate::FieldRef;
 pub use crate::FieldRefController;
+pub use crate::FieldSchema;
 pub use crate::FieldVTable;
 pub use crate::Frame;
 pub use crate::FrameListener;
 pub use crate::FrameUpdates;
 pub use crate::HostedResource;
+pub use crate::LoadCollection;
 pub use crate::NewFn;
 pub use crate::Node;
+pub use crate::NumericSchemaBound;
 pub use crate::OnParseField;
 pub use crate::OnReadField;
 pub use crate::OnWriteField;
+pub use crate::Op;
+pub use crate::OpResult;
 pub use crate::Pack;
 pub use crate::PacketRouter;
 pub use crate::PacketRoutes;
+pub use crate::PluginSchema;
 pub use crate::ParsableField;
 pub use crate::ParsedNode;
+pub use crate::Persist;
 pub use crate::Project;
+pub use crate::PropertyEdit;
 pub use crate::Property;
 pub use crate::RegisterWith;
 pub use crate::ResourceKey;
 pub use crate::ResourceKeyHashBuilder;
+pub use crate::SchemaBound;
+pub use crate::Secret;
+pub use crate::SecretFieldFlag;
+pub use crate::SecretFlag;
 pub use crate::SetField;
 pub use crate::SetIdentifiers;
 pub use crate::Shared;
@@ -55,9 +78,14 @@ pub use crate::ToFrame;
 pub use crate::Transform;
 pub use crate::VisitVirtual;
 pub use crate::VisitVirtualMut;
+pub use crate::WireCounters;
+pub use crate::WireMetrics;
+pub use crate::WireMetricsRegistry;
 pub use crate::WireServer;
 pub use crate::Workspace;
 
+pub use crate::validate_regex;
+
 // pub use crate::SharedFile;
 
 pub use crate::enable_virtual_dependencies;
@@ -0,0 +1,236 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+
+/// Enumeration of directives that are handled while loading runmd content,
+///
+/// Directives are written as nodes inside of a runmd block, but are removed from the content before it is parsed.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Directive {
+    /// Includes another runmd file or a named block from it, i.e. `+ .include ./shared.md#moniker`,
+    ///
+    Include {
+        /// Path to the file to include, relative to the file declaring the directive,
+        ///
+        path: PathBuf,
+        /// Moniker of the block to include, if not set all blocks are included,
+        ///
+        block: Option<String>,
+    },
+    /// Imports runmd files from another workspace directory w/ a namespace prefix, i.e. `+ shared .import ../shared`,
+    ///
+    /// **Note** The input of each node loaded from the directory is prefixed w/ `<namespace>/`.
+    ///
+    Import {
+        /// Namespace to prefix node inputs with,
+        ///
+        namespace: String,
+        /// Path to the directory to import, relative to the file declaring the directive,
+        ///
+        dir: PathBuf,
+    },
+}
+
+impl Directive {
+    /// Returns the path the directive refers to, resolved relative to the file declaring the directive,
+    ///
+    pub fn resolve(&self, relative: impl AsRef<Path>) -> PathBuf {
+        let parent = relative.as_ref().parent().unwrap_or(Path::new(""));
+
+        match self {
+            Directive::Include { path, .. } => parent.join(path),
+            Directive::Import { dir, .. } => parent.join(dir),
+        }
+    }
+
+    /// Parses a directive from a line inside of a runmd block,
+    ///
+    /// Returns None if the line is not a directive.
+    ///
+    fn parse(line: &str) -> Option<anyhow::Result<Self>> {
+        let node = line.trim().strip_prefix('+')?.trim();
+
+        let (tag, rest) = match node.split_once(char::is_whitespace) {
+            Some((tag, rest)) if !tag.starts_with('.') => (Some(tag), rest.trim()),
+            _ => (None, node),
+        };
+
+        let (name, input) = rest
+            .split_once(char::is_whitespace)
+            .map(|(n, i)| (n, i.trim()))
+            .unwrap_or((rest, ""));
+
+        match name {
+            ".include" if input.is_empty() => Some(Err(anyhow!(
+                "Missing path to include, i.e. `+ .include ./shared.md`"
+            ))),
+            ".include" => {
+                let (path, block) = match input.split_once('#') {
+                    Some((path, block)) => (path, Some(block.to_string())),
+                    None => (input, None),
+                };
+                Some(Ok(Directive::Include {
+                    path: PathBuf::from(path),
+                    block,
+                }))
+            }
+            ".import" => match tag {
+                Some(namespace) if !input.is_empty() => Some(Ok(Directive::Import {
+                    namespace: namespace.to_string(),
+                    dir: PathBuf::from(input),
+                })),
+                _ => Some(Err(anyhow!(
+                    "Import requires a namespace and a directory, i.e. `+ shared .import ../shared`"
+                ))),
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Removes directives from runmd content and returns the remaining content w/ the directives in order of declaration,
+///
+/// **Note** Removed lines are replaced w/ whitespace so that source spans of the remaining content are unchanged.
+///
+pub(crate) fn take_directives(content: &str) -> anyhow::Result<(String, Vec<Directive>)> {
+    let mut output = String::with_capacity(content.len());
+    let mut directives = vec![];
+    let mut in_block = false;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```runmd") {
+            in_block = true;
+        } else if trimmed.starts_with("```") {
+            in_block = false;
+        } else if in_block {
+            if let Some(directive) = Directive::parse(line) {
+                directives.push(directive?);
+                output.push_str(&blank(line));
+                continue;
+            }
+        }
+
+        output.push_str(line);
+    }
+
+    Ok((output, directives))
+}
+
+/// Returns content w/ every runmd block except the block w/ a matching moniker removed,
+///
+/// **Note** Removed lines are replaced w/ whitespace so that source spans of the remaining content are unchanged.
+///
+pub(crate) fn select_block(content: &str, moniker: &str) -> anyhow::Result<String> {
+    let mut output = String::with_capacity(content.len());
+    let mut selected = false;
+    let mut found = false;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if let Some(header) = trimmed.strip_prefix("```runmd") {
+            let mut parts = header.split_whitespace();
+            selected = matches!((parts.next(), parts.next()), (Some(_), Some(m)) if m == moniker);
+            found |= selected;
+        }
+
+        if selected {
+            output.push_str(line);
+        } else {
+            output.push_str(&blank(line));
+        }
+
+        if !trimmed.starts_with("```runmd") && trimmed.starts_with("```") {
+            selected = false;
+        }
+    }
+
+    if found {
+        Ok(output)
+    } else {
+        Err(anyhow!("Could not find a runmd block named `{moniker}`"))
+    }
+}
+
+/// Returns a line w/ every character except the line ending replaced w/ whitespace,
+///
+/// **Note** Multi-byte characters are replaced w/ a space per byte.
+///
+fn blank(line: &str) -> String {
+    let content = line.trim_end_matches(['\r', '\n']);
+    let ending = &line[content.len()..];
+
+    format!("{}{ending}", " ".repeat(content.len()))
+}
+
+#[test]
+fn test_take_directives() {
+    let content = r#"
++ .include ./outside.md
+```runmd
++ .include ./shared.md
++ .include ../lib/common.md#defaults
++ shared .import ../shared
++ .operation a
+<builtin.println> hello
+```
+"#;
+
+    let (output, directives) = take_directives(content).unwrap();
+    assert_eq!(content.len(), output.len());
+    assert!(output.contains("+ .include ./outside.md"));
+    assert!(!output.contains("./shared.md"));
+    assert!(output.contains("+ .operation a"));
+    assert_eq!(
+        content.find("+ .operation a"),
+        output.find("+ .operation a")
+    );
+    assert_eq!(
+        vec![
+            Directive::Include {
+                path: PathBuf::from("./shared.md"),
+                block: None
+            },
+            Directive::Include {
+                path: PathBuf::from("../lib/common.md"),
+                block: Some("defaults".to_string())
+            },
+            Directive::Import {
+                namespace: "shared".to_string(),
+                dir: PathBuf::from("../shared")
+            },
+        ],
+        directives
+    );
+
+    assert_eq!(
+        PathBuf::from("lib/runmd/../lib/common.md"),
+        directives[1].resolve("lib/runmd/app.md")
+    );
+
+    assert!(take_directives("```runmd\n+ .import ../shared\n```").is_err());
+}
+
+#[test]
+fn test_select_block() {
+    let content = r#"
+```runmd <demo> a
++ .operation a
+```
+
+```runmd <demo> b
++ .operation b
+```
+"#;
+
+    let output = select_block(content, "b").unwrap();
+    assert_eq!(content.len(), output.len());
+    assert!(!output.contains("+ .operation a"));
+    assert_eq!(
+        content.find("+ .operation b"),
+        output.find("+ .operation b")
+    );
+    assert!(select_block(content, "c").is_err());
+}
@@ -0,0 +1,43 @@
+use runir::prelude::Recv;
+
+use crate::AttributeParser;
+use crate::Shared;
+
+use super::EmptyWorkspace;
+use super::Project;
+use super::Workspace;
+
+/// Recv level linked to each `test` node compiled by a fixture,
+///
+pub(crate) struct PsuedoTest;
+
+impl Recv for PsuedoTest {
+    fn symbol() -> &'static str {
+        "test"
+    }
+}
+
+/// Returns a project w/ a `test` node plugin that registers object types w/ `register`,
+///
+pub(crate) fn test_project(
+    register: impl Fn(&mut AttributeParser<Shared>) + Send + Sync + 'static,
+) -> Project<Shared> {
+    let mut project = Project::new(Shared::default());
+    project.add_node_plugin("test", move |_, _, parser| {
+        register(parser);
+        parser.push_link_recv::<PsuedoTest>();
+    });
+    project
+}
+
+/// Compiles a single runmd buffer w/ a project,
+///
+pub(crate) async fn compile_fixture(
+    project: Project<Shared>,
+    name: &str,
+    fixture: &str,
+) -> anyhow::Result<Workspace> {
+    let mut workspace = EmptyWorkspace.workspace();
+    workspace.add_buffer(name, fixture);
+    workspace.compile(project).await
+}
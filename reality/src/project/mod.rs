@@ -1,7 +1,12 @@
+mod directive;
 mod extension;
+#[cfg(test)]
+pub(crate) mod fixture;
 mod host;
 mod node;
 mod package;
+mod persist;
+mod profile;
 mod program;
 mod source;
 mod workspace;
@@ -13,17 +18,23 @@ use crate::ResourceKeyHashBuilder;
 use crate::Shared;
 use crate::StorageTarget;
 use crate::StorageTargetEntry;
+use crate::Variables;
+use anyhow::anyhow;
 use async_trait::async_trait;
+pub use directive::Directive;
 pub use extension::Transform;
+use futures_util::future::LocalBoxFuture;
 pub use host::RegisterWith;
 pub use node::Node;
+pub use persist::Persist;
+pub use persist::PropertyEdit;
+pub use profile::Profile;
 pub use program::Program;
 use runmd::prelude::BlockInfo;
 use runmd::prelude::NodeInfo;
-use serde::Deserialize;
-use serde::Serialize;
 pub use source::Source;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -48,12 +59,22 @@ pub type NodePlugin<S> =
 pub type NodeTable<Storage> =
     BTreeMap<ResourceKey<crate::attributes::Node>, Arc<tokio::sync::RwLock<Storage>>>;
 
+/// Type-alias for the set of directives loaded into a project, keyed by the path, block and namespace of each directive,
+///
+type LoadedDirectives = BTreeSet<(PathBuf, Option<String>, Option<String>)>;
+
 /// Project storing the main runmd parser,
 ///
 pub struct Project<Storage: StorageTarget + 'static> {
     root: Storage,
 
     pub nodes: tokio::sync::RwLock<NodeTable<Storage::Namespace>>,
+    /// CLI arguments that can be interpolated into inputs w/ `${arg:name}`,
+    ///
+    args: Arc<BTreeMap<String, String>>,
+    /// Profile of property overrides applied while parsing,
+    ///
+    profile: Option<Arc<Profile>>,
 }
 
 impl Project<Shared> {
@@ -63,9 +84,23 @@ impl Project<Shared> {
         Self {
             root,
             nodes: Default::default(),
+            args: Default::default(),
+            profile: None,
         }
     }
 
+    /// Sets the CLI arguments that can be interpolated into inputs w/ `${arg:name}`,
+    ///
+    pub fn set_args(&mut self, args: BTreeMap<String, String>) {
+        self.args = Arc::new(args);
+    }
+
+    /// Sets the profile of property overrides applied while parsing,
+    ///
+    pub fn set_profile(&mut self, profile: Profile) {
+        self.profile = Some(Arc::new(profile));
+    }
+
     /// Adds a block plugin to the project,
     ///
     /// This plugin will be used to prepare the attribute parser for all nodes evaluated within a block.
@@ -114,28 +149,155 @@ impl Project<Shared> {
 
     /// Load content into the project,
     ///
+    /// Include and import directives in the content are loaded after the content w/ paths resolved relative to
+    /// `relative`. Returns an error if a directive cannot be loaded or if files include each other in a cycle.
+    ///
+    /// **Note** Content reached by more than one directive is only loaded once, i.e. two includes of the same block.
+    ///
     pub async fn load_content(
         self,
         relative: impl Into<PathBuf>,
         content: impl AsRef<str>,
     ) -> anyhow::Result<Self> {
-        let mut loading: Loading<Shared> = self.into();
+        let (project, _) = self
+            .load_content_with(
+                relative.into(),
+                content.as_ref().to_string(),
+                None,
+                vec![],
+                LoadedDirectives::new(),
+            )
+            .await?;
+        Ok(project)
+    }
 
-        loading.set_relative(relative);
+    /// Load content into the project w/ an optional namespace and the stack of files currently being loaded,
+    ///
+    /// Returns the project and the set of directives that have been loaded, keyed by path, block and namespace.
+    ///
+    fn load_content_with(
+        self,
+        relative: PathBuf,
+        content: String,
+        namespace: Option<String>,
+        mut loading_stack: Vec<PathBuf>,
+        mut loaded: LoadedDirectives,
+    ) -> LocalBoxFuture<'static, anyhow::Result<(Self, LoadedDirectives)>> {
+        Box::pin(async move {
+            let identity = relative.canonicalize().unwrap_or(relative.clone());
+            if loading_stack.contains(&identity) {
+                let cycle = loading_stack
+                    .iter()
+                    .chain(Some(&identity))
+                    .map(|p| format!("{p:?}"))
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                return Err(anyhow!("Include cycle detected: {cycle}"));
+            }
+            loading_stack.push(identity);
 
-        let mut parser = runmd::prelude::Parser::new(loading.clone(), loading.clone());
+            let (content, directives) = directive::take_directives(&content)
+                .map_err(|err| anyhow!("Could not load directives from {relative:?}: {err}"))?;
 
-        parser.parse(content.as_ref()).await;
+            let mut loading: Loading<Shared> = self.into();
 
-        drop(parser);
+            loading.set_relative(relative.clone());
+            loading.namespace = namespace.clone();
 
-        for (_, n) in loading.project.nodes.write().await.iter() {
-            let mut n = n.write().await;
+            let mut parser = runmd::prelude::Parser::new(loading.clone(), loading.clone());
 
-            n.drain_dispatch_queues();
-        }
+            parser.parse(content).await;
+
+            drop(parser);
+
+            for (_, n) in loading.project.nodes.write().await.iter() {
+                let mut n = n.write().await;
+
+                n.drain_dispatch_queues();
+            }
+
+            let mut project = loading.unload()?;
+
+            for directive in directives {
+                let path = directive.resolve(&relative);
+
+                match directive {
+                    Directive::Include { block, .. } => {
+                        let identity = path.canonicalize().unwrap_or(path.clone());
+                        if !loaded.insert((identity, block.clone(), namespace.clone())) {
+                            continue;
+                        }
+
+                        let mut content =
+                            tokio::fs::read_to_string(&path).await.map_err(|err| {
+                                anyhow!("Could not include {path:?} from {relative:?}: {err}")
+                            })?;
+
+                        if let Some(block) = block {
+                            content = directive::select_block(&content, &block).map_err(|err| {
+                                anyhow!("Could not include {path:?} from {relative:?}: {err}")
+                            })?;
+                        }
+
+                        (project, loaded) = project
+                            .load_content_with(
+                                path,
+                                content,
+                                namespace.clone(),
+                                loading_stack.clone(),
+                                loaded,
+                            )
+                            .await?;
+                    }
+                    Directive::Import {
+                        namespace: import_namespace,
+                        ..
+                    } => {
+                        let import_namespace = match namespace.as_ref() {
+                            Some(namespace) => format!("{namespace}/{import_namespace}"),
+                            None => import_namespace,
+                        };
+
+                        let import_err =
+                            |err| anyhow!("Could not import {path:?} from {relative:?}: {err}");
+                        let mut entries = tokio::fs::read_dir(&path).await.map_err(import_err)?;
+
+                        let mut files = vec![];
+                        while let Some(entry) = entries.next_entry().await.map_err(import_err)? {
+                            let file = entry.path();
+                            if matches!(
+                                file.extension().and_then(|e| e.to_str()),
+                                Some("md") | Some("runmd")
+                            ) {
+                                files.push(file);
+                            }
+                        }
+                        files.sort();
+
+                        for file in files {
+                            let identity = file.canonicalize().unwrap_or(file.clone());
+                            if !loaded.insert((identity, None, Some(import_namespace.clone()))) {
+                                continue;
+                            }
+
+                            let content = tokio::fs::read_to_string(&file).await?;
+
+                            (project, loaded) = project
+                                .load_content_with(
+                                    file,
+                                    content,
+                                    Some(import_namespace.clone()),
+                                    loading_stack.clone(),
+                                    loaded,
+                                )
+                                .await?;
+                        }
+                    }
+                }
+            }
 
-        loading.unload()
+            Ok((project, loaded))
+        })
     }
 
     /// Creates a package for this project,
@@ -161,6 +323,12 @@ impl Project<Shared> {
 struct Loading<Storage: StorageTarget + Send + Sync + 'static> {
     project: Arc<Project<Storage>>,
     relative: PathBuf,
+    /// Namespace prefixed to the input of each node, set when loading an imported directory,
+    ///
+    namespace: Option<String>,
+    /// Variables of the block currently being parsed,
+    ///
+    variables: Arc<std::sync::Mutex<Variables>>,
 }
 
 impl<Storage: StorageTarget + Send + Sync + 'static> From<Project<Storage>> for Loading<Storage> {
@@ -168,6 +336,8 @@ impl<Storage: StorageTarget + Send + Sync + 'static> From<Project<Storage>> for
         Loading {
             project: Arc::new(value),
             relative: PathBuf::new(),
+            namespace: None,
+            variables: Default::default(),
         }
     }
 }
@@ -177,6 +347,8 @@ impl<Storage: StorageTarget + Send + Sync + 'static> Clone for Loading<Storage>
         Self {
             project: self.project.clone(),
             relative: self.relative.clone(),
+            namespace: self.namespace.clone(),
+            variables: self.variables.clone(),
         }
     }
 }
@@ -220,6 +392,11 @@ impl<Storage: StorageTarget + Send + Sync + 'static> Loading<Storage> {
             parser.parsed_node.node = node;
         }
 
+        if let Ok(variables) = self.variables.lock() {
+            parser.variables = variables.clone();
+        }
+        parser.profile = self.project.profile.clone();
+
         // Blocks can have properties and load/unload properties
         if let Some(provider) = self
             .project
@@ -254,6 +431,11 @@ impl<Storage: StorageTarget + Send + Sync + 'static> Loading<Storage> {
 
 impl runmd::prelude::BlockProvider for Loading<Shared> {
     fn provide(&self, block_info: BlockInfo) -> Option<runmd::prelude::BoxedNode> {
+        // Properties can only be interpolated within the block they are defined in
+        if let Ok(mut variables) = self.variables.lock() {
+            *variables = Variables::new(self.project.args.clone());
+        }
+
         let parser = self.create_parser_for_block(&block_info, None);
 
         Some(Box::pin(parser))
@@ -270,9 +452,28 @@ impl runmd::prelude::NodeProvider for Loading<Shared> {
         node_info: &NodeInfo,
         block_info: &BlockInfo,
     ) -> Option<runmd::prelude::BoxedNode> {
+        // Variables in the input of a node are interpolated before the node is provided
+        let mut variables = self.variables.lock().map(|v| v.clone()).unwrap_or_default();
+        variables.set_arg_defaults(&node_info.line.comment_properties);
+        let (input, interpolation_err) = match input.map(|i| variables.interpolate(i)) {
+            Some(Ok(input)) => (Some(input), None),
+            Some(Err(err)) => (input.map(str::to_string), Some(err)),
+            None => (None, None),
+        };
+
+        // Nodes loaded from an imported directory are prefixed w/ the namespace of the import
+        let input = match (self.namespace.as_ref(), input) {
+            (Some(namespace), Some(input)) => Some(format!("{namespace}/{input}")),
+            (_, input) => input,
+        };
+        let input = input.as_deref();
+
         let mut key_builder = ResourceKeyHashBuilder::new_default_hasher();
         key_builder.hash(block_info);
         key_builder.hash(node_info);
+        if let Some(namespace) = self.namespace.as_ref() {
+            key_builder.hash(namespace);
+        }
         let key = key_builder.finish();
 
         let target = self.project.root.shared_namespace(key);
@@ -299,6 +500,12 @@ impl runmd::prelude::NodeProvider for Loading<Shared> {
         parser.relative = Some(self.relative.clone());
         parser.nodes.push(node);
 
+        if let Some(err) = interpolation_err {
+            let diagnostic = crate::Diagnostic::new("runmd", name, input.unwrap_or_default(), err)
+                .with_node(parser.nodes.last());
+            parser.report(diagnostic);
+        }
+
         self.apply_plugin(name, input, tag, &mut parser);
 
         if let Some(storage) = parser.clone_storage() {
@@ -310,6 +517,7 @@ impl runmd::prelude::NodeProvider for Loading<Shared> {
     }
 }
 
+#[cfg(test)]
 #[allow(unused)]
 mod tests {
     use super::*;
@@ -318,11 +526,15 @@ mod tests {
     use std::path::PathBuf;
     use std::str::FromStr;
 
+    use super::fixture::compile_fixture;
+    use super::fixture::test_project;
     use crate::AsyncStorageTarget;
     use crate::AttributeType;
     use crate::BlockObject;
     use crate::OnParseField;
     use reality::prelude::*;
+    use serde::Deserialize;
+    use serde::Serialize;
 
     mod reality {
         pub use crate::*;
@@ -374,6 +586,7 @@ mod tests {
     use async_trait::async_trait;
     use runir::prelude::CrcInterner;
     use runir::prelude::HostLevel;
+    use runir::prelude::InternScope;
     use runir::prelude::Linker;
     use runir::prelude::NodeLevel;
     use runir::prelude::Recv;
@@ -384,6 +597,101 @@ mod tests {
     #[reality(plugin, call = test_call)]
     pub struct Test4;
 
+    #[derive(Reality, Serialize, Default, Clone, Debug)]
+    #[reality(group = "reality", call = test_noop, plugin)]
+    pub struct TestValidate {
+        #[reality(derive_fromstr, one_of = "a, b")]
+        mode: String,
+        #[reality(range = 1..=10)]
+        count: u32,
+        #[reality(regex = "^[a-z]+$")]
+        name: String,
+        #[reality(required)]
+        file: PathBuf,
+        #[reality(range = 'a'..='f')]
+        grade: char,
+    }
+
+    #[derive(Reality, Default, Clone, Serialize, Deserialize, Debug)]
+    #[reality(group = "reality", call = test_noop, plugin)]
+    pub struct TestSecret {
+        #[reality(derive_fromstr)]
+        name: String,
+        token: Secret<String>,
+        #[reality(map_of=Secret<String>)]
+        header: BTreeMap<String, Secret<String>>,
+    }
+
+    #[derive(Reality, Default, Clone, Serialize, Deserialize, Debug)]
+    #[reality(group = "reality", call = test_noop, plugin)]
+    pub struct TestProfile {
+        #[reality(derive_fromstr)]
+        name: String,
+        uri: Decorated<String>,
+        timeout: Decorated<u32>,
+    }
+
+    /// Transport used by a TestServer,
+    ///
+    /// **Note** Field names must be unique across variants, the variant is selected by the input of the nested field.
+    ///
+    #[derive(Reality, RealityEnum, Default, Clone, PartialEq, Serialize, Deserialize, Debug)]
+    #[reality(group = "reality", call = test_noop, plugin)]
+    pub enum TestTransport {
+        Tcp {
+            port: u32,
+            nodelay: bool,
+        },
+        Unix {
+            socket: PathBuf,
+        },
+        #[default]
+        Disabled,
+    }
+
+    #[derive(Reality, Default, Clone, PartialEq, Serialize, Deserialize, Debug)]
+    #[reality(group = "reality", call = test_noop, plugin)]
+    pub struct TestServer {
+        #[reality(derive_fromstr)]
+        host: String,
+        #[reality(range = 1..=65535)]
+        port: u32,
+        #[reality(nested)]
+        transport: TestTransport,
+    }
+
+    #[derive(Reality, Default, Clone, Serialize, Deserialize, Debug)]
+    #[reality(group = "reality", call = test_noop, plugin)]
+    pub struct TestNested {
+        #[reality(derive_fromstr)]
+        name: String,
+        #[reality(nested)]
+        server: TestServer,
+        #[reality(nested, vec_of = TestServer)]
+        replicas: Vec<TestServer>,
+        label: String,
+    }
+
+    #[derive(Reality, Default, Clone, PartialEq, Serialize, Deserialize, Debug)]
+    #[reality(group = "reality", call = test_noop, plugin)]
+    pub struct TestRequired {
+        #[reality(derive_fromstr)]
+        name: String,
+        #[reality(required)]
+        file: PathBuf,
+    }
+
+    #[derive(Reality, Default, Clone, Serialize, Deserialize, Debug)]
+    #[reality(group = "reality", call = test_noop, plugin)]
+    pub struct TestRequiredOwner {
+        #[reality(derive_fromstr)]
+        name: String,
+        #[reality(required)]
+        file: PathBuf,
+        #[reality(nested)]
+        child: TestRequired,
+    }
+
     pub async fn test_call(tc: &mut ThunkContext) -> anyhow::Result<()> {
         let test = tc.initialized::<Test>().await;
         eprintln!("test call {:#?}", test);
@@ -460,21 +768,10 @@ mod tests {
     #[tokio::test]
     #[tracing_test::traced_test]
     async fn test_project_parser() {
-        let mut project = Project::new(crate::Shared::default());
-
-        struct PsuedoTest;
-
-        impl Recv for PsuedoTest {
-            fn symbol() -> &'static str {
-                "test"
-            }
-        }
-
-        project.add_node_plugin("test", |_, _, parser| {
+        let project = test_project(|parser| {
             parser.with_object_type::<Thunk<Test>>();
             parser.with_object_type::<Thunk<Test2>>();
             parser.with_object_type::<Thunk<Test3>>();
-            parser.push_link_recv::<PsuedoTest>();
         });
 
         tokio::fs::create_dir_all(".test").await.unwrap();
@@ -551,4 +848,826 @@ mod tests {
 
         ()
     }
+
+    #[tokio::test]
+    async fn test_workspace_diagnostics() {
+        let project = test_project(|parser| {
+            parser.with_object_type::<Thunk<TestValidate>>();
+        });
+
+        let err = compile_fixture(
+            project,
+            "validate.md",
+            r#"```runmd
++ .test
+<reality.testvalidate> c
+
++ .test
+<reality.testvalidate> a
+: .count 11
+: .name Hello
+: .count ten
+
++ .test
+<reality.testvalidate> b
+: .count 5
+: .name hello
+: .file test.md
+```"#,
+        )
+        .await
+        .unwrap_err();
+
+        let diagnostics = err.downcast_ref::<Diagnostics>().unwrap();
+        let reported = diagnostics
+            .iter()
+            .map(|d| {
+                assert_eq!(Some(PathBuf::from("validate.md")), d.relative);
+                (
+                    d.location.map(|(line, _)| line).unwrap_or_default(),
+                    d.field.as_str(),
+                    d.input.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                (3, "", "c"),
+                (6, "file", ""),
+                (7, "count", "11"),
+                (8, "name", "Hello"),
+                (9, "count", "ten"),
+            ],
+            reported
+        );
+        assert!(diagnostics.items[2].message.contains("out of range"));
+        assert!(diagnostics.items[3]
+            .message
+            .contains("does not match pattern"));
+        assert!(diagnostics.items[4]
+            .message
+            .contains("invalid digit found in string"));
+    }
+
+    #[test]
+    fn test_validate_schema() {
+        let schema = <TestValidate as BlockObject>::schema();
+        assert_eq!("reality.testvalidate", schema.symbol);
+        assert_eq!(Some("mode".to_string()), schema.input);
+
+        let json = schema.to_json_schema();
+        assert_eq!(serde_json::json!(["file"]), json["required"]);
+        assert_eq!(
+            serde_json::json!(["a", "b"]),
+            json["properties"]["mode"]["enum"]
+        );
+        assert_eq!(1.0, json["properties"]["count"]["minimum"]);
+        assert_eq!(10.0, json["properties"]["count"]["maximum"]);
+        assert_eq!("^[a-z]+$", json["properties"]["name"]["pattern"]);
+        assert!(json["properties"]["grade"].is_object());
+        assert!(json["properties"]["grade"].get("minimum").is_none());
+        assert!(json["properties"]["grade"].get("maximum").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_nested_fields() {
+        let project = test_project(|parser| {
+            parser.with_object_type::<Thunk<TestNested>>();
+        });
+
+        let workspace = compile_fixture(
+            project,
+            "nested.md",
+            r#"```runmd
++ .test
+<reality.testnested> demo
+: .server localhost
+:: .port 8080
+:: .transport tcp
+::: .port 9000
+::: .nodelay true
+: .replicas a.local
+:: .transport unix
+::: .socket /tmp/a.sock
+: .replicas b.local
+:: .port 8081
+: .label done
+```"#,
+        )
+        .await
+        .unwrap();
+        let project = workspace.project.unwrap();
+        let nodes = project.nodes.read().await;
+
+        let mut found = None;
+        let mut paths = vec![];
+        for (_, store) in nodes.iter() {
+            let store = store.read().await;
+            let parsed = store.root_ref().current::<ParsedNode>().unwrap();
+
+            for attr in parsed.attributes.iter() {
+                if let Some(nested) = store.resource::<TestNested>(attr.transmute()) {
+                    found = Some(nested.clone());
+                }
+            }
+
+            paths.extend(
+                parsed
+                    .properties
+                    .iter()
+                    .filter_map(|p| p.field())
+                    .filter_map(|f| f.path())
+                    .map(|p| p.to_string()),
+            );
+            assert!(parsed.resolve_path("?prop=server.transport.port").is_some());
+        }
+
+        let nested = found.expect("should have parsed test nested");
+        assert_eq!("demo", nested.name);
+        assert_eq!("done", nested.label);
+        assert_eq!("localhost", nested.server.host);
+        assert_eq!(8080, nested.server.port);
+        assert_eq!(
+            TestTransport::Tcp {
+                port: 9000,
+                nodelay: true
+            },
+            nested.server.transport
+        );
+
+        assert_eq!(2, nested.replicas.len());
+        assert_eq!("a.local", nested.replicas[0].host);
+        assert_eq!(
+            TestTransport::Unix {
+                socket: PathBuf::from("/tmp/a.sock")
+            },
+            nested.replicas[0].transport
+        );
+        assert_eq!("b.local", nested.replicas[1].host);
+        assert_eq!(8081, nested.replicas[1].port);
+        assert_eq!(TestTransport::Disabled, nested.replicas[1].transport);
+
+        for path in [
+            "server.port",
+            "server.transport",
+            "server.transport.port",
+            "replicas.transport.socket",
+            "replicas.port",
+        ] {
+            assert!(paths.iter().any(|p| p == path), "missing {path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nested_fields_diagnostics() {
+        let project = test_project(|parser| {
+            parser.with_object_type::<Thunk<TestNested>>();
+        });
+
+        let err = compile_fixture(
+            project,
+            "nested.md",
+            r#"```runmd
++ .test
+<reality.testnested> demo
+:: .port 8080
+: .server localhost
+:: .port 70000
+```"#,
+        )
+        .await
+        .unwrap_err();
+        let diagnostics = err.downcast_ref::<Diagnostics>().unwrap();
+        let reported = diagnostics
+            .iter()
+            .map(|d| {
+                (
+                    d.location.map(|(line, _)| line).unwrap_or_default(),
+                    d.field.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![(4, "port"), (6, "port")], reported);
+        assert!(diagnostics.items[0]
+            .message
+            .contains("nested 1 level(s) deep"));
+        assert!(diagnostics.items[1].message.contains("out of range"));
+    }
+
+    #[tokio::test]
+    async fn test_required_fields_diagnostics() {
+        let project = test_project(|parser| {
+            parser.with_object_type::<Thunk<TestRequiredOwner>>();
+        });
+
+        // A field defined on one attribute must not satisfy the same field of another
+        let err = compile_fixture(
+            project,
+            "required.md",
+            r#"```runmd
++ .test
+<reality.testrequiredowner> a
+: .child b
+:: .file b.md
+
++ .test
+<reality.testrequiredowner> c
+: .file c.md
+: .child d
+```"#,
+        )
+        .await
+        .unwrap_err();
+        let diagnostics = err.downcast_ref::<Diagnostics>().unwrap();
+        let reported = diagnostics
+            .iter()
+            .map(|d| {
+                (
+                    d.location.map(|(line, _)| line).unwrap_or_default(),
+                    d.owner.as_str(),
+                    d.field.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                (3, std::any::type_name::<TestRequiredOwner>(), "file"),
+                (10, std::any::type_name::<TestRequired>(), "file"),
+            ],
+            reported
+        );
+    }
+
+    #[tokio::test]
+    async fn test_include_import_directives() {
+        let tmp =
+            std::env::temp_dir().join(format!("test_runmd_directives_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(tmp.join("lib")).unwrap();
+        std::fs::create_dir_all(tmp.join("ext")).unwrap();
+
+        std::fs::write(
+            tmp.join("lib/main.md"),
+            r#"```runmd
++ .include ./shared.md#valid
++ ext .import ../ext
++ .test main
+<reality.testvalidate> a
+: .file main.txt
+```"#,
+        )
+        .unwrap();
+        std::fs::write(
+            tmp.join("lib/shared.md"),
+            r#"# Shared definitions
+```runmd <demo> valid
++ .test shared
+<reality.testvalidate> b
+: .file shared.txt
+```
+
+```runmd <demo> invalid
++ .test broken
+<reality.testvalidate> a
+: .count 11
+: .file broken.txt
+```"#,
+        )
+        .unwrap();
+        std::fs::write(
+            tmp.join("ext/op.md"),
+            r#"```runmd
++ .test imported
+<reality.testvalidate> a
+: .file ext.txt
+```"#,
+        )
+        .unwrap();
+
+        let inputs = Arc::new(std::sync::Mutex::new(vec![]));
+        let create_project = |inputs: Arc<std::sync::Mutex<Vec<String>>>| {
+            let mut project = Project::new(crate::Shared::default());
+            project.add_node_plugin("test", move |input, _, parser| {
+                inputs
+                    .lock()
+                    .unwrap()
+                    .push(input.unwrap_or_default().to_string());
+                parser.with_object_type::<Thunk<TestValidate>>();
+            });
+            project
+        };
+
+        let mut workspace = EmptyWorkspace.workspace();
+        workspace.add_local(tmp.join("lib/main.md"));
+        let workspace = workspace
+            .compile(create_project(inputs.clone()))
+            .await
+            .unwrap();
+        assert_eq!(3, workspace.project.unwrap().nodes.read().await.len());
+
+        let mut inputs = inputs.lock().unwrap().clone();
+        inputs.sort();
+        assert_eq!(vec!["ext/imported", "main", "shared"], inputs);
+
+        // Diagnostics of included blocks should point at the included file
+        let mut workspace = EmptyWorkspace.workspace();
+        workspace.add_buffer(
+            tmp.join("lib/broken.md"),
+            "```runmd\n+ .include ./shared.md#invalid\n```",
+        );
+        let err = workspace
+            .compile(create_project(Arc::new(Default::default())))
+            .await
+            .unwrap_err();
+        let diagnostics = err.downcast_ref::<Diagnostics>().unwrap();
+        assert_eq!(1, diagnostics.items.len());
+        assert_eq!(
+            Some(tmp.join("lib/shared.md")),
+            diagnostics.items[0].relative
+        );
+        assert_eq!(Some((11, 1)), diagnostics.items[0].location);
+
+        // Including a missing block is an error
+        let mut workspace = EmptyWorkspace.workspace();
+        workspace.add_buffer(
+            tmp.join("lib/missing.md"),
+            "```runmd\n+ .include ./shared.md#missing\n```",
+        );
+        let err = workspace
+            .compile(create_project(Arc::new(Default::default())))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("`missing`"), "{err}");
+
+        // Files including each other is an error
+        std::fs::write(tmp.join("lib/a.md"), "```runmd\n+ .include ./b.md\n```").unwrap();
+        std::fs::write(tmp.join("lib/b.md"), "```runmd\n+ .include ./a.md\n```").unwrap();
+        let mut workspace = EmptyWorkspace.workspace();
+        workspace.add_local(tmp.join("lib/a.md"));
+        let err = workspace
+            .compile(create_project(Arc::new(Default::default())))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("cycle"), "{err}");
+
+        // Files included by more than one file are only loaded once
+        std::fs::write(
+            tmp.join("lib/base.md"),
+            "```runmd\n+ .test base\n<reality.testvalidate> a\n: .file base.txt\n```",
+        )
+        .unwrap();
+        for side in ["left", "right"] {
+            std::fs::write(
+                tmp.join(format!("lib/{side}.md")),
+                format!("```runmd\n+ .include ./base.md\n+ .test {side}\n<reality.testvalidate> a\n: .file {side}.txt\n```"),
+            )
+            .unwrap();
+        }
+        std::fs::write(
+            tmp.join("lib/diamond.md"),
+            "```runmd\n+ .include ./left.md\n+ .include ./right.md\n```",
+        )
+        .unwrap();
+
+        let inputs = Arc::new(std::sync::Mutex::new(vec![]));
+        let mut workspace = EmptyWorkspace.workspace();
+        workspace.add_local(tmp.join("lib/diamond.md"));
+        workspace
+            .compile(create_project(inputs.clone()))
+            .await
+            .unwrap();
+
+        let mut inputs = inputs.lock().unwrap().clone();
+        inputs.sort();
+        assert_eq!(vec!["base", "left", "right"], inputs);
+
+        std::fs::remove_dir_all(&tmp).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_interpolate_inputs() {
+        std::env::set_var("REALITY_TEST_INTERPOLATE_DIR", "/tmp/docs");
+
+        let project = test_project(|parser| {
+            parser.with_object_type::<Thunk<TestValidate>>();
+        });
+
+        let mut workspace = EmptyWorkspace.workspace();
+        let matches = clap::Command::new("test")
+            .subcommand(clap::Command::new("run").arg(clap::Arg::new("count").long("count")))
+            .get_matches_from(["test", "run", "--count", "7"]);
+        workspace.set_args_from_matches(&matches);
+        workspace.add_buffer(
+            "interpolate.md",
+            r#"```runmd
++ .test
+<reality.testvalidate> ${arg:mode}
+|# arg.mode = b
+: .name demo
+: .count ${arg:count}
+: .file ${env:REALITY_TEST_INTERPOLATE_DIR}/${prop:name}.md
+
++ .test
+<reality.testvalidate> a
+: .name ${prop:name}
+: .file $${prop:name}
+```"#,
+        );
+
+        let workspace = workspace.compile(project).await.unwrap();
+        let project = workspace.project.unwrap();
+        let nodes = project.nodes.read().await;
+
+        let mut parsed = vec![];
+        for (_, store) in nodes.iter() {
+            let store = store.read().await;
+            let node = store.root_ref().current::<ParsedNode>().unwrap();
+
+            for attr in node.attributes.iter() {
+                if let Some(validate) = store.resource::<TestValidate>(attr.transmute()) {
+                    parsed.push((
+                        validate.mode.clone(),
+                        validate.name.clone(),
+                        validate.count,
+                        validate.file.clone(),
+                    ));
+                }
+            }
+        }
+        parsed.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(
+            vec![
+                (
+                    "a".to_string(),
+                    "demo".to_string(),
+                    0,
+                    PathBuf::from("${prop:name}")
+                ),
+                (
+                    "b".to_string(),
+                    "demo".to_string(),
+                    7,
+                    PathBuf::from("/tmp/docs/demo.md")
+                ),
+            ],
+            parsed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_interpolate_diagnostics() {
+        let project = test_project(|parser| {
+            parser.with_object_type::<Thunk<TestValidate>>();
+        });
+
+        let err = compile_fixture(
+            project,
+            "interpolate.md",
+            r#"```runmd
++ .test ${arg:missing}
+<reality.testvalidate> a
+: .file ${prop:name}
+: .name ${env:REALITY_TEST_INTERPOLATE_UNDEFINED}
+```"#,
+        )
+        .await
+        .unwrap_err();
+        let diagnostics = err.downcast_ref::<Diagnostics>().unwrap();
+        let reported = diagnostics
+            .iter()
+            .map(|d| {
+                assert_eq!(Some(PathBuf::from("interpolate.md")), d.relative);
+                (
+                    d.location.map(|(line, _)| line).unwrap_or_default(),
+                    d.message.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                (2, "undefined variable `${arg:missing}`"),
+                (4, "undefined variable `${prop:name}`"),
+                (
+                    5,
+                    "undefined variable `${env:REALITY_TEST_INTERPOLATE_UNDEFINED}`"
+                ),
+            ],
+            reported
+        );
+    }
+
+    #[tokio::test]
+    async fn test_secret_fields() {
+        std::env::set_var("REALITY_TEST_SECRET_TOKEN", "hunter2");
+        std::env::set_var("REALITY_TEST_SECRET_HEADER", "Bearer hunter3");
+
+        let project = test_project(|parser| {
+            parser.with_object_type::<Thunk<TestSecret>>();
+        });
+
+        let workspace = compile_fixture(
+            project,
+            "secret.md",
+            r#"```runmd
++ .test
+<reality.testsecret> demo
+: .token env:REALITY_TEST_SECRET_TOKEN
+: Authorization .header env:REALITY_TEST_SECRET_HEADER
+```"#,
+        )
+        .await
+        .unwrap();
+        let project = workspace.project.unwrap();
+        let nodes = project.nodes.read().await;
+
+        let mut found = None;
+        for (_, store) in nodes.iter() {
+            let store = store.read().await;
+            let node = store.root_ref().current::<ParsedNode>().unwrap();
+
+            for attr in node.attributes.iter() {
+                if let Some(secret) = store.resource::<TestSecret>(attr.transmute()) {
+                    found = Some((secret.clone(), *attr, node.clone()));
+                }
+            }
+        }
+
+        let (secret, key, node) = found.expect("should have parsed test secret");
+        assert_eq!("hunter2", secret.token.reveal().unwrap());
+        assert_eq!(
+            "Bearer hunter3",
+            secret.header["Authorization"].reveal().unwrap()
+        );
+
+        // Secret values are not written to debug output or frames
+        let debug = format!("{:?}", secret);
+        assert!(!debug.contains("hunter"), "{debug}");
+
+        let frame = secret.to_frame(key);
+        for packet in frame.fields.iter() {
+            let wire = packet.wire_data.clone().unwrap_or_default();
+            assert!(!String::from_utf8_lossy(&wire).contains("hunter"));
+        }
+
+        // Secret fields are flagged by the derive and cannot be persisted
+        let secrets = node
+            .properties
+            .iter()
+            .filter_map(|p| p.field())
+            .map(|f| (f.name().unwrap_or_default(), f.is_secret()))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![("token", true), ("header", true)], secrets);
+
+        let mut persist = Persist::new();
+        let token = frame
+            .fields
+            .iter()
+            .find(|p| p.field_name == "token")
+            .unwrap();
+        let err = persist.edit(&node, token).unwrap_err();
+        assert!(err.to_string().contains("cannot be persisted"), "{err}");
+        assert!(persist.is_empty());
+
+        // Undefined secrets are reported while parsing
+        let project = test_project(|parser| {
+            parser.with_object_type::<Thunk<TestSecret>>();
+        });
+
+        let err = compile_fixture(
+            project,
+            "secret.md",
+            r#"```runmd
++ .test
+<reality.testsecret> demo
+: .token env:REALITY_TEST_SECRET_UNDEFINED
+```"#,
+        )
+        .await
+        .unwrap_err();
+        let diagnostics = err.downcast_ref::<Diagnostics>().unwrap();
+        assert_eq!(1, diagnostics.items.len());
+        assert_eq!(Some((4, 1)), diagnostics.items[0].location);
+        assert!(!diagnostics.items[0].input.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_profile_overrides() {
+        use tokio::runtime::Handle;
+
+        fn project() -> Project<Shared> {
+            test_project(|parser| {
+                parser.with_object_type::<Thunk<TestProfile>>();
+            })
+        }
+
+        async fn compile(workspace: &Workspace) -> anyhow::Result<Vec<TestProfile>> {
+            let workspace = workspace.compile(project()).await?;
+            let project = workspace.project.unwrap();
+            let nodes = project.nodes.read().await;
+
+            let mut parsed = vec![];
+            for (_, store) in nodes.iter() {
+                let node = store
+                    .read()
+                    .await
+                    .root_ref()
+                    .current::<ParsedNode>()
+                    .unwrap();
+                let target = AsyncStorageTarget::from_parts(store.clone(), Handle::current());
+                let mut tc: ThunkContext = target.into();
+
+                // Decorations are synced w/ the parsed node when the plugin is created
+                for attr in node.attributes.iter() {
+                    tc.set_attribute(*attr);
+                    let profile = Remote.create::<TestProfile>(&mut tc).await;
+                    if !profile.name.is_empty() {
+                        parsed.push(profile);
+                    }
+                }
+            }
+            parsed.sort_by(|a, b| a.name.cmp(&b.name));
+            Ok(parsed)
+        }
+
+        let mut workspace = EmptyWorkspace.workspace();
+        workspace.set_name("test");
+        workspace.add_buffer(
+            "profile.md",
+            r#"```runmd
++ .test api
+<a/reality.testprofile> a
+: .uri http://localhost:8080
+: .timeout 5
+
++ mock .test api
+<a/reality.testprofile> b
+: .uri http://localhost:8080
+: .timeout 5
+```"#,
+        );
+        workspace.add_profile(
+            Profile::new("staging")
+                .with_override(
+                    "engine://api/a/reality.testprofile",
+                    "uri",
+                    "https://staging",
+                )
+                .with_override("api/a/reality.testprofile#mock", "timeout", "30"),
+        );
+
+        // Base sources are unchanged when a profile is not selected
+        let parsed = compile(&workspace).await.unwrap();
+        assert_eq!(2, parsed.len());
+        assert_eq!(
+            Some(&"http://localhost:8080".to_string()),
+            parsed[0].uri.value()
+        );
+        assert_eq!(None, parsed[0].uri.property("profile"));
+
+        let parsed = workspace.compile_with_profile(project(), "missing").await;
+        assert!(parsed.is_err());
+
+        workspace.set_profile("staging");
+        let parsed = compile(&workspace).await.unwrap();
+        assert_eq!(Some(&"https://staging".to_string()), parsed[0].uri.value());
+        assert_eq!(
+            Some("staging".to_string()),
+            parsed[0].uri.property("profile")
+        );
+        assert_eq!(Some(&5), parsed[0].timeout.value());
+        assert_eq!(None, parsed[0].timeout.property("profile"));
+
+        assert_eq!(
+            Some(&"http://localhost:8080".to_string()),
+            parsed[1].uri.value()
+        );
+        assert_eq!(Some(&30), parsed[1].timeout.value());
+        assert_eq!(
+            Some("staging".to_string()),
+            parsed[1].timeout.property("profile")
+        );
+
+        // The profile can be selected w/ the `--profile` flag of the generated command
+        workspace.profile = None;
+        let compiled = workspace.compile(project()).await.unwrap();
+        let package = compiled.project.unwrap().package().await.unwrap();
+
+        let matches = clap::Command::from(package)
+            .try_get_matches_from(["test", "--profile", "staging"])
+            .unwrap();
+        workspace.set_args_from_matches(&matches);
+        assert_eq!(Some("staging".to_string()), workspace.profile);
+        assert!(workspace.args.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_workspace_intern_scope() {
+        let project = test_project(|parser| {
+            parser.with_object_type::<Thunk<TestProfile>>();
+        });
+
+        let mut workspace = EmptyWorkspace.workspace();
+        workspace.add_buffer(
+            "scope.md",
+            r#"```runmd
++ .test
+<reality.testprofile> intern-scope-test
+: .uri http://intern-scope-test
+```"#,
+        );
+        assert!(workspace.intern_stats().is_empty());
+        workspace.set_intern_scope(InternScope::new());
+
+        let mut compiled = workspace.compile(project).await.unwrap();
+        let stats = compiled.intern_stats();
+        assert!(stats.iter().any(|s| s.scoped > 0));
+
+        let project = compiled.project.take().unwrap();
+        let mut input = None;
+        for (_, store) in project.nodes.read().await.iter() {
+            let node = store
+                .read()
+                .await
+                .root_ref()
+                .current::<ParsedNode>()
+                .unwrap();
+            for attr in node.attributes.iter() {
+                let repr = attr.repr();
+                let node = repr.as_ref().and_then(|r| r.as_node());
+                if let Some(i) = node.as_ref().and_then(|n| n.input()) {
+                    input = Some((repr.unwrap(), node.unwrap(), i));
+                }
+            }
+        }
+        let (repr, node, value) = input.expect("should have an input");
+        assert!(repr.get_levels().is_ok());
+        assert_eq!("intern-scope-test", value.as_str());
+
+        // Values are released once the workspace and every compilation have been dropped
+        drop(project);
+        drop(compiled);
+        assert!(node.input().is_some());
+        drop(workspace);
+        assert!(node.input().is_none());
+        assert!(repr.get_levels().is_err());
+        assert!(repr.as_node().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_package_query() {
+        let project = test_project(|parser| {
+            parser.with_object_type::<Thunk<Test>>();
+        });
+
+        let compiled = compile_fixture(
+            project,
+            "query.md",
+            r#"```runmd
++ .test demo
+<a/reality.test> a
+|# notify=demo.done
+: .name Hello World
+: .file .test/query.md
+
+<b/reality.test> b
+: .name World Hello
+```"#,
+        )
+        .await
+        .unwrap();
+        let package = compiled.project.unwrap().package().await.unwrap();
+
+        let nodes = package
+            .query("nodes where host ~ demo and annotations contains `notify`")
+            .unwrap();
+        assert_eq!(1, nodes.len());
+        assert_eq!(
+            Some("a".to_string()),
+            nodes[0]
+                .as_node()
+                .and_then(|n| n.input())
+                .map(|i| i.to_string())
+        );
+
+        assert_eq!(
+            Some("demo/a/reality.test".to_string()),
+            nodes[0]
+                .as_host()
+                .and_then(|h| h.address())
+                .map(|a| a.to_string())
+        );
+
+        let fields = package.query("fields where type = PathBuf").unwrap();
+        assert_eq!(1, fields.len());
+        assert_eq!(Some("file"), fields[0].field_name());
+
+        let hosts = package.query("hosts where host ~ demo").unwrap();
+        assert_eq!(3, hosts.len());
+
+        assert!(package.query("nodes where color = red").is_err());
+    }
 }
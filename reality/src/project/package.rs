@@ -1,9 +1,11 @@
+use std::collections::BTreeSet;
 use std::fmt::Debug;
 
 use clap::Arg;
 use runir::prelude::*;
 use tracing::{debug, trace, warn};
 
+use super::workspace::PROFILE_ARG;
 use super::Program;
 use crate::ResourceKey;
 use crate::Workspace;
@@ -54,6 +56,58 @@ impl Package {
     pub fn programs_mut(&mut self) -> impl Iterator<Item = &mut Program> {
         self.programs.iter_mut()
     }
+
+    /// Returns every representation interned by the programs in the package,
+    ///
+    /// Includes the representations of each node, attribute and property, as well as the fields of each receiver and
+    /// the extensions of each host. Each representation is only returned once.
+    ///
+    pub fn reprs(&self) -> Vec<Repr> {
+        let mut reprs = vec![];
+        let mut visited = BTreeSet::new();
+
+        let keys = self.programs.iter().flat_map(|p| {
+            std::iter::once(p.node.node.repr())
+                .chain(p.node.attributes.iter().map(|a| a.repr()))
+                .chain(p.node.properties.iter().map(|p| p.repr()))
+        });
+
+        let mut queue = keys.flatten().collect::<Vec<_>>();
+        queue.reverse();
+
+        while let Some(repr) = queue.pop() {
+            if !visited.insert(repr) {
+                continue;
+            }
+            reprs.push(repr);
+
+            let mut children = vec![];
+            if let Some(fields) = repr.as_recv().and_then(|r| r.fields()) {
+                children.extend(fields.iter().copied());
+            }
+            if let Some(extensions) = repr.as_host().and_then(|h| h.extensions()) {
+                children.extend(extensions.iter().copied());
+            }
+            queue.extend(children.into_iter().rev());
+        }
+
+        reprs
+    }
+
+    /// Runs a query over every representation in the package,
+    ///
+    /// **Example**
+    ///
+    /// ```rs no_run
+    /// // All nodes in host `demo` whose annotations contain `notify`
+    /// let nodes = package.query("nodes where host ~ demo and annotations ~ notify")?;
+    /// ```
+    ///
+    pub fn query(&self, query: impl AsRef<str>) -> anyhow::Result<Vec<Repr>> {
+        let query = query.as_ref().parse::<Query>()?;
+
+        Ok(query.run(self.reprs()))
+    }
 }
 
 /// Struct containing the result of a program search,
@@ -85,6 +139,23 @@ impl From<Package> for clap::Command {
         // Package name is the name of the command
         let mut command = clap::Command::new(name);
 
+        // Profiles of the workspace can be selected w/ `--profile`
+        if !value.workspace.profiles.is_empty() {
+            let mut arg = Arg::new(PROFILE_ARG)
+                .long("profile")
+                .global(true)
+                .help("Profile of property overrides to compile the workspace with")
+                .value_parser(clap::builder::PossibleValuesParser::new(
+                    value.workspace.profiles.keys().cloned(),
+                ));
+
+            if let Some(profile) = value.workspace.profile.as_ref() {
+                arg = arg.default_value(profile.to_string());
+            }
+
+            command = command.arg(arg);
+        }
+
         // Map out all of the programs into their own subcommand
         for m in value.search("*") {
             let add = m.host.address().expect("should be an address");
@@ -0,0 +1,591 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use runir::prelude::NodeRepr;
+use runir::prelude::SourceSpan;
+use tracing::debug;
+
+use crate::FieldPacket;
+use crate::Frame;
+use crate::ParsedNode;
+
+/// Edit that replaces the value of a property defined in a runmd source,
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PropertyEdit {
+    /// Relative path of the source that defined the property,
+    ///
+    pub relative: PathBuf,
+    /// Span of the property line in the source,
+    ///
+    pub span: SourceSpan,
+    /// Name of the property,
+    ///
+    pub name: String,
+    /// Next value of the property,
+    ///
+    pub value: String,
+}
+
+/// Collection of property edits that write field values back to the runmd sources that defined them,
+///
+/// Only the input of each property line is replaced, so surrounding markdown, comments and `|#` annotations are
+/// preserved,
+///
+/// ```text
+/// : .uri http://localhost:8080 # Endpoint    ->    : .uri https://staging.example.com # Endpoint
+/// ```
+///
+/// **Note** Fields must have been defined by a property in order to be persisted. Multi-line inputs, secrets, and
+/// properties that were interpolated or overridden by a profile cannot be persisted.
+///
+#[derive(Clone, Debug, Default)]
+pub struct Persist {
+    /// Edits by the relative path of the source,
+    ///
+    edits: BTreeMap<PathBuf, Vec<PropertyEdit>>,
+}
+
+impl Persist {
+    /// Returns a new empty set of edits,
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an edit for each packet in a frame,
+    ///
+    /// Fields that cannot be persisted, such as fields w/o a property, secrets, or properties that were interpolated or
+    /// overridden by a profile, are skipped.
+    ///
+    pub fn edit_frame(&mut self, node: &ParsedNode, frame: &Frame) -> anyhow::Result<()> {
+        for packet in frame.fields.iter() {
+            match defining_property(node, packet) {
+                Ok(property) => self.edit_property(&property, packet, packet.to_text()?)?,
+                Err(err) => debug!(field = packet.field_name, "Skipping field, {err}"),
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds an edit that persists the value of a field packet to the property that defined the field,
+    ///
+    pub fn edit(&mut self, node: &ParsedNode, packet: &FieldPacket) -> anyhow::Result<()> {
+        let property = defining_property(node, packet)?;
+        self.edit_property(&property, packet, packet.to_text()?)
+    }
+
+    /// Adds an edit that persists a text value to the property that defined the field of a packet,
+    ///
+    pub fn edit_text(
+        &mut self,
+        node: &ParsedNode,
+        packet: &FieldPacket,
+        value: impl Into<String>,
+    ) -> anyhow::Result<()> {
+        let property = defining_property(node, packet)?;
+        self.edit_property(&property, packet, value.into())
+    }
+
+    /// Adds an edit that replaces the input of a property w/ a value,
+    ///
+    fn edit_property(
+        &mut self,
+        property: &NodeRepr,
+        packet: &FieldPacket,
+        value: String,
+    ) -> anyhow::Result<()> {
+        let (Some(relative), Some(span)) = (property.relative(), property.span()) else {
+            return Err(anyhow!(
+                "Property `{}` does not have a source location",
+                packet.field_name
+            ));
+        };
+
+        self.push(PropertyEdit {
+            relative: relative.as_ref().clone(),
+            span: span.as_ref().clone(),
+            name: packet.field_name.to_string(),
+            value,
+        });
+        Ok(())
+    }
+
+    /// Adds an edit, replacing any previous edit of the same property,
+    ///
+    pub fn push(&mut self, edit: PropertyEdit) {
+        let edits = self.edits.entry(edit.relative.clone()).or_default();
+        edits.retain(|e| e.span != edit.span);
+        edits.push(edit);
+    }
+
+    /// Returns an iterator over the relative paths of sources w/ edits,
+    ///
+    pub fn sources(&self) -> impl Iterator<Item = &PathBuf> {
+        self.edits.keys()
+    }
+
+    /// Returns true if there are no edits,
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Returns the content of a source w/ the edits for the source applied,
+    ///
+    /// Returns an error if a property line no longer matches the span it was parsed from.
+    ///
+    pub fn rewrite(&self, relative: impl AsRef<Path>, content: &str) -> anyhow::Result<String> {
+        let mut edits = self
+            .edits
+            .get(relative.as_ref())
+            .map(|e| e.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        // Edits are applied from the end of the source so that earlier spans are unchanged
+        edits.sort_by_key(|e| std::cmp::Reverse(e.span.start));
+
+        let mut content = content.to_string();
+        for edit in edits {
+            let line_end = content
+                .get(edit.span.start..)
+                .and_then(|l| l.find('\n'))
+                .map(|end| edit.span.start + end)
+                .unwrap_or(content.len());
+
+            let line = content.get(edit.span.start..line_end).ok_or(anyhow!(
+                "Source {:?} has changed since it was parsed",
+                edit.relative
+            ))?;
+
+            let (start, end) = input_range(line, &edit.name).ok_or(anyhow!(
+                "Could not find property `{}` in {:?}, the source has changed since it was parsed",
+                edit.name,
+                edit.relative
+            ))?;
+
+            if is_interpolated(line, &edit.name) {
+                return Err(anyhow!(
+                    "Property `{}` in {:?} is interpolated and cannot be persisted",
+                    edit.name,
+                    edit.relative
+                ));
+            }
+
+            let value = escape(&edit.value)?;
+            let value = if start == end && !line[..start].ends_with(char::is_whitespace) {
+                format!(" {value}")
+            } else {
+                value
+            };
+
+            content.replace_range(edit.span.start + start..edit.span.start + end, &value);
+        }
+
+        Ok(content)
+    }
+
+    /// Writes the edits to the sources on disk,
+    ///
+    /// Returns the paths of the sources that were written.
+    ///
+    pub async fn write(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let mut written = vec![];
+        for relative in self.sources() {
+            let content = tokio::fs::read_to_string(relative)
+                .await
+                .map_err(|err| anyhow!("Could not read {:?}, {err}", relative))?;
+
+            let content = self.rewrite(relative, &content)?;
+            tokio::fs::write(relative, content)
+                .await
+                .map_err(|err| anyhow!("Could not write {:?}, {err}", relative))?;
+            written.push(relative.clone());
+        }
+        Ok(written)
+    }
+}
+
+/// Returns the property that defined the field of a packet if the property can be persisted,
+///
+fn defining_property(node: &ParsedNode, packet: &FieldPacket) -> anyhow::Result<NodeRepr> {
+    let mut defined = node
+        .properties
+        .iter()
+        .filter_map(|p| p.field().zip(p.node()))
+        .filter(|(f, _)| {
+            f.owner_name() == Some(packet.owner_name.as_str())
+                && f.offset() == Some(packet.field_offset)
+        });
+
+    let (Some((field, property)), None) = (defined.next(), defined.next()) else {
+        return Err(anyhow!(
+            "Field `{}` of `{}` must be defined by exactly one property to be persisted",
+            packet.field_name,
+            packet.owner_name
+        ));
+    };
+
+    if field.is_secret() {
+        return Err(anyhow!(
+            "Secret field `{}` cannot be persisted",
+            packet.field_name
+        ));
+    }
+
+    if let Some(profile) = property
+        .annotations()
+        .and_then(|a| a.get("profile").cloned())
+    {
+        return Err(anyhow!(
+            "Field `{}` is overridden by profile `{profile}` and cannot be persisted",
+            packet.field_name
+        ));
+    }
+
+    if property
+        .source()
+        .is_some_and(|line| is_interpolated(&line, &packet.field_name))
+    {
+        return Err(anyhow!(
+            "Field `{}` is interpolated and cannot be persisted",
+            packet.field_name
+        ));
+    }
+
+    Ok(property)
+}
+
+/// Returns true if the input of a property line contains a variable,
+///
+fn is_interpolated(line: &str, name: &str) -> bool {
+    input_range(line, name).is_some_and(|(start, end)| {
+        let input = &line[start..end];
+        input.match_indices("${").any(|(idx, _)| {
+            // A variable is escaped if it is preceded by an even number of `$`
+            !input[..idx + 1]
+                .chars()
+                .rev()
+                .take_while(|c| *c == '$')
+                .count()
+                .is_multiple_of(2)
+        })
+    })
+}
+
+/// Returns the range of the input of a property line,
+///
+/// If the property does not have an input, an empty range after the property name is returned.
+///
+fn input_range(line: &str, name: &str) -> Option<(usize, usize)> {
+    let rest = line.trim_start().strip_prefix(':')?;
+    let mut offset = line.len() - rest.len();
+
+    // Skip the optional tag before the property name
+    let name_start = rest.find('.')?;
+    if !rest[..name_start]
+        .trim()
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return None;
+    }
+    offset += name_start + 1;
+
+    let rest = line[offset..].strip_prefix(name)?;
+    if rest.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return None;
+    }
+    offset += name.len();
+
+    let trimmed = rest.trim_start();
+    let start = offset + (rest.len() - trimmed.len());
+
+    let end = match trimmed.chars().next() {
+        Some(quote @ ('"' | '`')) => start + 1 + trimmed[1..].find(quote)? + 1,
+        // A `#` only starts a comment after whitespace
+        _ => {
+            start
+                + trimmed
+                    .char_indices()
+                    .find(|(idx, c)| {
+                        matches!(c, '"' | '`')
+                            || (*c == '#' && trimmed[..*idx].ends_with(char::is_whitespace))
+                    })
+                    .map(|(idx, _)| idx)
+                    .unwrap_or(trimmed.len())
+        }
+    };
+
+    // Whitespace before a trailing comment is preserved
+    let end = start + line[start..end].trim_end().len();
+    Some((start, end))
+}
+
+/// Returns a value escaped so that it is parsed as the same input,
+///
+fn escape(value: &str) -> anyhow::Result<String> {
+    if value.contains('\n') {
+        return Err(anyhow!("Multi-line values cannot be persisted"));
+    }
+
+    // Variables are escaped so that the value is not interpolated when it is parsed
+    let mut escaped = String::with_capacity(value.len());
+    let mut run = 0;
+    for c in value.chars() {
+        if c == '$' {
+            run += 1;
+            continue;
+        }
+        escaped.push_str(&"$".repeat(if c == '{' { run * 2 } else { run }));
+        escaped.push(c);
+        run = 0;
+    }
+    escaped.push_str(&"$".repeat(run));
+    let value = escaped.as_str();
+
+    let plain = !value.is_empty()
+        && value.trim() == value
+        && !value.contains(['"', '`'])
+        && !value.split_whitespace().any(|w| w.starts_with('#'));
+
+    if plain {
+        Ok(value.to_string())
+    } else if !value.contains('`') {
+        Ok(format!("`{value}`"))
+    } else if !value.contains('"') {
+        Ok(format!("\"{value}\""))
+    } else {
+        Err(anyhow!("Value cannot be escaped, {value}"))
+    }
+}
+
+#[cfg(test)]
+#[allow(unused)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::project::fixture::test_project;
+    use crate::Profile;
+    use tokio::runtime::Handle;
+
+    #[derive(Reality, Debug, Default, Clone)]
+    #[reality(call = test, plugin)]
+    pub struct TestPersist {
+        #[reality(derive_fromstr)]
+        name: String,
+        uri: String,
+        path: PathBuf,
+    }
+
+    async fn test(_: &mut ThunkContext) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Compiles the workspace and returns the parsed node and initialized plugin,
+    ///
+    async fn compile(workspace: &Workspace) -> (ParsedNode, TestPersist) {
+        let project = test_project(|parser| {
+            parser.with_object_type::<Thunk<TestPersist>>();
+        });
+
+        let workspace = workspace.compile(project).await.unwrap();
+        let project = workspace.project.unwrap();
+        let nodes = project.nodes.read().await;
+        let (_, store) = nodes.iter().next().unwrap();
+        let node = store
+            .read()
+            .await
+            .root_ref()
+            .current::<ParsedNode>()
+            .unwrap();
+        let target = AsyncStorageTarget::from_parts(store.clone(), Handle::current());
+        let mut tc: ThunkContext = target.into();
+        tc.set_attribute(*node.attributes.first().unwrap());
+        let plugin = tc.initialized::<TestPersist>().await;
+        (node, plugin)
+    }
+
+    #[tokio::test]
+    async fn test_workspace_persist() {
+        let mut workspace = EmptyWorkspace.workspace();
+        workspace.add_buffer(
+            "persist.md",
+            r#"# Persist
+
+```runmd
++ .test persist
+<a/reality.testpersist> a
+: .uri http://localhost:8080 # Endpoint
+|# title=Endpoint
+: .path
+```
+
+Notes about the endpoint.
+"#,
+        );
+
+        let (node, test) = compile(&workspace).await;
+        assert_eq!("a", test.name);
+        assert_eq!("http://localhost:8080", test.uri);
+
+        let edit = TestPersist {
+            uri: String::from("https://staging.example.com"),
+            path: PathBuf::from("# notes.md"),
+            ..test.clone()
+        };
+        let frame = edit.to_frame(ResourceKey::new());
+        let mut persist = Persist::new();
+
+        // The name is not defined by a property, so it is skipped when the frame is edited
+        assert!(persist.edit(&node, &frame.fields[0]).is_err());
+        persist.edit_frame(&node, &frame).unwrap();
+
+        let persisted = workspace.persist(&persist).await.unwrap();
+        assert_eq!(vec![PathBuf::from("persist.md")], persisted);
+
+        let Source::TextBuffer { source, .. } = &workspace.sources[0] else {
+            unreachable!()
+        };
+        assert_eq!(
+            r#"# Persist
+
+```runmd
++ .test persist
+<a/reality.testpersist> a
+: .uri https://staging.example.com # Endpoint
+|# title=Endpoint
+: .path `# notes.md`
+```
+
+Notes about the endpoint.
+"#,
+            source
+        );
+
+        let (_, test) = compile(&workspace).await;
+        assert_eq!("a", test.name);
+        assert_eq!("https://staging.example.com", test.uri);
+        assert_eq!(PathBuf::from("# notes.md"), test.path);
+    }
+
+    #[tokio::test]
+    async fn test_persist_skips_interpolated_and_profile() {
+        let source = r#"```runmd
++ .test persist
+<a/reality.testpersist> a
+: .uri http://localhost:8080
+: .path ${prop:uri}/notes.md
+```"#;
+        let mut workspace = EmptyWorkspace.workspace();
+        workspace.add_buffer("persist.md", source);
+        workspace.add_profile(Profile::new("staging").with_override(
+            "engine://persist/a/reality.testpersist",
+            "uri",
+            "https://staging",
+        ));
+        workspace.set_profile("staging");
+
+        let (node, test) = compile(&workspace).await;
+        assert_eq!("https://staging", test.uri);
+        assert_eq!(PathBuf::from("https://staging/notes.md"), test.path);
+
+        let edit = TestPersist {
+            uri: String::from("https://staging.example.com"),
+            path: PathBuf::from("notes.md"),
+            ..test.clone()
+        };
+        let frame = edit.to_frame(ResourceKey::new());
+        let mut persist = Persist::new();
+
+        // The uri is overridden by the profile and the path is interpolated
+        let err = persist.edit(&node, &frame.fields[1]).unwrap_err();
+        assert!(err.to_string().contains("profile `staging`"), "{err}");
+        let err = persist.edit(&node, &frame.fields[2]).unwrap_err();
+        assert!(err.to_string().contains("interpolated"), "{err}");
+
+        persist.edit_frame(&node, &frame).unwrap();
+        assert!(persist.is_empty());
+    }
+
+    #[test]
+    fn test_persist_rewrite() {
+        let source = r#"# Example
+
+```runmd
++ .test api
+<a/reality.testprofile> a
+: .uri http://localhost:8080   # Endpoint
+|# title=Endpoint
+: mock .timeout
+: .name `hello # world`
+: .link http://x/#frag # Link
+```
+"#;
+
+        let span = |line: &str| {
+            let start = source.find(line).unwrap();
+            start..start + line.len() + 1
+        };
+
+        let mut persist = Persist::new();
+        for (line, name, value) in [
+            (": .uri", "uri", "https://staging.example.com"),
+            (": mock .timeout", "timeout", "30"),
+            (": .name", "name", "hello `world`"),
+            (": .link", "link", "http://y/#frag"),
+        ] {
+            persist.push(PropertyEdit {
+                relative: PathBuf::from("test.md"),
+                span: span(line),
+                name: name.to_string(),
+                value: value.to_string(),
+            });
+        }
+
+        assert_eq!(
+            r#"# Example
+
+```runmd
++ .test api
+<a/reality.testprofile> a
+: .uri https://staging.example.com   # Endpoint
+|# title=Endpoint
+: mock .timeout 30
+: .name "hello `world`"
+: .link http://y/#frag # Link
+```
+"#,
+            persist.rewrite("test.md", source).unwrap()
+        );
+
+        // Sources w/o edits are unchanged
+        assert_eq!(source, persist.rewrite("other.md", source).unwrap());
+
+        // Edits that no longer match the source are rejected
+        let mut persist = Persist::new();
+        persist.push(PropertyEdit {
+            relative: PathBuf::from("test.md"),
+            span: span(": .uri"),
+            name: String::from("timeout"),
+            value: String::from("30"),
+        });
+        assert!(persist.rewrite("test.md", source).is_err());
+    }
+
+    #[test]
+    fn test_persist_escape() {
+        assert_eq!("http://x/#frag", escape("http://x/#frag").unwrap());
+        assert_eq!("`# notes`", escape("# notes").unwrap());
+        assert_eq!("`a # b`", escape("a # b").unwrap());
+        assert_eq!("$${env:HOME}", escape("${env:HOME}").unwrap());
+        assert_eq!("$$$${env:HOME} $", escape("$${env:HOME} $").unwrap());
+
+        // Interpolated properties are not rewritten
+        assert!(is_interpolated(": .path ${env:HOME}/notes", "path"));
+        assert!(!is_interpolated(": .path $${env:HOME}/notes", "path"));
+        assert!(!is_interpolated(": .path ~/notes # ${env:HOME}", "path"));
+    }
+}
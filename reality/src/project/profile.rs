@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::anyhow;
+
+/// Profile of property overrides that is applied on top of the base sources of a workspace,
+///
+/// Overrides are addressed by the address of the hosted resource and the name of the field, each line of a profile
+/// is formatted as `<address> .<field> <value>`,
+///
+/// ```text
+/// # Lines starting w/ `#` are comments
+/// engine://request/a/builtin.request .uri https://staging.example.com
+/// request/a/builtin.request#mock .config.timeout 30
+/// ```
+///
+/// **Note** The host of an address is optional, i.e. `engine://request` and `request` refer to the same resource.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Profile {
+    /// Name of the profile, i.e. `staging`,
+    ///
+    pub name: String,
+    /// Table of overrides by address and field name,
+    ///
+    overrides: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl Profile {
+    /// Returns a new empty profile,
+    ///
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            overrides: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the profile w/ an override set,
+    ///
+    pub fn with_override(
+        mut self,
+        address: impl AsRef<str>,
+        field: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.set_override(address, field, value);
+        self
+    }
+
+    /// Sets the value of a field of the resource hosted at address,
+    ///
+    pub fn set_override(
+        &mut self,
+        address: impl AsRef<str>,
+        field: impl Into<String>,
+        value: impl Into<String>,
+    ) {
+        self.overrides
+            .entry(normalize(address.as_ref()).to_string())
+            .or_default()
+            .insert(field.into(), value.into());
+    }
+
+    /// Returns the value of a field override of the resource hosted at address,
+    ///
+    pub fn get(&self, address: impl AsRef<str>, field: impl AsRef<str>) -> Option<&String> {
+        self.overrides
+            .get(normalize(address.as_ref()))
+            .and_then(|o| o.get(field.as_ref()))
+    }
+
+    /// Returns an iterator over each override as `(address, field, value)`,
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String, &String)> {
+        self.overrides
+            .iter()
+            .flat_map(|(a, o)| o.iter().map(move |(f, v)| (a, f, v)))
+    }
+
+    /// Parses a profile from content,
+    ///
+    pub fn parse(name: impl Into<String>, content: impl AsRef<str>) -> anyhow::Result<Self> {
+        let mut profile = Profile::new(name);
+
+        for (idx, line) in content.as_ref().lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (address, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let rest = rest.trim_start();
+            let (field, value) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+
+            match field.strip_prefix('.') {
+                Some(field) if !field.is_empty() => {
+                    profile.set_override(address, field, value.trim());
+                }
+                _ => {
+                    return Err(anyhow!(
+                        "Could not parse line {} of profile `{}`, expected `<address> .<field> <value>`",
+                        idx + 1,
+                        profile.name
+                    ))
+                }
+            }
+        }
+
+        Ok(profile)
+    }
+
+    /// Loads a profile from a file, the name of the profile is the file stem, i.e. `staging.profile`,
+    ///
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let name = path
+            .file_stem()
+            .and_then(|n| n.to_str())
+            .ok_or(anyhow!("Could not derive a profile name from {:?}", path))?;
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| anyhow!("Could not read profile {:?}, {err}", path))?;
+
+        Profile::parse(name, content)
+    }
+}
+
+/// Returns an address w/o the host,
+///
+fn normalize(address: &str) -> &str {
+    address.split_once("://").map(|(_, a)| a).unwrap_or(address)
+}
+
+#[test]
+fn test_profile_parse() {
+    let profile = Profile::parse(
+        "staging",
+        r#"
+# Staging endpoints
+engine://request/a/builtin.request .uri https://staging.example.com
+request/a/builtin.request#mock .config.timeout   30
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        Some(&"https://staging.example.com".to_string()),
+        profile.get("request/a/builtin.request", "uri")
+    );
+    assert_eq!(
+        Some(&"30".to_string()),
+        profile.get("test://request/a/builtin.request#mock", "config.timeout")
+    );
+    assert_eq!(None, profile.get("request/a/builtin.request#mock", "uri"));
+    assert_eq!(2, profile.iter().count());
+
+    assert!(Profile::parse("bad", "request/a/builtin.request uri value").is_err());
+}
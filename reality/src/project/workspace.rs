@@ -1,15 +1,27 @@
+use anyhow::anyhow;
+use runir::prelude::InternScope;
+use runir::prelude::InternTableStats;
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::path::PathBuf;
 use tracing::debug;
 use tracing::info;
 use tracing::warn;
 
+use super::Persist;
+use super::Profile;
 use super::Source;
+use crate::Diagnostics;
 use crate::Project;
 use crate::Shared;
 use crate::StorageTarget;
+use crate::StorageTargetEntry;
 use crate::StorageTargetEntryMut;
 
+/// Id of the argument used to select a profile from the command generated from a package,
+///
+pub(crate) const PROFILE_ARG: &str = "workspace_profile";
+
 /// Pointer struct for creating a workspace based on the current directory,
 ///
 pub struct CurrentDir;
@@ -29,6 +41,8 @@ pub struct Dir(pub PathBuf);
 impl Dir {
     /// Scans the directory for .md and .runmd files and returns a workspace,
     ///
+    /// **Note** Files w/ a .profile extension are added as profiles named after the file stem, i.e. `staging.profile`.
+    ///
     pub fn workspace(self) -> Workspace {
         let mut workspace = Empty.workspace();
 
@@ -51,6 +65,15 @@ fn read_dir(workspace: &mut Workspace, dir: impl AsRef<Path>) {
                         debug!("Adding -- {:?}", entry.path());
                         workspace.add_local(entry.path());
                     }
+                    Some("profile") => match Profile::load(entry.path()) {
+                        Ok(profile) => {
+                            debug!("Adding profile -- {:?}", entry.path());
+                            workspace.add_profile(profile);
+                        }
+                        Err(err) => {
+                            warn!("Couldn't load profile - {err}");
+                        }
+                    },
                     _ => {}
                 }
             }
@@ -85,6 +108,21 @@ pub struct Workspace {
     /// Project to compile sources with,
     ///
     pub project: Option<Project<Shared>>,
+    /// CLI arguments that can be interpolated into inputs w/ `${arg:name}`,
+    ///
+    pub args: BTreeMap<String, String>,
+    /// Profiles of property overrides that can be selected when compiling,
+    ///
+    pub profiles: BTreeMap<String, Profile>,
+    /// Name of the selected profile,
+    ///
+    pub profile: Option<String>,
+    /// Scope that owns the values interned while compiling the workspace,
+    ///
+    /// **Note** A copy of the workspace is stored in the root of the compiled project, so the interned values are
+    /// released once every clone of the scope, including compiled workspaces and projects, has been dropped.
+    ///
+    pub intern_scope: Option<InternScope>,
 }
 
 impl std::fmt::Debug for Workspace {
@@ -92,6 +130,9 @@ impl std::fmt::Debug for Workspace {
         f.debug_struct("Workspace")
             .field("name", &self.name)
             .field("sources", &self.sources)
+            .field("args", &self.args)
+            .field("profiles", &self.profiles)
+            .field("profile", &self.profile)
             .finish()
     }
 }
@@ -102,6 +143,10 @@ impl Clone for Workspace {
             name: self.name.to_string(),
             sources: self.sources.clone(),
             project: None,
+            args: self.args.clone(),
+            profiles: self.profiles.clone(),
+            profile: self.profile.clone(),
+            intern_scope: self.intern_scope.clone(),
         }
     }
 }
@@ -120,6 +165,10 @@ impl Workspace {
             name: String::new(),
             sources: vec![],
             project: None,
+            args: BTreeMap::new(),
+            profiles: BTreeMap::new(),
+            profile: None,
+            intern_scope: None,
         }
     }
 
@@ -157,10 +206,139 @@ impl Workspace {
         self.sources.push(source);
     }
 
+    /// Sets a CLI argument that can be interpolated into inputs w/ `${arg:name}`,
+    ///
+    pub fn set_arg(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.args.insert(name.into(), value.into());
+    }
+
+    /// Adds a profile of property overrides to the workspace,
+    ///
+    pub fn add_profile(&mut self, profile: Profile) {
+        self.profiles.insert(profile.name.to_string(), profile);
+    }
+
+    /// Selects the profile to apply when the workspace is compiled,
+    ///
+    pub fn set_profile(&mut self, name: impl Into<String>) {
+        self.profile = Some(name.into());
+    }
+
+    /// Sets the intern scope that owns the values interned when the workspace is compiled,
+    ///
+    /// **Note** Values that are still in use after the scope has been dropped, i.e. a `Repr` stored in a plugin, can no
+    /// longer be resolved. Long-running processes that recompile a workspace should set a new scope for each
+    /// compilation and drop the previous compilation as a unit.
+    ///
+    pub fn set_intern_scope(&mut self, scope: InternScope) {
+        self.intern_scope = Some(scope);
+    }
+
+    /// Returns statistics for the values interned while compiling this workspace,
+    ///
+    /// **Note** Returns an empty list if an intern scope has not been set.
+    ///
+    pub fn intern_stats(&self) -> Vec<InternTableStats> {
+        self.intern_scope
+            .as_ref()
+            .map(|s| s.stats())
+            .unwrap_or_default()
+    }
+
+    /// Sets CLI arguments from matches of a command, i.e. a command generated from a package,
+    ///
+    /// **Note** Arguments of subcommands are included, if an argument has multiple values the last value is used. The
+    /// `--profile` flag selects the profile instead of setting an argument.
+    ///
+    pub fn set_args_from_matches(&mut self, matches: &clap::ArgMatches) {
+        for id in matches.ids() {
+            if let Some(value) = matches
+                .try_get_raw(id.as_str())
+                .ok()
+                .flatten()
+                .and_then(|mut v| v.next_back())
+            {
+                if id.as_str() == PROFILE_ARG {
+                    self.set_profile(value.to_string_lossy());
+                } else {
+                    self.set_arg(id.as_str(), value.to_string_lossy());
+                }
+            }
+        }
+
+        if let Some((_, matches)) = matches.subcommand() {
+            self.set_args_from_matches(matches);
+        }
+    }
+
+    /// Sets CLI arguments by matching a command line against the command generated from the compiled package,
+    ///
+    /// **Note** The workspace is compiled w/ project to generate the command. If the command line does not match, i.e.
+    /// `--help` or a missing subcommand, arguments are left unchanged so that the error can be reported when the command
+    /// is processed.
+    ///
+    pub async fn set_args_from_command(
+        &mut self,
+        project: Project<Shared>,
+        args: impl IntoIterator<Item = impl Into<String>>,
+    ) -> anyhow::Result<()> {
+        let package = self
+            .compile(project)
+            .await?
+            .project
+            .take()
+            .ok_or(anyhow!("Workspace was not compiled"))?
+            .package()
+            .await?;
+
+        let command: clap::Command = package.into();
+        match command.try_get_matches_from(args.into_iter().map(Into::<String>::into)) {
+            Ok(matches) => self.set_args_from_matches(&matches),
+            Err(err) => debug!("Command line does not match, {err}"),
+        }
+
+        Ok(())
+    }
+
+    /// Compiles the workspace w/ project and the selected profile,
+    ///
+    pub async fn compile_with_profile(
+        &self,
+        project: Project<Shared>,
+        profile: impl Into<String>,
+    ) -> anyhow::Result<Self> {
+        let mut workspace = self.clone();
+        workspace.set_profile(profile);
+        workspace.compile(project).await
+    }
+
     /// Compiles the workspace w/ project,
     ///
-    pub async fn compile(&self, mut project: Project<Shared>) -> anyhow::Result<Self> {
+    /// **Note** If a profile is selected, its overrides are applied to the properties parsed from the base sources.
+    ///
+    /// If an intern scope is set, values interned while compiling are owned by the scope.
+    ///
+    pub async fn compile(&self, project: Project<Shared>) -> anyhow::Result<Self> {
+        if let Some(scope) = self.intern_scope.clone() {
+            scope.enter(self.compile_in_scope(project)).await
+        } else {
+            self.compile_in_scope(project).await
+        }
+    }
+
+    /// Compiles the workspace w/ project in the current intern scope,
+    ///
+    async fn compile_in_scope(&self, mut project: Project<Shared>) -> anyhow::Result<Self> {
         let mut compiled = self.clone();
+        project.set_args(self.args.clone());
+
+        if let Some(name) = self.profile.as_ref() {
+            let profile = self.profiles.get(name).ok_or(anyhow::anyhow!(
+                "Profile `{name}` has not been added to the workspace"
+            ))?;
+            info!("Compiling w/ profile {name}");
+            project.set_profile(profile.clone());
+        }
 
         for source in self.sources.iter() {
             match source {
@@ -175,6 +353,31 @@ impl Workspace {
             }
         }
 
+        // Diagnostics reported by node storages while parsing fail the compilation
+        let mut diagnostics = Diagnostics::default();
+        for (_, node) in project.nodes.read().await.iter() {
+            if let Some(reported) = node.read().await.root_ref().current::<Diagnostics>() {
+                diagnostics.items.extend(reported.items);
+            }
+        }
+
+        if !diagnostics.is_empty() {
+            for diagnostic in diagnostics.items.iter_mut() {
+                if let Some(source) = self.source_content(diagnostic.relative.as_ref()).await {
+                    diagnostic.locate(&source);
+                }
+            }
+
+            diagnostics.items.sort_by_key(|d| {
+                (
+                    d.relative.clone(),
+                    d.span.as_ref().map(|s| s.start).unwrap_or_default(),
+                )
+            });
+
+            return Err(diagnostics.into());
+        }
+
         project.root.root().put(self.clone());
 
         compiled.project = Some(project);
@@ -183,6 +386,71 @@ impl Workspace {
         Ok(compiled)
     }
 
+    /// Returns the content of the source w/ a matching relative path,
+    ///
+    /// **Note** If a source is not found, the relative path is read from disk.
+    ///
+    async fn source_content(&self, relative: Option<&PathBuf>) -> Option<String> {
+        let relative = relative?;
+
+        for source in self.sources.iter() {
+            match source {
+                Source::Local(path) if path == relative => {
+                    return tokio::fs::read_to_string(path).await.ok();
+                }
+                Source::TextBuffer {
+                    relative: r,
+                    source,
+                } if r == relative => {
+                    return Some(source.clone());
+                }
+                _ => {}
+            }
+        }
+
+        // Sources loaded by an include or import directive are read from disk
+        tokio::fs::read_to_string(relative).await.ok()
+    }
+
+    /// Persists edited property values to the sources of the workspace,
+    ///
+    /// Text buffers are rewritten in memory, all other sources are rewritten on disk. Returns the relative paths of the
+    /// sources that were rewritten.
+    ///
+    /// **Note** The workspace must be compiled again for the edits to be reflected by the project.
+    ///
+    pub async fn persist(&mut self, persist: &Persist) -> anyhow::Result<Vec<PathBuf>> {
+        let mut persisted = vec![];
+
+        'sources: for relative in persist.sources() {
+            for source in self.sources.iter_mut() {
+                if let Source::TextBuffer {
+                    relative: r,
+                    source,
+                } = source
+                {
+                    if r == relative {
+                        *source = persist.rewrite(relative, source)?;
+                        persisted.push(relative.clone());
+                        continue 'sources;
+                    }
+                }
+            }
+
+            let content = self
+                .source_content(Some(relative))
+                .await
+                .ok_or(anyhow::anyhow!("Could not read source {:?}", relative))?;
+
+            let content = persist.rewrite(relative, &content)?;
+            tokio::fs::write(relative, content).await?;
+            info!("Persisted {:?}", relative);
+            persisted.push(relative.clone());
+        }
+
+        Ok(persisted)
+    }
+
     /// Returns an iterator over sources,
     ///
     pub async fn iter_sources(&self) -> impl Iterator<Item = &Source> {
@@ -0,0 +1,605 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+
+/// JSON Schema dialect of generated schemas,
+///
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Schema describing the runmd surface of a block object,
+///
+/// **Note** Generated by `#[derive(Reality)]`, use `to_json_schema` to convert to a JSON Schema.
+///
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct PluginSchema {
+    /// Symbol used to load the plugin, i.e. `<group.name>`,
+    ///
+    pub symbol: String,
+    /// Rust type name of the plugin,
+    ///
+    pub ty: String,
+    /// Doc comments of the plugin,
+    ///
+    pub docs: String,
+    /// Name of the field parsed from the input of the extension line,
+    ///
+    pub input: Option<String>,
+    /// Fields that can be defined as properties,
+    ///
+    pub fields: Vec<FieldSchema>,
+}
+
+/// Schema describing a field of a block object,
+///
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct FieldSchema {
+    /// Name of the property,
+    ///
+    pub name: String,
+    /// Rust type name of the value parsed from the property input,
+    ///
+    pub ty: String,
+    /// How values of the property are collected,
+    ///
+    pub kind: FieldKind,
+    /// Doc comments of the field,
+    ///
+    pub docs: String,
+    /// True if the property must be defined,
+    ///
+    pub required: bool,
+    /// Inclusive lower bound of the value,
+    ///
+    pub minimum: Option<f64>,
+    /// Inclusive upper bound of the value,
+    ///
+    pub maximum: Option<f64>,
+    /// Exclusive upper bound of the value,
+    ///
+    pub exclusive_maximum: Option<f64>,
+    /// Pattern the input must match,
+    ///
+    pub pattern: Option<String>,
+    /// Accepted inputs, empty if any input is accepted,
+    ///
+    pub one_of: Vec<String>,
+    /// True if the field is a secret, the input is a reference to the value i.e. `env:NAME`,
+    ///
+    #[serde(default)]
+    pub secret: bool,
+}
+
+/// Enumeration of how values of a field are collected,
+///
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+    /// Property sets a single value,
+    ///
+    #[default]
+    Scalar,
+    /// Property may be omitted,
+    ///
+    Option,
+    /// Each property appends a value, i.e. `vec_of`,
+    ///
+    Vec,
+    /// Each property appends a value, i.e. `vecdeq_of`,
+    ///
+    VecDeque,
+    /// Each property inserts a unique value, i.e. `set_of`,
+    ///
+    Set,
+    /// Each property inserts a value by tag, i.e. `map_of`,
+    ///
+    Map,
+    /// Property is decorated w/ comment properties, i.e. `decorated`,
+    ///
+    Decorated,
+    /// Property is parsed by an attribute type, i.e. `attribute_type`,
+    ///
+    AttributeType,
+    /// Property enters a scope where deeper properties define fields of the value, i.e. `nested`,
+    ///
+    Nested,
+    /// Property loads an extension, i.e. `ext`,
+    ///
+    Ext,
+}
+
+/// Wraps the bound of a field's range so that derived schemas only report numeric bounds,
+///
+/// When `T` is a numeric primitive, `(&SchemaBound(bound)).to_f64()` resolves to `NumericSchemaBound`, otherwise it
+/// resolves to `AnySchemaBound` and returns None.
+///
+#[doc(hidden)]
+pub struct SchemaBound<T>(pub T);
+
+/// Converts numeric bounds into the f64 values used by JSON Schema,
+///
+#[doc(hidden)]
+pub trait NumericSchemaBound {
+    fn to_f64(&self) -> Option<f64>;
+}
+
+macro_rules! impl_numeric_schema_bound {
+    ($($ty:ty),*) => {
+        $(
+            impl NumericSchemaBound for SchemaBound<$ty> {
+                fn to_f64(&self) -> Option<f64> {
+                    Some(self.0 as f64)
+                }
+            }
+        )*
+    };
+}
+
+impl_numeric_schema_bound!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64
+);
+
+/// Fallback for bounds that cannot be represented in JSON Schema, i.e. `char` or string ranges,
+///
+#[doc(hidden)]
+pub trait AnySchemaBound {
+    fn to_f64(&self) -> Option<f64>;
+}
+
+impl<T> AnySchemaBound for &SchemaBound<T> {
+    fn to_f64(&self) -> Option<f64> {
+        None
+    }
+}
+
+impl PluginSchema {
+    /// Returns the JSON Schema of the plugin,
+    ///
+    pub fn to_json_schema(&self) -> Value {
+        let mut properties = Map::new();
+        let mut required = vec![];
+
+        for field in self.fields.iter() {
+            properties.insert(field.name.clone(), field.to_json_schema());
+
+            if field.required {
+                required.push(Value::from(field.name.clone()));
+            }
+        }
+
+        let mut schema = json!({
+            "$schema": JSON_SCHEMA_DIALECT,
+            "title": self.symbol,
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false,
+            "x-reality-type": self.ty,
+        });
+
+        if !self.docs.is_empty() {
+            schema["description"] = Value::from(self.docs.clone());
+        }
+
+        if let Some(input) = self.input.as_ref() {
+            schema["x-reality-input"] = Value::from(input.clone());
+        }
+
+        schema
+    }
+
+    /// Returns a JSON Schema document w/ the schema of each plugin under `$defs` keyed by symbol,
+    ///
+    pub fn document<'a>(schemas: impl IntoIterator<Item = &'a PluginSchema>) -> Value {
+        let defs = schemas
+            .into_iter()
+            .map(|s| {
+                let mut schema = s.to_json_schema();
+                if let Some(schema) = schema.as_object_mut() {
+                    schema.remove("$schema");
+                }
+                (s.symbol.clone(), schema)
+            })
+            .collect::<Map<_, _>>();
+
+        json!({
+            "$schema": JSON_SCHEMA_DIALECT,
+            "$defs": defs,
+        })
+    }
+}
+
+impl PluginSchema {
+    /// Returns the group of the plugin, i.e. `builtin` for `builtin.process`,
+    ///
+    pub fn group(&self) -> &str {
+        self.symbol
+            .split_once('.')
+            .map(|(group, _)| group)
+            .unwrap_or(self.symbol.as_str())
+    }
+
+    /// Returns a runmd block that loads the plugin as an extension of an operation,
+    ///
+    /// **Note** Each field is defined once w/ an example value derived from the field's type and validation.
+    ///
+    pub fn example_runmd(&self) -> String {
+        let mut runmd = String::from("```runmd\n+ .operation example\n");
+
+        let input = self
+            .input
+            .as_ref()
+            .and_then(|i| self.fields.iter().find(|f| &f.name == i));
+        match input {
+            Some(input) => {
+                let _ = writeln!(runmd, "<{}> {}", self.symbol, input.example());
+            }
+            None => {
+                let _ = writeln!(runmd, "<{}>", self.symbol);
+            }
+        }
+
+        for field in self
+            .fields
+            .iter()
+            .filter(|f| Some(&f.name) != self.input.as_ref() && f.kind != FieldKind::Ext)
+        {
+            match field.kind {
+                FieldKind::Map => {
+                    let _ = writeln!(runmd, ": KEY .{} {}", field.name, field.example());
+                }
+                _ => {
+                    let _ = writeln!(runmd, ": .{} {}", field.name, field.example());
+                }
+            }
+        }
+
+        runmd.push_str("```\n");
+        runmd
+    }
+
+    /// Returns a markdown reference section describing the plugin,
+    ///
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+
+        let _ = writeln!(markdown, "## {}\n", self.symbol);
+        if !self.docs.is_empty() {
+            let _ = writeln!(markdown, "{}\n", self.docs.trim());
+        }
+        let _ = writeln!(markdown, "- **Extension**: `<{}>`", self.symbol);
+        let _ = writeln!(markdown, "- **Type**: `{}`", short_type(&self.ty));
+        if let Some(input) = self.input.as_ref() {
+            let _ = writeln!(markdown, "- **Input**: `{input}`");
+        }
+        markdown.push('\n');
+
+        if !self.fields.is_empty() {
+            markdown.push_str("| Field | Type | Kind | Required | Description |\n");
+            markdown.push_str("| ----- | ---- | ---- | -------- | ----------- |\n");
+            for field in self.fields.iter() {
+                let _ = writeln!(
+                    markdown,
+                    "| `.{}` | `{}` | {} | {} | {} |",
+                    field.name,
+                    short_type(&field.ty),
+                    serde_json::to_value(field.kind)
+                        .ok()
+                        .and_then(|k| k.as_str().map(str::to_string))
+                        .unwrap_or_default(),
+                    if field.required { "yes" } else { "no" },
+                    field.describe()
+                );
+            }
+            markdown.push('\n');
+        }
+
+        let _ = writeln!(markdown, "### Example\n");
+        markdown.push_str(&self.example_runmd());
+        markdown
+    }
+
+    /// Returns markdown reference pages keyed by plugin group,
+    ///
+    /// **Note** Plugins are listed in order of their symbol on each page.
+    ///
+    pub fn reference_pages<'a>(
+        schemas: impl IntoIterator<Item = &'a PluginSchema>,
+    ) -> BTreeMap<String, String> {
+        let mut groups = BTreeMap::<String, Vec<&PluginSchema>>::new();
+        for schema in schemas {
+            groups
+                .entry(schema.group().to_string())
+                .or_default()
+                .push(schema);
+        }
+
+        groups
+            .into_iter()
+            .map(|(group, mut schemas)| {
+                schemas.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+                schemas.dedup_by(|a, b| a.symbol == b.symbol);
+
+                let mut page = format!("# {group}\n\n");
+                let _ = writeln!(page, "Reference of plugins in the `{group}` group.\n");
+                for schema in schemas.iter() {
+                    let _ = writeln!(page, "- [{0}](#{1})", schema.symbol, anchor(&schema.symbol));
+                }
+
+                for schema in schemas {
+                    page.push('\n');
+                    page.push_str(&schema.to_markdown());
+                }
+
+                (group, page)
+            })
+            .collect()
+    }
+}
+
+impl FieldSchema {
+    /// Returns the JSON Schema of the field,
+    ///
+    pub fn to_json_schema(&self) -> Value {
+        let mut value = json!({ "type": json_type(&self.ty) });
+
+        if let Some(minimum) = self.minimum {
+            value["minimum"] = Value::from(minimum);
+        }
+
+        if let Some(maximum) = self.maximum {
+            value["maximum"] = Value::from(maximum);
+        }
+
+        if let Some(exclusive_maximum) = self.exclusive_maximum {
+            value["exclusiveMaximum"] = Value::from(exclusive_maximum);
+        }
+
+        if let Some(pattern) = self.pattern.as_ref() {
+            value["pattern"] = Value::from(pattern.clone());
+        }
+
+        if !self.one_of.is_empty() {
+            value["enum"] = Value::from(self.one_of.clone());
+        }
+
+        let mut schema = match self.kind {
+            FieldKind::Vec | FieldKind::VecDeque => json!({ "type": "array", "items": value }),
+            FieldKind::Set => json!({ "type": "array", "items": value, "uniqueItems": true }),
+            FieldKind::Map => json!({ "type": "object", "additionalProperties": value }),
+            _ => value,
+        };
+
+        schema["x-reality-type"] = Value::from(self.ty.clone());
+
+        if !self.docs.is_empty() {
+            schema["description"] = Value::from(self.docs.clone());
+        }
+
+        schema
+    }
+}
+
+impl FieldSchema {
+    /// Returns an example input of the field,
+    ///
+    fn example(&self) -> String {
+        if let Some(value) = self.one_of.first() {
+            return value.clone();
+        }
+
+        if self.secret {
+            return "env:EXAMPLE_SECRET".to_string();
+        }
+
+        match json_type(&self.ty) {
+            "integer" => self.minimum.map(|m| m as i64).unwrap_or(1).to_string(),
+            "number" => format!("{:?}", self.minimum.unwrap_or(1.0)),
+            "boolean" => "true".to_string(),
+            _ if self.ty.ends_with("PathBuf") => "./example".to_string(),
+            _ => "example".to_string(),
+        }
+    }
+
+    /// Returns the description of the field for a markdown table cell,
+    ///
+    fn describe(&self) -> String {
+        let mut description = self
+            .docs
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut constraints = vec![];
+        match (self.minimum, self.maximum, self.exclusive_maximum) {
+            (Some(min), Some(max), _) => constraints.push(format!("range `{min}..={max}`")),
+            (Some(min), None, Some(max)) => constraints.push(format!("range `{min}..{max}`")),
+            (Some(min), None, None) => constraints.push(format!("minimum `{min}`")),
+            (None, Some(max), _) => constraints.push(format!("maximum `{max}`")),
+            (None, None, Some(max)) => constraints.push(format!("less than `{max}`")),
+            _ => {}
+        }
+        if let Some(pattern) = self.pattern.as_ref() {
+            constraints.push(format!("pattern `{pattern}`"));
+        }
+        if !self.one_of.is_empty() {
+            constraints.push(format!("one of `{}`", self.one_of.join("`, `")));
+        }
+
+        if !constraints.is_empty() {
+            if !description.is_empty() {
+                description.push(' ');
+            }
+            let _ = write!(description, "({})", constraints.join(", "));
+        }
+
+        description.replace('|', "\\|")
+    }
+}
+
+/// Returns a rust type name w/o module paths, i.e. `Vec<String>` for `alloc::vec::Vec<alloc::string::String>`,
+///
+fn short_type(ty: &str) -> String {
+    let mut short = String::new();
+    let mut segment = String::new();
+
+    for c in ty.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            segment.push(c);
+        } else if c == ':' {
+            segment.clear();
+        } else {
+            short.push_str(&segment);
+            segment.clear();
+            short.push(c);
+        }
+    }
+    short.push_str(&segment);
+    short
+}
+
+/// Returns the markdown heading anchor of a plugin symbol,
+///
+fn anchor(symbol: &str) -> String {
+    symbol
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Returns the JSON type of a value parsed as a rust type,
+///
+fn json_type(ty: &str) -> &'static str {
+    match ty.rsplit("::").next().unwrap_or(ty) {
+        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64" | "i128"
+        | "isize" => "integer",
+        "f32" | "f64" => "number",
+        "bool" => "boolean",
+        _ => "string",
+    }
+}
+
+#[test]
+fn test_plugin_schema() {
+    let schema = PluginSchema {
+        symbol: "test.schema".to_string(),
+        ty: "Test".to_string(),
+        docs: "Test plugin".to_string(),
+        input: Some("name".to_string()),
+        fields: vec![
+            FieldSchema {
+                name: "count".to_string(),
+                ty: "u32".to_string(),
+                required: true,
+                minimum: Some(1.0),
+                maximum: Some(10.0),
+                ..Default::default()
+            },
+            FieldSchema {
+                name: "tags".to_string(),
+                ty: "alloc::string::String".to_string(),
+                kind: FieldKind::Set,
+                one_of: vec!["a".to_string(), "b".to_string()],
+                ..Default::default()
+            },
+        ],
+    };
+
+    let json = schema.to_json_schema();
+    assert_eq!("test.schema", json["title"]);
+    assert_eq!("Test plugin", json["description"]);
+    assert_eq!("name", json["x-reality-input"]);
+    assert_eq!(json!(["count"]), json["required"]);
+    assert_eq!("integer", json["properties"]["count"]["type"]);
+    assert_eq!(10.0, json["properties"]["count"]["maximum"]);
+    assert_eq!("array", json["properties"]["tags"]["type"]);
+    assert_eq!(true, json["properties"]["tags"]["uniqueItems"]);
+    assert_eq!(
+        json!(["a", "b"]),
+        json["properties"]["tags"]["items"]["enum"]
+    );
+
+    let document = PluginSchema::document([&schema]);
+    assert_eq!("test.schema", document["$defs"]["test.schema"]["title"]);
+    assert!(document["$defs"]["test.schema"].get("$schema").is_none());
+}
+
+#[test]
+fn test_plugin_reference_pages() {
+    let process = PluginSchema {
+        symbol: "builtin.process".to_string(),
+        ty: "loopio::ext::std_ext::Process".to_string(),
+        docs: "Process plugin,\n".to_string(),
+        input: Some("program".to_string()),
+        fields: vec![
+            FieldSchema {
+                name: "program".to_string(),
+                ty: "alloc::string::String".to_string(),
+                docs: "Name of the program,\n".to_string(),
+                ..Default::default()
+            },
+            FieldSchema {
+                name: "env".to_string(),
+                ty: "alloc::string::String".to_string(),
+                kind: FieldKind::Map,
+                ..Default::default()
+            },
+            FieldSchema {
+                name: "arg".to_string(),
+                ty: "alloc::string::String".to_string(),
+                kind: FieldKind::Vec,
+                docs: "List of arguments | flags,\n".to_string(),
+                ..Default::default()
+            },
+            FieldSchema {
+                name: "timeout".to_string(),
+                ty: "u64".to_string(),
+                minimum: Some(5.0),
+                maximum: Some(60.0),
+                ..Default::default()
+            },
+        ],
+    };
+    let println = PluginSchema {
+        symbol: "builtin.println".to_string(),
+        ..Default::default()
+    };
+    let other = PluginSchema {
+        symbol: "demo.std.io".to_string(),
+        ..Default::default()
+    };
+
+    assert_eq!("demo", other.group());
+    assert_eq!(
+        "Vec<String>",
+        short_type("alloc::vec::Vec<alloc::string::String>")
+    );
+
+    let example = process.example_runmd();
+    assert_eq!(
+        "```runmd\n+ .operation example\n<builtin.process> example\n: KEY .env example\n: .arg example\n: .timeout 5\n```\n",
+        example
+    );
+
+    let pages = PluginSchema::reference_pages([&process, &println, &other]);
+    assert_eq!(vec!["builtin", "demo"], pages.keys().collect::<Vec<_>>());
+
+    let builtin = &pages["builtin"];
+    assert!(builtin.starts_with("# builtin\n"));
+    assert!(
+        builtin.find("## builtin.println").unwrap() < builtin.find("## builtin.process").unwrap()
+    );
+    assert!(builtin.contains("- [builtin.process](#builtinprocess)"));
+    assert!(builtin.contains("- **Extension**: `<builtin.process>`"));
+    assert!(builtin.contains("| `.program` | `String` | scalar | no | Name of the program, |"));
+    assert!(builtin.contains("| `.arg` | `String` | vec | no | List of arguments \\| flags, |"));
+    assert!(builtin.contains("| `.timeout` | `u64` | scalar | no | (range `5..=60`) |"));
+    assert!(builtin.contains(&example));
+}
@@ -0,0 +1,322 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use anyhow::anyhow;
+use once_cell::sync::Lazy;
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Cipher used to decrypt the values of a local secrets file,
+///
+static SECRET_CIPHER: Lazy<RwLock<Option<Arc<dyn SecretCipher>>>> = Lazy::new(|| RwLock::new(None));
+
+/// Trait for decrypting the values of a local secrets file,
+///
+/// **Note** reality does not ship a cipher, applications must register one w/ `set_secret_cipher` before
+/// secrets can be resolved from a file.
+///
+pub trait SecretCipher: Send + Sync + 'static {
+    /// Returns the plaintext of an encrypted value stored under `key`,
+    ///
+    fn decrypt(&self, key: &str, ciphertext: &[u8]) -> anyhow::Result<Vec<u8>>;
+}
+
+/// Sets the cipher used to decrypt the values of local secrets files,
+///
+pub fn set_secret_cipher(cipher: impl SecretCipher) {
+    if let Ok(mut current) = SECRET_CIPHER.write() {
+        *current = Some(Arc::new(cipher));
+    }
+}
+
+/// Secret value of a runmd property,
+///
+/// The input of the property is a reference to the value instead of the value itself,
+///
+/// - `env:NAME` resolves the value from the environment variable `NAME`
+/// - `file:path#key` resolves the value from a local secrets file, where each line is formatted as
+///   `key = <base64 encoded ciphertext>` and decrypted w/ the cipher set by `set_secret_cipher`
+///
+/// The value is redacted from `Debug` and `Display`, and only the reference is serialized. This means
+/// the value is never written to frames or the wire, and is resolved again by the receiving end.
+///
+/// ```runmd
+/// + .operation request
+/// <builtin.request> https://example.com
+/// : Authorization .secret_header env:API_TOKEN
+/// ```
+///
+#[derive(Default)]
+pub struct Secret<T = String> {
+    /// Reference to the secret value,
+    ///
+    source: String,
+    /// Resolved value,
+    ///
+    value: OnceCell<T>,
+}
+
+impl<T> Secret<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    /// Returns a reference to the resolved secret value,
+    ///
+    /// Returns an error if the value could not be resolved from its source.
+    ///
+    pub fn reveal(&self) -> anyhow::Result<&T> {
+        self.value.get_or_try_init(|| {
+            let plaintext = resolve(&self.source)?;
+
+            T::from_str(&plaintext).map_err(|err| {
+                anyhow!(
+                    "Could not parse secret `{}` as {}, {err}",
+                    self.source,
+                    std::any::type_name::<T>()
+                )
+            })
+        })
+    }
+}
+
+impl<T> Secret<T> {
+    /// Returns the reference to the secret value, i.e. `env:API_TOKEN`,
+    ///
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+/// Returns the plaintext value of a secret source,
+///
+fn resolve(source: &str) -> anyhow::Result<String> {
+    match source.split_once(':') {
+        Some(("env", name)) => std::env::var(name)
+            .map_err(|err| anyhow!("Could not resolve secret from environment `{name}`, {err}")),
+        Some(("file", path)) => {
+            let (path, key) = path.split_once('#').ok_or(anyhow!(
+                "Secret file reference is missing a key, i.e. `file:{path}#key`"
+            ))?;
+
+            let cipher = SECRET_CIPHER
+                .read()
+                .ok()
+                .and_then(|c| c.clone())
+                .ok_or(anyhow!(
+                    "Could not resolve secret from `{path}`, a secret cipher has not been set"
+                ))?;
+
+            let content = std::fs::read_to_string(PathBuf::from(path))
+                .map_err(|err| anyhow!("Could not read secrets file `{path}`, {err}"))?;
+
+            let entries = content
+                .lines()
+                .filter_map(|l| l.split_once('='))
+                .map(|(k, v)| (k.trim(), v.trim()))
+                .collect::<BTreeMap<_, _>>();
+
+            let encoded = entries
+                .get(key)
+                .ok_or(anyhow!("Secrets file `{path}` does not have a key `{key}`"))?;
+
+            let ciphertext = base64::decode(encoded)?;
+            let plaintext = cipher.decrypt(key, &ciphertext)?;
+
+            Ok(String::from_utf8(plaintext)?)
+        }
+        _ => Err(anyhow!(
+            "Secrets must reference a source, i.e. `env:NAME` or `file:path#key`"
+        )),
+    }
+}
+
+impl<T> FromStr for Secret<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    type Err = anyhow::Error;
+
+    /// Parses a reference to a secret and resolves the value so that errors are reported while parsing,
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let secret = Secret {
+            source: s.trim().to_string(),
+            value: OnceCell::new(),
+        };
+        secret.reveal()?;
+        Ok(secret)
+    }
+}
+
+impl<T: Clone> Clone for Secret<T> {
+    fn clone(&self) -> Self {
+        Self {
+            source: self.source.clone(),
+            value: self.value.clone(),
+        }
+    }
+}
+
+impl<T> Debug for Secret<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Secret")
+            .field("source", &self.source)
+            .field("value", &"<redacted>")
+            .finish()
+    }
+}
+
+impl<T> Display for Secret<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<redacted>")
+    }
+}
+
+impl<T> PartialEq for Secret<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl<T> Eq for Secret<T> {}
+
+impl<T> PartialOrd for Secret<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Secret<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.source.cmp(&other.source)
+    }
+}
+
+impl<T> Serialize for Secret<T> {
+    /// Only the reference to the secret is serialized,
+    ///
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.source.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Secret<T> {
+    /// The secret is resolved when it is revealed,
+    ///
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(Secret {
+            source: String::deserialize(deserializer)?,
+            value: OnceCell::new(),
+        })
+    }
+}
+
+/// Wraps the parse type of a field so that derived fields can record whether they are secrets,
+///
+/// When `T` is a `Secret`, `(&SecretFlag::<T>(PhantomData)).is_secret()` resolves to `SecretFieldFlag`, otherwise it
+/// resolves to `AnyFieldFlag` and returns false.
+///
+#[doc(hidden)]
+pub struct SecretFlag<T>(pub PhantomData<T>);
+
+/// Flags fields that parse a secret,
+///
+#[doc(hidden)]
+pub trait SecretFieldFlag {
+    fn is_secret(&self) -> bool;
+}
+
+impl<T> SecretFieldFlag for SecretFlag<Secret<T>> {
+    fn is_secret(&self) -> bool {
+        true
+    }
+}
+
+/// Fallback for fields that do not parse a secret,
+///
+#[doc(hidden)]
+pub trait AnyFieldFlag {
+    fn is_secret(&self) -> bool;
+}
+
+impl<T> AnyFieldFlag for &SecretFlag<T> {
+    fn is_secret(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn test_secret() {
+    std::env::set_var("REALITY_TEST_SECRET", "hunter2");
+
+    let secret = Secret::<String>::from_str("env:REALITY_TEST_SECRET").unwrap();
+    assert_eq!("hunter2", secret.reveal().unwrap());
+    assert_eq!("env:REALITY_TEST_SECRET", secret.source());
+
+    assert!(SecretFlag::<Secret<String>>(PhantomData).is_secret());
+    assert!(!(&SecretFlag::<String>(PhantomData)).is_secret());
+
+    let debug = format!("{:?}", secret);
+    assert!(!debug.contains("hunter2"), "{debug}");
+    assert_eq!("<redacted>", secret.to_string());
+
+    // Only the reference is serialized
+    let bin = bincode::serialize(&secret).unwrap();
+    assert!(!String::from_utf8_lossy(&bin).contains("hunter2"));
+    let deserialized = bincode::deserialize::<Secret>(&bin).unwrap();
+    assert_eq!(secret, deserialized);
+    assert_eq!("hunter2", deserialized.reveal().unwrap());
+
+    assert!(Secret::<String>::from_str("hunter2").is_err());
+    assert!(Secret::<String>::from_str("env:REALITY_TEST_SECRET_UNDEFINED").is_err());
+}
+
+#[test]
+fn test_secret_file() {
+    struct Xor;
+
+    impl SecretCipher for Xor {
+        fn decrypt(&self, _: &str, ciphertext: &[u8]) -> anyhow::Result<Vec<u8>> {
+            Ok(ciphertext.iter().map(|b| b ^ 0x2a).collect())
+        }
+    }
+
+    // Unique per process so that concurrent test runs do not share the secrets file
+    let path = std::env::temp_dir().join(format!("reality_test_secrets_{}", std::process::id()));
+    let encrypted = "s3cr3t".bytes().map(|b| b ^ 0x2a).collect::<Vec<_>>();
+    std::fs::write(
+        &path,
+        format!("api_token = {}\n", base64::encode(encrypted)),
+    )
+    .unwrap();
+
+    // The cipher is process-global, this is the only test that sets it so no other test observes it
+    set_secret_cipher(Xor);
+
+    let secret = Secret::<String>::from_str(&format!("file:{}#api_token", path.display())).unwrap();
+    assert_eq!("s3cr3t", secret.reveal().unwrap());
+
+    assert!(
+        Secret::<String>::from_str(&format!("file:{}#missing", path.display()))
+            .unwrap_err()
+            .to_string()
+            .contains("does not have a key")
+    );
+
+    std::fs::remove_file(path).unwrap();
+}
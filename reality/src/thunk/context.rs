@@ -11,6 +11,7 @@ use tokio_util::sync::CancellationToken;
 use tracing::debug;
 use tracing::error;
 use tracing::warn;
+use tracing::Instrument;
 use uuid::Uuid;
 
 use crate::prelude::Latest;
@@ -341,7 +342,7 @@ impl Context {
     ///
     /// Returns a join-handle if the task was created.
     ///
-    /// **Note** Will start immediately on the tokio-runtime.
+    /// **Note** Will start immediately on the tokio-runtime. The task is instrumented w/ the current span.
     ///
     #[inline]
     pub fn spawn<F>(&self, task: impl FnOnce(Context) -> F + Sync + 'static) -> CallOutput
@@ -353,7 +354,7 @@ impl Context {
                 .runtime
                 .clone()
                 .as_ref()
-                .map(|h| h.clone().spawn(task(self.clone()))),
+                .map(|h| h.clone().spawn(task(self.clone()).in_current_span())),
         )
     }
 
@@ -437,6 +438,8 @@ impl Context {
     /// Listens for one packet,
     ///
     #[inline]
+    #[allow(deprecated)]
+    #[deprecated(note = "Packets are committed in batches by `WireServer::start`")]
     pub async fn listen_one<P: Plugin + Sync + Send + 'static>(self) -> ThunkContext {
         if let Some(router) = self.router().await {
             P::listen_one(router).await;
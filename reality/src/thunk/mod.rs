@@ -3,6 +3,7 @@ mod call_async;
 mod context;
 mod kvp_ext;
 mod plugin;
+mod snapshot;
 
 pub mod prelude {
     pub use super::cache_ext::*;
@@ -17,6 +18,8 @@ pub mod prelude {
     pub use super::plugin::NewFn;
     pub use super::plugin::Pack;
     pub use super::plugin::Plugin;
+    pub use super::snapshot::Snapshot;
+    pub use super::snapshot::SnapshotResource;
     pub use crate::AsyncStorageTarget;
     pub use crate::AttributeType;
     use crate::AttributeTypeParser;
@@ -39,6 +42,7 @@ pub mod prelude {
     use crate::FieldPacket;
     use crate::FieldRefController;
     use crate::ParsedNode;
+    use crate::PluginSchema;
     use crate::ResourceKey;
     use crate::SetField;
     use crate::StorageTargetEntry;
@@ -146,6 +150,12 @@ pub mod prelude {
             AttributeTypeParser::new::<Self>(ResourceLevel::new::<P>())
         }
 
+        /// Returns the schema of the inner plugin,
+        ///
+        fn schema() -> PluginSchema {
+            <P as BlockObject>::schema()
+        }
+
         /// Called when the block object is being loaded into it's namespace,
         ///
         async fn on_load(
@@ -96,6 +96,7 @@ pub trait Plugin: ToFrame + BlockObject + CallAsync + Clone + Default {
     /// Listens for one packet,
     ///
     #[allow(unused_variables)]
+    #[deprecated(note = "Packets are committed in batches by `WireServer::start`")]
     fn listen_one(
         router: std::sync::Arc<PacketRouter<Self>>,
     ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
@@ -209,7 +210,7 @@ pub mod repr {
         type Error = anyhow::Error;
 
         fn try_from(value: Repr) -> Result<Self, Self::Error> {
-            if let Some(l) = value.get_levels().get(4) {
+            if let Some(l) = value.get_levels()?.get(4) {
                 Ok(PluginRepr(*l))
             } else {
                 Err(anyhow!(
@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::Frame;
+use crate::ResourceKey;
+use crate::StorageTarget;
+
+use super::prelude::*;
+
+/// Serializable capture of the state of a thunk context,
+///
+/// Snapshots can be used to reproduce a plugin call outside of the environment it originally ran in.
+///
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct Snapshot {
+    /// Address of the context when the snapshot was captured,
+    ///
+    pub address: Option<String>,
+    /// Resource key data of the attribute the context was bound to,
+    ///
+    pub attribute: u128,
+    /// Variant id of the context if it was branched,
+    ///
+    pub variant_id: Option<u128>,
+    /// Local properties set on the context,
+    ///
+    pub properties: BTreeMap<String, String>,
+    /// Wire frame of the plugin state,
+    ///
+    /// **Note** The receiver packet contains the bincode of the entire plugin.
+    ///
+    pub frame: Frame,
+    /// Resources captured from transient storage,
+    ///
+    pub transient: Vec<SnapshotResource>,
+    /// Resources captured from cached storage,
+    ///
+    pub cached: Vec<SnapshotResource>,
+}
+
+/// Serialized resource captured by a snapshot,
+///
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct SnapshotResource {
+    /// Type name of the resource,
+    ///
+    pub type_name: String,
+    /// Resource key data the resource was stored under,
+    ///
+    pub key: u128,
+    /// Bincode of the resource,
+    ///
+    pub data: Vec<u8>,
+}
+
+impl SnapshotResource {
+    /// Returns true if this resource was captured from type T,
+    ///
+    #[inline]
+    fn is<T>(&self) -> bool {
+        self.type_name == std::any::type_name::<T>()
+    }
+
+    /// Decodes the resource and the key it was stored under,
+    ///
+    fn decode<T>(&self) -> anyhow::Result<(ResourceKey<T>, T)>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let key = ResourceKey::<T>::from(Uuid::from_u128(self.key));
+        let value = bincode::deserialize(&self.data)?;
+        Ok((key, value))
+    }
+}
+
+impl Snapshot {
+    /// Captures the current state of plugin P from a thunk context,
+    ///
+    /// **Note** If a cached version of P exists it will be used, otherwise the initialized state is used.
+    ///
+    pub async fn capture<P>(tc: &ThunkContext) -> Self
+    where
+        P: Plugin + Serialize + Sync + Send + 'static,
+    {
+        let plugin = match tc.cached::<P>() {
+            Some(cached) => cached,
+            None => tc.initialized::<P>().await,
+        };
+
+        let properties = tc
+            .attribute
+            .into_link()
+            .and_then(|key| tc.fetch_kv::<LocalAnnotations>(key))
+            .map(|(_, a)| a.map.clone())
+            .unwrap_or_default();
+
+        let address = tc
+            .attribute
+            .host()
+            .and_then(|h| h.address())
+            .map(|a| a.to_string())
+            .or_else(|| tc.property("address"));
+
+        Self {
+            address,
+            attribute: tc.attribute.data,
+            variant_id: tc.variant_id.map(|v| v.as_u128()),
+            properties,
+            frame: plugin.to_wire_frame(tc.attribute),
+            transient: vec![],
+            cached: vec![],
+        }
+    }
+
+    /// Captures resource T stored in transient storage at key,
+    ///
+    pub async fn capture_transient<T>(
+        &mut self,
+        tc: &ThunkContext,
+        key: ResourceKey<T>,
+    ) -> anyhow::Result<&mut Self>
+    where
+        T: Serialize + ToOwned<Owned = T> + Send + Sync + 'static,
+    {
+        let resource = tc
+            .transient()
+            .await
+            .current_resource::<T>(key)
+            .ok_or(anyhow!(
+                "Transient resource {} does not exist",
+                std::any::type_name::<T>()
+            ))?;
+
+        self.transient.push(SnapshotResource {
+            type_name: std::any::type_name::<T>().to_string(),
+            key: key.data,
+            data: bincode::serialize(&resource)?,
+        });
+        Ok(self)
+    }
+
+    /// Captures resource T stored in the context's cache,
+    ///
+    pub fn capture_cached<T>(&mut self, tc: &ThunkContext) -> anyhow::Result<&mut Self>
+    where
+        T: Serialize + ToOwned<Owned = T> + Send + Sync + 'static,
+    {
+        let resource = tc.cached::<T>().ok_or(anyhow!(
+            "Cached resource {} does not exist",
+            std::any::type_name::<T>()
+        ))?;
+
+        self.cached.push(SnapshotResource {
+            type_name: std::any::type_name::<T>().to_string(),
+            key: tc.attribute.data,
+            data: bincode::serialize(&resource)?,
+        });
+        Ok(self)
+    }
+
+    /// Returns the plugin state captured by this snapshot,
+    ///
+    pub fn plugin<P>(&self) -> anyhow::Result<P>
+    where
+        P: Plugin + DeserializeOwned,
+    {
+        let wire = self
+            .frame
+            .recv
+            .wire_data
+            .as_ref()
+            .ok_or(anyhow!("Snapshot frame does not have wire data"))?;
+
+        Ok(bincode::deserialize(wire)?)
+    }
+
+    /// Restores the captured state of plugin P onto a thunk context,
+    ///
+    /// **Note** The plugin is restored to node storage at the context's current attribute, this means
+    /// the initialized state of the plugin will be replaced for any context sharing the same node storage.
+    ///
+    pub async fn restore<P>(&self, tc: &mut ThunkContext) -> anyhow::Result<()>
+    where
+        P: Plugin + DeserializeOwned + Sync + Send + 'static,
+    {
+        let plugin = self.plugin::<P>()?;
+
+        tc.node
+            .storage
+            .write()
+            .await
+            .put_resource(plugin, tc.attribute.transmute());
+
+        if tc.variant_id.is_none() {
+            tc.variant_id = self.variant_id.map(Uuid::from_u128);
+        }
+
+        for (name, value) in self.properties.iter() {
+            tc.set_property(name, value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Restores captured transient resources of type T,
+    ///
+    pub async fn restore_transient<T>(&self, tc: &ThunkContext) -> anyhow::Result<()>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let mut transient = tc.transient_mut().await;
+        for resource in self.transient.iter().filter(|r| r.is::<T>()) {
+            let (key, value) = resource.decode::<T>()?;
+            transient.put_resource(value, key);
+        }
+        Ok(())
+    }
+
+    /// Restores captured cached resources of type T,
+    ///
+    pub fn restore_cached<T>(&self, tc: &mut ThunkContext) -> anyhow::Result<()>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        for resource in self.cached.iter().filter(|r| r.is::<T>()) {
+            let (_, value) = resource.decode::<T>()?;
+            tc.write_cache(value);
+        }
+        Ok(())
+    }
+
+    /// Encodes the snapshot w/ bincode,
+    ///
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Decodes a snapshot from bincode,
+    ///
+    pub fn from_bytes(bytes: impl AsRef<[u8]>) -> anyhow::Result<Self> {
+        Ok(bincode::deserialize(bytes.as_ref())?)
+    }
+}
+
+impl ThunkContext {
+    /// Captures a snapshot of the current state of plugin P,
+    ///
+    #[inline]
+    pub async fn snapshot<P>(&self) -> Snapshot
+    where
+        P: Plugin + Serialize + Sync + Send + 'static,
+    {
+        Snapshot::capture::<P>(self).await
+    }
+}
+
+#[allow(unused)]
+mod test {
+    use crate::prelude::*;
+    use serde::Deserialize;
+    use serde::Serialize;
+
+    #[derive(Reality, Clone, Serialize, Deserialize, Default, Debug, PartialEq)]
+    #[reality(call=test_noop, plugin)]
+    struct Test {
+        #[reality(derive_fromstr)]
+        name: String,
+        other: String,
+    }
+
+    async fn test_noop(_tc: &mut ThunkContext) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_snapshot_restore() {
+        let node = Shared::default().into_thread_safe_with(tokio::runtime::Handle::current());
+        let mut tc: ThunkContext = node.into();
+        tc.attribute = ResourceKey::with_hash("test_snapshot_restore");
+
+        let test = Test {
+            name: String::from("hello world"),
+            other: String::from("hello other world"),
+        };
+        tc.node
+            .storage
+            .write()
+            .await
+            .put_resource(test.clone(), tc.attribute.transmute());
+
+        let transient_key = ResourceKey::<String>::with_hash("transient");
+        tc.transient_mut()
+            .await
+            .put_resource(String::from("transient value"), transient_key);
+
+        let mut snapshot = tc.snapshot::<Test>().await;
+        snapshot
+            .capture_transient::<String>(&tc, transient_key)
+            .await
+            .unwrap();
+
+        let bytes = snapshot.to_bytes().unwrap();
+        let snapshot = Snapshot::from_bytes(bytes).unwrap();
+        assert_eq!(test, snapshot.plugin::<Test>().unwrap());
+
+        // Restore into a context w/ fresh storage
+        let node = Shared::default().into_thread_safe_with(tokio::runtime::Handle::current());
+        let mut restored: ThunkContext = node.into();
+        restored.attribute = ResourceKey::from(uuid::Uuid::from_u128(snapshot.attribute));
+
+        snapshot.restore::<Test>(&mut restored).await.unwrap();
+        snapshot
+            .restore_transient::<String>(&restored)
+            .await
+            .unwrap();
+
+        assert_eq!(test, restored.initialized::<Test>().await);
+        assert_eq!(
+            Some(String::from("transient value")),
+            restored
+                .transient()
+                .await
+                .current_resource::<String>(transient_key)
+        );
+    }
+}
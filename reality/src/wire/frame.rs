@@ -1,4 +1,5 @@
 use anyhow::anyhow;
+use serde::Deserialize;
 use serde::Serialize;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -16,7 +17,7 @@ use crate::Shared;
 
 /// Type-alias for a the frame version of an attribute type,
 ///
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct Frame {
     /// TODO: If set, acts as the receiver for any field packets,
     ///
@@ -236,6 +237,7 @@ pub trait ToFrame: AttributeType<Shared> {
             field_offset: usize::MAX,
             field_name: Self::symbol().to_string(),
             owner_name: "self".to_string(),
+            field_path: None,
             attribute_hash: Some(key.data),
             op: 0,
         }
@@ -0,0 +1,495 @@
+use std::collections::BTreeSet;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+
+use super::server::FieldRefController;
+use super::server::WireClient;
+use super::server::WireServer;
+use crate::prelude::*;
+
+/// Default number of batches kept in the history of a wire server,
+///
+pub const DEFAULT_HISTORY_LEN: usize = 64;
+
+/// Change applied to a field by a field packet,
+///
+#[derive(Clone, Debug)]
+pub struct FieldChange {
+    /// Packet w/ the value of the field before the change was applied,
+    ///
+    pub previous: FieldPacket,
+    /// Packet that was applied,
+    ///
+    pub next: FieldPacket,
+}
+
+/// Bounded history of changes applied to the fields of a plugin attribute,
+///
+/// Each entry in the history contains the changes of a single committed batch. When the history is full, the oldest
+/// entry is dropped.
+///
+#[derive(Clone, Debug)]
+pub struct FieldHistory {
+    /// Maximum number of entries,
+    ///
+    capacity: usize,
+    /// Frame of the plugin as it was parsed from runmd,
+    ///
+    initial: Frame,
+    /// Offsets of fields defined by runmd properties,
+    ///
+    parsed: BTreeSet<usize>,
+    /// Frame of the plugin as of the last applied change,
+    ///
+    current: Frame,
+    /// Entries that can be undone, oldest first,
+    ///
+    undo: VecDeque<Vec<FieldChange>>,
+    /// Entries that can be redone, most recently undone last,
+    ///
+    redo: Vec<Vec<FieldChange>>,
+}
+
+impl FieldHistory {
+    /// Returns a new empty history starting from an initial frame,
+    ///
+    pub fn new(capacity: usize, initial: Frame) -> Self {
+        Self {
+            capacity: usize::max(capacity, 1),
+            current: initial.clone(),
+            initial,
+            parsed: BTreeSet::new(),
+            undo: VecDeque::new(),
+            redo: vec![],
+        }
+    }
+
+    /// Returns the history w/ the offsets of fields that were defined by runmd properties,
+    ///
+    pub fn with_parsed(mut self, parsed: impl IntoIterator<Item = usize>) -> Self {
+        self.parsed.extend(parsed);
+        self
+    }
+
+    /// Sets the maximum number of entries, dropping the oldest entries that no longer fit,
+    ///
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = usize::max(capacity, 1);
+        while self.undo.len() > self.capacity {
+            self.undo.pop_front();
+        }
+    }
+
+    /// Returns the frame of the plugin as it was parsed from runmd,
+    ///
+    pub fn initial(&self) -> &Frame {
+        &self.initial
+    }
+
+    /// Returns an iterator over each change that can be undone, oldest first,
+    ///
+    pub fn changes(&self) -> impl Iterator<Item = &FieldChange> {
+        self.undo.iter().flatten()
+    }
+
+    /// Returns the number of entries that can be undone,
+    ///
+    pub fn len(&self) -> usize {
+        self.undo.len()
+    }
+
+    /// Returns true if there are no entries that can be undone,
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.undo.is_empty()
+    }
+
+    /// Returns true if there is an entry that can be redone,
+    ///
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Returns the condition of the field at offset,
+    ///
+    /// If the field has the same value it was parsed with, the condition is `Initial` if the field was defined by
+    /// runmd and otherwise `Default`. In all other cases the field is `Pending`.
+    ///
+    pub fn condition(&self, offset: usize) -> FieldCondition {
+        let unchanged = find(&self.current, offset).map(|p| &p.wire_data)
+            == find(&self.initial, offset).map(|p| &p.wire_data);
+
+        match (unchanged, self.parsed.contains(&offset)) {
+            (true, true) => FieldCondition::Initial,
+            (true, false) => FieldCondition::Default,
+            _ => FieldCondition::Pending,
+        }
+    }
+
+    /// Records a batch that was applied, producing the next frame,
+    ///
+    /// **Note** Clears any entries that could be redone.
+    ///
+    pub(super) fn record(&mut self, batch: &[FieldPacket], next: Frame) {
+        let changes = batch
+            .iter()
+            .filter_map(|packet| {
+                // Fields that are not framed cannot be restored
+                find(&self.current, packet.field_offset).map(|previous| FieldChange {
+                    previous: previous.clone(),
+                    next: packet.clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        self.current = next;
+        self.redo.clear();
+
+        if !changes.is_empty() {
+            self.undo.push_back(changes);
+            while self.undo.len() > self.capacity {
+                self.undo.pop_front();
+            }
+        }
+    }
+
+    /// Returns the batch that would undo the most recent entry,
+    ///
+    pub(super) fn undo_batch(&self) -> Option<Vec<FieldPacket>> {
+        self.undo
+            .back()
+            .map(|changes| changes.iter().rev().map(|c| c.previous.clone()).collect())
+    }
+
+    /// Returns the batch that would redo the most recently undone entry,
+    ///
+    pub(super) fn redo_batch(&self) -> Option<Vec<FieldPacket>> {
+        self.redo
+            .last()
+            .map(|changes| changes.iter().map(|c| c.next.clone()).collect())
+    }
+
+    /// Returns the batch that would revert each field to the value it was parsed with,
+    ///
+    pub(super) fn revert_batch(&self) -> Vec<FieldPacket> {
+        self.initial
+            .fields
+            .iter()
+            .filter(|p| {
+                find(&self.current, p.field_offset).map(|c| &c.wire_data) != Some(&p.wire_data)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Moves the most recent entry to the redo stack after it has been undone,
+    ///
+    pub(super) fn undone(&mut self, next: Frame) {
+        if let Some(changes) = self.undo.pop_back() {
+            self.redo.push(changes);
+        }
+        self.current = next;
+    }
+
+    /// Moves the most recently undone entry back to the history after it has been redone,
+    ///
+    pub(super) fn redone(&mut self, next: Frame) {
+        if let Some(changes) = self.redo.pop() {
+            self.undo.push_back(changes);
+        }
+        self.current = next;
+    }
+}
+
+/// Finds the packet for the field at offset in a frame,
+///
+fn find(frame: &Frame, offset: usize) -> Option<&FieldPacket> {
+    frame.fields.iter().find(|p| p.field_offset == offset)
+}
+
+impl<P, const BUFFER_LEN: usize> WireServer<P, BUFFER_LEN>
+where
+    P: Plugin,
+    P::Virtual: NewFn<Inner = P> + FieldRefController<Owner = P>,
+{
+    /// Returns a copy of the history of changes committed by the server,
+    ///
+    pub fn history(&self) -> FieldHistory {
+        self.history
+            .lock()
+            .map(|h| h.clone())
+            .unwrap_or_else(|p| p.into_inner().clone())
+    }
+
+    /// Sets the maximum number of batches kept in the history,
+    ///
+    pub fn set_history_len(&self, len: usize) {
+        if let Ok(mut history) = self.history.lock() {
+            history.set_capacity(len);
+        }
+    }
+
+    /// Undoes the most recently committed batch,
+    ///
+    /// Returns true if a batch was undone.
+    ///
+    pub async fn undo(&self) -> anyhow::Result<bool> {
+        let batch = {
+            let mut history = self
+                .history
+                .lock()
+                .map_err(|_| anyhow!("History is poisoned"))?;
+
+            let Some(batch) = history.undo_batch() else {
+                return Ok(false);
+            };
+
+            self.apply(&mut history, &batch, |history, _, next| history.undone(next))?;
+            batch
+        };
+
+        self.deliver(Arc::new(batch)).await;
+        Ok(true)
+    }
+
+    /// Redoes the most recently undone batch,
+    ///
+    /// Returns true if a batch was redone.
+    ///
+    pub async fn redo(&self) -> anyhow::Result<bool> {
+        let batch = {
+            let mut history = self
+                .history
+                .lock()
+                .map_err(|_| anyhow!("History is poisoned"))?;
+
+            let Some(batch) = history.redo_batch() else {
+                return Ok(false);
+            };
+
+            self.apply(&mut history, &batch, |history, _, next| history.redone(next))?;
+            batch
+        };
+
+        self.deliver(Arc::new(batch)).await;
+        Ok(true)
+    }
+
+    /// Reverts each field to the value it was parsed with from runmd,
+    ///
+    /// The revert is recorded as a batch in the history, so it can be undone.
+    ///
+    /// Returns true if any field was changed.
+    ///
+    pub async fn revert(&self) -> anyhow::Result<bool> {
+        let batch = {
+            let mut history = self
+                .history
+                .lock()
+                .map_err(|_| anyhow!("History is poisoned"))?;
+
+            let batch = history.revert_batch();
+            if batch.is_empty() {
+                return Ok(false);
+            }
+
+            self.apply(&mut history, &batch, |history, applied, next| {
+                history.record(applied, next)
+            })?;
+            batch
+        };
+
+        self.deliver(Arc::new(batch)).await;
+        Ok(true)
+    }
+}
+
+impl<P, const BUFFER_LEN: usize> WireClient<P, BUFFER_LEN>
+where
+    P: Plugin,
+    P::Virtual: NewFn<Inner = P> + FieldRefController<Owner = P>,
+{
+    /// Undoes the most recently committed batch of the wire server,
+    ///
+    pub async fn undo(&self) -> anyhow::Result<bool> {
+        self.0.undo().await
+    }
+
+    /// Redoes the most recently undone batch of the wire server,
+    ///
+    pub async fn redo(&self) -> anyhow::Result<bool> {
+        self.0.redo().await
+    }
+
+    /// Reverts the plugin of the wire server to the values it was parsed with,
+    ///
+    pub async fn revert(&self) -> anyhow::Result<bool> {
+        self.0.revert().await
+    }
+}
+
+#[cfg(test)]
+#[allow(unused)]
+mod tests {
+    use super::*;
+    use crate::project::fixture::test_project;
+    use tokio::runtime::Handle;
+
+    #[derive(Reality, Debug, Default, Clone)]
+    #[reality(call = test, plugin)]
+    pub struct TestHistory {
+        #[reality(derive_fromstr)]
+        name: String,
+        uri: String,
+        method: String,
+    }
+
+    async fn test(_: &mut ThunkContext) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Returns the packet for the field at offset of a plugin,
+    ///
+    fn packet(plugin: TestHistory, offset: usize) -> FieldPacket {
+        plugin
+            .to_frame(ResourceKey::new())
+            .fields
+            .into_iter()
+            .find(|p| p.field_offset == offset)
+            .unwrap()
+    }
+
+    /// Returns the current state and condition of each field,
+    ///
+    fn current(server: &WireServer<TestHistory>) -> (TestHistory, Vec<FieldCondition>) {
+        let routes = server.listener.routes();
+        let routes = routes.borrow();
+        let virt = routes.virtual_ref();
+
+        (
+            virt.current(),
+            (0..3).map(|o| virt.condition(o).unwrap()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_field_history_capacity() {
+        let mut history = FieldHistory::new(2, TestHistory::default().to_frame(ResourceKey::new()));
+
+        for method in ["GET", "PUT", "POST"] {
+            let next = TestHistory {
+                method: method.to_string(),
+                ..Default::default()
+            };
+            history.record(
+                &[packet(next.clone(), 2)],
+                next.to_frame(ResourceKey::new()),
+            );
+        }
+
+        assert_eq!(2, history.len());
+        assert_eq!(2, history.changes().count());
+        assert!(!history.can_redo());
+        assert_eq!(FieldCondition::Pending, history.condition(2));
+        assert_eq!(FieldCondition::Default, history.condition(0));
+        assert_eq!(1, history.revert_batch().len());
+    }
+
+    #[tokio::test]
+    async fn test_wire_server_history() {
+        let project = test_project(|parser| {
+            parser.with_object_type::<Thunk<TestHistory>>();
+        });
+
+        let mut workspace = EmptyWorkspace.workspace();
+        workspace.set_name("test");
+        workspace.add_buffer(
+            "history.md",
+            r#"```runmd
++ .test history
+<a/reality.testhistory>
+: .name a
+: .uri http://localhost:8080
+```"#,
+        );
+
+        let workspace = workspace.compile(project).await.unwrap();
+        let project = workspace.project.unwrap();
+        let nodes = project.nodes.read().await;
+        let (_, store) = nodes.iter().next().unwrap();
+        let node = store
+            .read()
+            .await
+            .root_ref()
+            .current::<ParsedNode>()
+            .unwrap();
+        let target = AsyncStorageTarget::from_parts(store.clone(), Handle::current());
+        let mut tc: ThunkContext = target.into();
+        tc.set_attribute(*node.attributes.first().unwrap());
+
+        let server = WireServer::<TestHistory>::new(&mut tc).await.unwrap();
+        let mut port = server.open_port();
+
+        // Fields defined in runmd start in the initial condition
+        let (test, conditions) = current(&server);
+        assert_eq!("a", test.name);
+        assert_eq!("http://localhost:8080", test.uri);
+        use FieldCondition::*;
+        assert_eq!(vec![Initial, Initial, Default], conditions);
+
+        let edit = TestHistory {
+            uri: String::from("https://staging"),
+            method: String::from("POST"),
+            ..test.clone()
+        };
+        server
+            .commit(&[packet(edit.clone(), 1), packet(edit.clone(), 2)])
+            .unwrap();
+
+        let (test, conditions) = current(&server);
+        assert_eq!("https://staging", test.uri);
+        assert_eq!("POST", test.method);
+        assert_eq!(vec![Initial, Pending, Pending], conditions);
+
+        let history = server.history();
+        let changes = history.changes().collect::<Vec<_>>();
+        assert_eq!(2, changes.len());
+        let mut previous = TestHistory::default();
+        assert!(previous.set_field(changes[0].previous.clone().into_field_owned()));
+        assert_eq!("http://localhost:8080", previous.uri);
+
+        // Undo restores the previous values of the batch
+        assert!(server.undo().await.unwrap());
+        let (test, conditions) = current(&server);
+        assert_eq!("http://localhost:8080", test.uri);
+        assert_eq!("", test.method);
+        assert_eq!(vec![Initial, Initial, Default], conditions);
+        assert_eq!(2, port.try_next().unwrap().len());
+        assert!(!server.undo().await.unwrap());
+
+        // Redo re-applies the batch
+        assert!(server.redo().await.unwrap());
+        let (test, _) = current(&server);
+        assert_eq!("https://staging", test.uri);
+        assert_eq!("POST", test.method);
+        assert!(!server.redo().await.unwrap());
+
+        // Revert restores the parsed values and can be undone
+        assert!(server.revert().await.unwrap());
+        let (test, conditions) = current(&server);
+        assert_eq!("http://localhost:8080", test.uri);
+        assert_eq!(vec![Initial, Initial, Default], conditions);
+        assert!(!server.revert().await.unwrap());
+
+        assert!(server.undo().await.unwrap());
+        let (test, _) = current(&server);
+        assert_eq!("https://staging", test.uri);
+
+        // A new commit clears the redo stack
+        assert!(server.undo().await.unwrap());
+        server.commit(&[packet(edit, 2)]).unwrap();
+        assert!(!server.history().can_redo());
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::Plugin;
+
+/// Counters for field packets handled by wire servers of a plugin,
+///
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WireCounters {
+    /// Number of packets received by the wire server listener,
+    ///
+    pub received: u64,
+    /// Number of packets committed by the wire server,
+    ///
+    pub forwarded: u64,
+    /// Number of committed batches that had to wait for capacity on a port,
+    ///
+    pub retried: u64,
+    /// Number of committed packets queued to frame updates,
+    ///
+    pub routed: u64,
+    /// Number of packets in batches rejected by the wire server,
+    ///
+    pub rejected: u64,
+}
+
+/// Point-in-time copy of wire counters keyed by plugin symbol,
+///
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct WireMetrics {
+    /// Counters for each plugin,
+    ///
+    pub plugins: BTreeMap<String, WireCounters>,
+}
+
+impl WireMetrics {
+    /// Returns the counters for plugin P,
+    ///
+    pub fn plugin<P: Plugin>(&self) -> WireCounters {
+        self.plugins.get(P::symbol()).copied().unwrap_or_default()
+    }
+
+    /// Returns the sum of counters across all plugins,
+    ///
+    pub fn total(&self) -> WireCounters {
+        self.plugins
+            .values()
+            .fold(WireCounters::default(), |mut total, c| {
+                total.received += c.received;
+                total.forwarded += c.forwarded;
+                total.retried += c.retried;
+                total.routed += c.routed;
+                total.rejected += c.rejected;
+                total
+            })
+    }
+}
+
+/// Registry of wire counters shared by the wire servers created on the same node storage,
+///
+/// When a wire server is created it records to the registry found in the root of the node's storage. If the node
+/// does not have a registry, the wire server records to a registry of its own.
+///
+#[derive(Default, Clone, Debug)]
+pub struct WireMetricsRegistry {
+    /// Counters keyed by plugin symbol,
+    ///
+    counters: Arc<Mutex<BTreeMap<&'static str, WireCounters>>>,
+}
+
+impl WireMetricsRegistry {
+    /// Returns a snapshot of the current wire counters,
+    ///
+    pub fn snapshot(&self) -> WireMetrics {
+        let plugins = self
+            .counters
+            .lock()
+            .map(|r| r.iter().map(|(k, v)| (k.to_string(), *v)).collect())
+            .unwrap_or_default();
+
+        WireMetrics { plugins }
+    }
+
+    /// Updates the wire counters for plugin P,
+    ///
+    pub(crate) fn record<P: Plugin>(&self, update: impl FnOnce(&mut WireCounters)) {
+        if let Ok(mut counters) = self.counters.lock() {
+            update(counters.entry(P::symbol()).or_default());
+        }
+    }
+}
@@ -1,16 +1,28 @@
 mod frame;
+mod history;
+mod metrics;
 mod op;
 mod packet;
 mod routes;
 mod server;
+mod transport;
 
 pub mod prelude {
     pub use super::frame::Frame;
     pub use super::frame::FrameListener;
     pub use super::frame::FrameUpdates;
     pub use super::frame::ToFrame;
+    pub use super::history::FieldChange;
+    pub use super::history::FieldHistory;
+    pub use super::history::DEFAULT_HISTORY_LEN;
+    pub use super::metrics::WireCounters;
+    pub use super::metrics::WireMetrics;
+    pub use super::metrics::WireMetricsRegistry;
+    pub use super::op::ApplyOp;
     pub use super::op::Code;
+    pub use super::op::LoadCollection;
     pub use super::op::Op;
+    pub use super::op::OpResult;
     pub use super::packet::FieldPacket;
     pub use super::packet::FieldPacketType;
     pub use super::routes::FieldIndex;
@@ -20,7 +32,15 @@ pub mod prelude {
     pub use super::server::enable_virtual_dependencies;
     pub use super::server::FieldRefController;
     pub use super::server::WireClient;
+    pub use super::server::WirePort;
     pub use super::server::WireServer;
+    pub use super::server::DEFAULT_PORT_CAPACITY;
+    pub use super::transport::read_message;
+    pub use super::transport::write_message;
+    pub use super::transport::FieldLayout;
+    pub use super::transport::Handshake;
+    pub use super::transport::WireMessage;
+    pub use super::transport::WIRE_PROTOCOL_VERSION;
 }
 
 #[allow(unused_imports)]
@@ -152,6 +172,10 @@ mod test {
             assert_eq!("hello world cool test 3", v);
         });
 
+        let metrics = server.counters();
+        assert_eq!(2, metrics.received);
+        assert_eq!(2, metrics.forwarded);
+        assert_eq!(2, metrics.routed);
         ()
     }
 }
@@ -57,6 +57,17 @@
 //! - [u8; 8] -- Data-bytes; 64 bits used as input for the operation. Usage determined by instruction handler.
 //!
 
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::hash::Hash;
+
+use anyhow::anyhow;
+
+use crate::prelude::*;
+
 /// Op data,
 ///
 #[derive(Copy, Hash, PartialEq, PartialOrd, Clone, Eq, Ord, Debug, Default)]
@@ -111,6 +122,18 @@ impl Op {
         Code::ReadDecoratedString.op()
     }
 
+    /// Creates a new util operation,
+    ///
+    pub const fn util() -> Self {
+        Code::Util.op()
+    }
+
+    /// Creates a new clear operation,
+    ///
+    pub const fn clear() -> Self {
+        Code::Clear.op()
+    }
+
     /// Sets the hash value setting,
     ///
     pub fn set_hash_value(&mut self, hash: u64) {
@@ -118,6 +141,24 @@ impl Op {
 
         self.0 = uuid::Uuid::from_fields(code, ra, rb, &hash.to_be_bytes())
     }
+
+    /// Returns the code of this operation,
+    ///
+    pub fn code(&self) -> Code {
+        Code::from_bits_retain(self.0.as_fields().0)
+    }
+
+    /// Returns the operation encoded as a u128,
+    ///
+    pub const fn as_u128(&self) -> u128 {
+        self.0.as_u128()
+    }
+
+    /// Returns the operation decoded from a u128,
+    ///
+    pub const fn from_u128(op: u128) -> Self {
+        Self(uuid::Uuid::from_u128(op))
+    }
 }
 
 impl From<Code> for Op {
@@ -227,6 +268,277 @@ impl Code {
     pub const fn op(self) -> Op {
         Op(uuid::Uuid::from_fields(self.bits(), 0, 0, &[0; 8]))
     }
+
+    /// Returns the operation of this code w/o the mode,
+    ///
+    pub const fn operation(self) -> Code {
+        Code::from_bits_retain(self.bits() & 0xFFFF)
+    }
+
+    /// Returns true if this code loads a value into a field,
+    ///
+    pub const fn is_load(self) -> bool {
+        self.operation().bits() == Code::Load.bits()
+    }
+
+    /// Returns true if this code reads the value of a field,
+    ///
+    pub const fn is_read(self) -> bool {
+        self.operation().bits() == Code::Read.bits()
+    }
+
+    /// Returns true if this code responds w/ a packet instead of changing the field,
+    ///
+    pub const fn is_response(self) -> bool {
+        self.is_read() || self.operation().bits() == Code::Util.bits()
+    }
+}
+
+/// Result of applying a field packet w/ an operation,
+///
+#[derive(Debug)]
+pub enum OpResult {
+    /// Packet that was applied to the field,
+    ///
+    /// **Note** A clear packet is applied as a load of the field's default value.
+    ///
+    Applied(FieldPacket),
+    /// Response to a read or util packet,
+    ///
+    Response(FieldPacket),
+}
+
+impl OpResult {
+    /// Returns the packet of the result,
+    ///
+    pub fn into_packet(self) -> FieldPacket {
+        match self {
+            OpResult::Applied(packet) | OpResult::Response(packet) => packet,
+        }
+    }
+}
+
+/// Extension trait for applying field packets to a plugin according to the code of each packet's operation,
+///
+/// | code                  | result                                                                    |
+/// | --------------------- | ------------------------------------------------------------------------- |
+/// | `Load`, `LoadReplace` | Replaces the field w/ the packet's value                                  |
+/// | `LoadParse`           | Parses the packet's input the same way as a property of the field in runmd |
+/// | `LoadMerge`           | Merges the packet's value into a `vec_of`, `map_of` or `set_of` field     |
+/// | `LoadAppend`          | Appends the packet's value to a `vec_of`, `map_of` or `set_of` field      |
+/// | `Clear`               | Resets the field to its default value                                     |
+/// | `Read`                | Responds w/ the current value of the field                                |
+/// | `ReadString`          | Responds w/ the current value of the field as a bincode String            |
+/// | `ReadDecoratedString` | Responds w/ the current value of the field as a bincode Decorated<String> |
+/// | `Util`                | Responds w/ the layout of the field w/o any data                          |
+///
+pub trait ApplyOp: Plugin {
+    /// Applies a field packet,
+    ///
+    /// Returns an error if the packet could not be applied, in which case the plugin is unchanged.
+    ///
+    /// If the packet has a field path, the field is resolved from the path instead of the offset. When the path
+    /// addresses a field of a nested field, the result is the packet of the nested field w/ its new value.
+    ///
+    fn apply_op(&mut self, packet: &FieldPacket) -> anyhow::Result<OpResult> {
+        let code = packet.code();
+        let packet = &resolve_path(self, packet)?;
+
+        let nested = packet.field_path.as_deref().filter(|p| p.contains('.'));
+        if let (Some(path), true) = (nested, code.is_response() || code == Code::Clear) {
+            return Err(anyhow!(
+                "{:?} is not supported for nested field `{path}`",
+                code
+            ));
+        }
+
+        if code.is_response() {
+            return self.respond(packet).map(OpResult::Response);
+        }
+
+        let packet = if code == Code::Clear {
+            field_packet(&Self::default(), packet)?.with_op(Op::load())
+        } else {
+            packet.clone()
+        };
+
+        if self.set_field(packet.clone().into_field_owned()) {
+            if nested.is_some() {
+                return Ok(OpResult::Applied(
+                    field_packet(self, &packet)?.with_op(Op::load()),
+                ));
+            }
+
+            Ok(OpResult::Applied(packet))
+        } else {
+            Err(anyhow!(
+                "Could not apply {:?} to field `{}`",
+                code,
+                packet.field_name
+            ))
+        }
+    }
+
+    /// Returns the response to a read or util packet,
+    ///
+    /// The response keeps the operation of the request so that receivers can tell it apart from a change.
+    ///
+    fn respond(&self, packet: &FieldPacket) -> anyhow::Result<FieldPacket> {
+        let code = packet.code();
+        let mut response = field_packet(self, packet)?;
+
+        if code == Code::ReadString {
+            let text = response.to_text()?;
+            response = FieldPacket::new::<String>();
+            response.wire_data = Some(bincode::serialize(&text)?);
+        } else if code == Code::ReadDecoratedString {
+            let text = response.to_decorated_text()?;
+            response = FieldPacket::new::<Decorated<String>>();
+            response.wire_data = Some(bincode::serialize(&text)?);
+        } else if !code.is_read() {
+            response.wire_data = None;
+        }
+
+        response.field_offset = packet.field_offset;
+        response.field_name = packet.field_name.to_string();
+        response.owner_name = packet.owner_name.to_string();
+        response.field_path = packet.field_path.clone();
+        response.attribute_hash = packet.attribute_hash;
+        Ok(response.with_op(packet.op()))
+    }
+}
+
+impl<P: Plugin> ApplyOp for P {}
+
+/// Returns the packet addressed by offset to the field of a plugin named by the first segment of the packet's field
+/// path,
+///
+/// Returns a clone of the packet if the packet does not have a field path.
+///
+fn resolve_path<P: Plugin>(plugin: &P, packet: &FieldPacket) -> anyhow::Result<FieldPacket> {
+    let Some(path) = packet.field_path.as_deref() else {
+        return Ok(packet.clone());
+    };
+
+    let head = path.split('.').next().unwrap_or(path);
+    let field = plugin
+        .to_frame(ResourceKey::new())
+        .fields
+        .into_iter()
+        .find(|f| f.field_name == head)
+        .ok_or(anyhow!("Field `{path}` does not exist"))?;
+
+    let mut packet = packet.clone();
+    packet.field_offset = field.field_offset;
+    packet.field_name = field.field_name;
+    Ok(packet)
+}
+
+/// Returns the packet for the field of a plugin a packet is addressed to,
+///
+fn field_packet<P: Plugin>(plugin: &P, packet: &FieldPacket) -> anyhow::Result<FieldPacket> {
+    plugin
+        .to_frame(ResourceKey::new())
+        .fields
+        .into_iter()
+        .find(|f| f.field_offset == packet.field_offset && f.field_name == packet.field_name)
+        .ok_or(anyhow!(
+            "Field `{}` at offset {} does not exist",
+            packet.field_name,
+            packet.field_offset
+        ))
+}
+
+/// Implemented by collection field types that can load a value by merging or appending,
+///
+pub trait LoadCollection {
+    /// Merges other into the collection,
+    ///
+    /// Lists replace elements at the same index, maps replace entries w/ the same key and sets take the union.
+    ///
+    fn load_merge(&mut self, other: Self);
+
+    /// Appends other to the collection,
+    ///
+    /// Lists push each element, maps only insert entries w/ new keys and sets take the union.
+    ///
+    fn load_append(&mut self, other: Self);
+}
+
+impl<T> LoadCollection for Vec<T> {
+    fn load_merge(&mut self, other: Self) {
+        for (idx, value) in other.into_iter().enumerate() {
+            if let Some(current) = self.get_mut(idx) {
+                *current = value;
+            } else {
+                self.push(value);
+            }
+        }
+    }
+
+    fn load_append(&mut self, other: Self) {
+        self.extend(other);
+    }
+}
+
+impl<T> LoadCollection for VecDeque<T> {
+    fn load_merge(&mut self, other: Self) {
+        for (idx, value) in other.into_iter().enumerate() {
+            if let Some(current) = self.get_mut(idx) {
+                *current = value;
+            } else {
+                self.push_back(value);
+            }
+        }
+    }
+
+    fn load_append(&mut self, other: Self) {
+        self.extend(other);
+    }
+}
+
+impl<K: Ord, V> LoadCollection for BTreeMap<K, V> {
+    fn load_merge(&mut self, other: Self) {
+        self.extend(other);
+    }
+
+    fn load_append(&mut self, other: Self) {
+        for (key, value) in other {
+            self.entry(key).or_insert(value);
+        }
+    }
+}
+
+impl<K: Eq + Hash, V> LoadCollection for HashMap<K, V> {
+    fn load_merge(&mut self, other: Self) {
+        self.extend(other);
+    }
+
+    fn load_append(&mut self, other: Self) {
+        for (key, value) in other {
+            self.entry(key).or_insert(value);
+        }
+    }
+}
+
+impl<T: Ord> LoadCollection for BTreeSet<T> {
+    fn load_merge(&mut self, other: Self) {
+        self.extend(other);
+    }
+
+    fn load_append(&mut self, other: Self) {
+        self.extend(other);
+    }
+}
+
+impl<T: Eq + Hash> LoadCollection for HashSet<T> {
+    fn load_merge(&mut self, other: Self) {
+        self.extend(other);
+    }
+
+    fn load_append(&mut self, other: Self) {
+        self.extend(other);
+    }
 }
 
 #[test]
@@ -234,4 +546,124 @@ fn test_op() {
     for c in (Code::ReadString).iter_names() {
         eprintln!("{:?}", c);
     }
+
+    assert_eq!(Code::Load, Op::load().code());
+    assert_eq!(Code::LoadMerge, Op::load_merge().code());
+    assert_eq!(Code::Clear, Op::from_u128(Op::clear().as_u128()).code());
+    assert!(Code::LoadAppend.is_load());
+    assert!(Code::ReadDecoratedString.is_read());
+    assert!(Code::Util.is_response());
+    assert!(!Code::Clear.is_load() && !Code::Clear.is_response());
+}
+
+#[test]
+fn test_load_collection() {
+    let mut list = vec![1, 2, 3];
+    list.load_merge(vec![4, 5]);
+    assert_eq!(vec![4, 5, 3], list);
+    list.load_append(vec![6]);
+    assert_eq!(vec![4, 5, 3, 6], list);
+
+    let mut map = BTreeMap::from([("a", 1), ("b", 2)]);
+    map.load_append(BTreeMap::from([("a", 3), ("c", 4)]));
+    assert_eq!(BTreeMap::from([("a", 1), ("b", 2), ("c", 4)]), map);
+    map.load_merge(BTreeMap::from([("a", 3)]));
+    assert_eq!(Some(&3), map.get("a"));
+}
+
+// The derived plugin clones each field, including `port`
+#[allow(unused, clippy::clone_on_copy)]
+mod tests {
+    use super::*;
+
+    #[derive(Reality, Debug, Default, Clone)]
+    #[reality(call = test_noop, plugin)]
+    struct TestOps {
+        #[reality(derive_fromstr)]
+        name: String,
+        port: u16,
+        #[reality(vec_of=String)]
+        tag: Vec<String>,
+        #[reality(map_of=String)]
+        header: BTreeMap<String, String>,
+    }
+
+    async fn test_noop(_: &mut ThunkContext) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_op() {
+        let mut test = TestOps {
+            name: String::from("a"),
+            tag: vec![String::from("a")],
+            header: BTreeMap::from([(String::from("accept"), String::from("*/*"))]),
+            ..Default::default()
+        };
+        let edit = PacketRoutes::<TestOps>::new(TestOps {
+            tag: vec![String::from("b")],
+            header: BTreeMap::from([
+                (String::from("accept"), String::from("text/plain")),
+                (String::from("host"), String::from("localhost")),
+            ]),
+            ..Default::default()
+        });
+
+        // Parse packets are parsed the same way as a property in runmd
+        let parse = edit.route::<1>().encode().parse(String::from("8080"));
+        assert!(test.apply_op(&parse).is_ok());
+        assert_eq!(8080, test.port);
+
+        // Collection fields can be appended to or merged
+        let tag = edit.route::<2>().encode();
+        test.apply_op(&tag.clone().with_op(Op::load_append()))
+            .unwrap();
+        assert_eq!(vec!["a", "b"], test.tag);
+        test.apply_op(&tag.clone().with_op(Op::load_merge()))
+            .unwrap();
+        assert_eq!(vec!["b", "b"], test.tag);
+
+        let header = edit.route::<3>().encode();
+        test.apply_op(&header.clone().with_op(Op::load_append()))
+            .unwrap();
+        assert_eq!("*/*", test.header["accept"]);
+        assert_eq!("localhost", test.header["host"]);
+        test.apply_op(&header.with_op(Op::load_merge())).unwrap();
+        assert_eq!("text/plain", test.header["accept"]);
+
+        // Merging into a field that is not a collection is an error
+        let name = edit.route::<0>().encode();
+        assert!(test
+            .apply_op(&name.clone().with_op(Op::load_merge()))
+            .is_err());
+        assert_eq!("a", test.name);
+
+        // Clear is applied as a load of the default value
+        let OpResult::Applied(cleared) = test.apply_op(&tag.with_op(Op::clear())).unwrap() else {
+            panic!("should be applied");
+        };
+        assert_eq!(Code::Load, cleared.code());
+        assert!(test.tag.is_empty());
+
+        // Read packets respond w/ the current value and leave the plugin unchanged
+        let OpResult::Response(response) = test
+            .apply_op(&name.clone().with_op(Op::read_string()))
+            .unwrap()
+        else {
+            panic!("should be a response");
+        };
+        assert_eq!(Code::ReadString, response.code());
+        assert_eq!("a", response.to_text().unwrap());
+        assert_eq!(
+            "8080",
+            test.respond(&edit.route::<1>().encode().with_op(Op::read_string()))
+                .unwrap()
+                .to_text()
+                .unwrap()
+        );
+
+        let util = test.respond(&name.with_op(Op::util())).unwrap();
+        assert!(util.wire_data.is_none());
+        assert_eq!("name", util.field_name);
+    }
 }
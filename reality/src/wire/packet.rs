@@ -1,8 +1,12 @@
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tracing::error;
 
+use super::op::Code;
+use super::op::Op;
+use crate::Decorated;
 use crate::FieldOwned;
 
 /// Implemented by a type that can be stored into a packet,
@@ -10,6 +14,8 @@ use crate::FieldOwned;
 pub trait FieldPacketType: Send + Sync + 'static {
     /// Type that can be serialized to/from a string,
     ///
+    /// Returns an error if the string could not be parsed, leaving dest unchanged.
+    ///
     fn from_str_to_dest(str: &str, dest: &mut Option<Self>) -> anyhow::Result<()>
     where
         Self: FromStr + Sized;
@@ -44,9 +50,10 @@ where
     where
         Self: FromStr + Sized,
     {
-        if let Ok(value) = <T as FromStr>::from_str(str) {
-            let _ = dest.insert(value);
-        }
+        let value = <T as FromStr>::from_str(str).map_err(|_| {
+            anyhow::anyhow!("Could not parse `{str}` as {}", std::any::type_name::<T>())
+        })?;
+        let _ = dest.insert(value);
         Ok(())
     }
 }
@@ -74,9 +81,12 @@ pub struct FieldPacket {
     /// Type name of the owner of this field,
     ///
     pub owner_name: String,
+    /// Path of the field from the owner of the node, set for fields of a nested field, i.e. `server.port`,
+    ///
+    pub field_path: Option<String>,
     /// Operation code,
     ///
-    #[serde(skip)]
+    #[serde(default)]
     pub(crate) op: u128,
     /// Attribute hash value,
     ///
@@ -96,6 +106,7 @@ impl Clone for FieldPacket {
             field_offset: self.field_offset,
             field_name: self.field_name.clone(),
             owner_name: self.owner_name.clone(),
+            field_path: self.field_path.clone(),
             attribute_hash: self.attribute_hash,
             op: self.op,
         }
@@ -111,6 +122,7 @@ impl std::fmt::Debug for FieldPacket {
             .field("field_offset", &self.field_offset)
             .field("field_name", &self.field_name)
             .field("owner_name", &self.owner_name)
+            .field("field_path", &self.field_path)
             .field("attribute_hash", &self.attribute_hash)
             .field("op", &self.op)
             .finish()
@@ -128,6 +140,7 @@ impl FieldPacket {
             data_type_size: std::mem::size_of::<T>(),
             field_name: String::new(),
             owner_name: String::new(),
+            field_path: None,
             field_offset: 0,
             attribute_hash: None,
             op: 0,
@@ -153,28 +166,127 @@ impl FieldPacket {
 
     /// Sets the packet into parse mode,
     ///
-    /// TODO: parse_insert/parse_push/parse
-    ///
     pub fn parse(mut self, input: String) -> Self {
         self.wire_data = Some(input.as_bytes().to_vec());
-        self.op = 1;
+        self.with_op(Op::load_parse())
+    }
+
+    /// Returns the packet w/ an operation set,
+    ///
+    pub fn with_op(mut self, op: impl Into<Op>) -> Self {
+        self.set_op(op);
         self
     }
 
+    /// Sets the operation of the packet,
+    ///
+    pub fn set_op(&mut self, op: impl Into<Op>) {
+        self.op = op.into().as_u128();
+    }
+
+    /// Returns the operation of the packet,
+    ///
+    pub fn op(&self) -> Op {
+        Op::from_u128(self.op)
+    }
+
+    /// Returns the code of the packet's operation,
+    ///
+    pub fn code(&self) -> Code {
+        self.op().code()
+    }
+
+    /// If packet is set in parse mode, returns the input to parse,
+    ///
+    pub fn parse_input(&self) -> Option<&str> {
+        self.wire_data
+            .as_ref()
+            .filter(|_| self.code() == Code::LoadParse)
+            .and_then(|d| std::str::from_utf8(d).ok())
+    }
+
     /// If packet is set in parse mode, converts packet into T from parsing wire as a str,
     ///
     pub fn into_box_from_wire<T>(self) -> Option<Box<T>>
     where
         T: FromStr + Send + Sync + 'static,
     {
-        self.wire_data
-            .as_ref()
-            .filter(|_| self.op == 1)
-            .and_then(|d| std::str::from_utf8(d).ok())
+        self.parse_input()
             .and_then(|s| T::from_str(s).ok())
             .map(|v| Box::new(v))
     }
 
+    /// Returns the value of the packet as text,
+    ///
+    /// Returns an error if the packet is a secret or the type of data cannot be read as text.
+    ///
+    pub fn to_text(&self) -> anyhow::Result<String> {
+        self.to_decorated_text()?.value.ok_or(anyhow::anyhow!(
+            "Field `{}` does not have a value",
+            self.field_name
+        ))
+    }
+
+    /// Returns the value of the packet as decorated text,
+    ///
+    /// If the data is decorated, the tag and property of the decoration are preserved.
+    ///
+    pub fn to_decorated_text(&self) -> anyhow::Result<Decorated<String>> {
+        if self.data_type_name.contains("::Secret<") {
+            return Err(anyhow::anyhow!(
+                "Secret field `{}` cannot be read as text",
+                self.field_name
+            ));
+        }
+
+        let data = self.wire_data.as_deref().ok_or(anyhow::anyhow!(
+            "Field `{}` does not have wire data",
+            self.field_name
+        ))?;
+
+        macro_rules! decode_text {
+            ($($ty:ty),*) => {
+                $(
+                    if self.data_type_name == std::any::type_name::<$ty>() {
+                        return Ok(Decorated {
+                            value: Some(bincode::deserialize::<$ty>(data)?.to_string()),
+                            tag: None,
+                            property: None,
+                        });
+                    }
+
+                    if self.data_type_name == std::any::type_name::<Decorated<$ty>>() {
+                        let decorated = bincode::deserialize::<Decorated<$ty>>(data)?;
+                        return Ok(Decorated {
+                            value: decorated.value.map(|v| v.to_string()),
+                            tag: decorated.tag,
+                            property: decorated.property,
+                        });
+                    }
+                )*
+            };
+        }
+
+        decode_text!(
+            String, bool, char, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize,
+            f32, f64
+        );
+
+        if self.data_type_name == std::any::type_name::<PathBuf>() {
+            return Ok(Decorated {
+                value: Some(bincode::deserialize::<PathBuf>(data)?.display().to_string()),
+                tag: None,
+                property: None,
+            });
+        }
+
+        Err(anyhow::anyhow!(
+            "Field `{}` of type `{}` cannot be read as text",
+            self.field_name,
+            self.data_type_name
+        ))
+    }
+
     /// Converts a field packet ptr into data,
     ///
     pub fn into_box<T>(self) -> Option<Box<T>>
@@ -235,6 +347,7 @@ impl FieldPacket {
             attribute_hash: self.attribute_hash,
             wire_data: None,
             owner_name: self.owner_name.to_string(),
+            field_path: self.field_path.clone(),
             op: 0,
         };
 
@@ -253,3 +366,14 @@ impl FieldPacket {
         }
     }
 }
+
+#[test]
+fn test_from_str_to_dest() {
+    let mut dest = None::<u64>;
+    u64::from_str_to_dest("10", &mut dest).unwrap();
+    assert_eq!(Some(10), dest);
+
+    let err = u64::from_str_to_dest("ten", &mut dest).unwrap_err();
+    assert!(err.to_string().contains("Could not parse `ten` as u64"));
+    assert_eq!(Some(10), dest);
+}
@@ -13,7 +13,9 @@ use crate::{
     Property, ResourceKey, Shared, StorageTarget,
 };
 
+use super::prelude::ApplyOp;
 use super::prelude::FieldPacket;
+use super::prelude::OpResult;
 
 /// Wrapper over the field offset and type so that the compiler can match by offset,
 ///
@@ -72,6 +74,7 @@ where
     pub dispatcher: OnceLock<Dispatcher<S, FrameUpdates>>,
     /// Broadcast channel for forwarding packets to routes,
     ///
+    #[deprecated(note = "Packets are committed in batches by `WireServer::start`")]
     pub tx: Arc<tokio::sync::broadcast::Sender<super::packet::FieldPacket>>,
 }
 
@@ -82,18 +85,32 @@ where
 {
     /// Creates a new packet router,
     ///
-    pub fn new(routes: Arc<tokio::sync::watch::Sender<PacketRoutes<P>>>) -> Self
-    where
-        P::Virtual: NewFn<Inner = P>,
-    {
+    #[allow(deprecated)]
+    pub fn new(routes: Arc<tokio::sync::watch::Sender<PacketRoutes<P>>>) -> Self {
         let len = P::default().to_frame(ResourceKey::new()).fields.len();
 
         let (tx, _rx) = tokio::sync::broadcast::channel(usize::max(len, 1));
 
         Self {
             routes,
-            tx: Arc::new(tx),
             dispatcher: OnceLock::new(),
+            tx: Arc::new(tx),
+        }
+    }
+
+    /// Queues a dispatch that pushes a batch of applied packets to frame updates,
+    ///
+    /// When the dispatch is drained, the next time the remote plugin is loaded the packets are applied to the
+    /// initialized state of the plugin. Returns false if the router is not bound to a dispatcher.
+    ///
+    pub(crate) fn dispatch_batch(&self, packets: Vec<FieldPacket>) -> bool {
+        if let Some(mut dispatcher) = self.dispatcher.get().cloned() {
+            dispatcher.queue_dispatch_mut(move |f| {
+                f.frame.fields.extend(packets);
+            });
+            true
+        } else {
+            false
         }
     }
 
@@ -102,6 +119,8 @@ where
     /// Returns Ok(()) if the packet was received and dispatched successfully, otherwise
     /// returns an error.
     ///
+    #[allow(deprecated)]
+    #[deprecated(note = "Packets are committed in batches by `WireServer::start`")]
     pub async fn route_one<const OFFSET: usize>(&self) -> anyhow::Result<()>
     where
         P: OnWriteField<OFFSET> + OnReadField<OFFSET> + OnParseField<OFFSET> + Plugin,
@@ -112,7 +131,7 @@ where
 
             let next = rx.recv().await?;
 
-            self.try_route(next).await?;
+            self.try_route::<OFFSET>(next).await?;
 
             Ok(())
         } else {
@@ -122,17 +141,21 @@ where
 
     /// Tries to route the packet to field at OFFSET,
     ///
-    /// If the packet can be decoded and applied, and a change was applied, a dispatch
-    /// is queued that pushes the update to frame updates.
+    /// If the packet applies to the field, it is applied to a copy of the current state of the owner the same way as
+    /// a packet of a committed batch, and the applied packet is dispatched to frame updates as a batch of one packet.
     ///
+    #[deprecated(note = "Packets are committed in batches by `WireServer::start`")]
     pub async fn try_route<const OFFSET: usize>(&self, packet: FieldPacket) -> anyhow::Result<()>
     where
         P: OnReadField<OFFSET> + OnWriteField<OFFSET> + OnParseField<OFFSET> + Plugin,
         P::Virtual: NewFn<Inner = P>,
     {
-        if let Some(mut dispatcher) = self.dispatcher.get().cloned() {
-            let routes = self.routes.borrow();
+        if self.dispatcher.get().is_none() {
+            return Err(anyhow!("Not bound to a dispatcher"));
+        }
 
+        let applied = {
+            let routes = self.routes.borrow();
             let field_ref = routes.route::<OFFSET>();
 
             // It's possible this packet comes from outside of the process. Log what we are checking against
@@ -143,22 +166,21 @@ where
                 field_name_remote = packet.field_name,
                 "Filtering packet",
             );
-            match field_ref.filter_packet(&packet) {
-                Ok(field) => {
-                    // When this is queued to the dispatcher, the next time the remote_plugin is loaded
-                    // the dispatcher will drain this queue and frame updates will be updated
-                    dispatcher.queue_dispatch_mut(move |f| {
-                        f.frame.fields.push(field.encode());
-                    });
-                    return Ok(());
-                }
-                Err(err) => {
-                    trace!("Skipping packet, {err}",);
-                }
+            field_ref
+                .filter_packet(&packet)
+                .and_then(|f| f.owner().apply_op(&packet))
+        };
+
+        match applied {
+            Ok(OpResult::Applied(applied)) => {
+                self.dispatch_batch(vec![applied]);
+                Ok(())
+            }
+            Ok(OpResult::Response(_)) => Err(anyhow!("Did not apply packet via this route")),
+            Err(err) => {
+                trace!("Skipping packet, {err}");
+                Err(anyhow!("Did not apply packet via this route"))
             }
-            Err(anyhow!("Did not apply packet via this route"))
-        } else {
-            Err(anyhow!("Not bound to a dispatcher"))
         }
     }
 }
@@ -1,9 +1,10 @@
+use std::collections::BTreeSet;
 use std::sync::Arc;
-use std::time::Duration;
+use std::sync::Mutex;
 
 use reality_derive::Reality;
 use tokio::select;
-use tokio::sync::broadcast::error::SendError;
+use tokio::sync::mpsc::error::TrySendError;
 use tokio::sync::watch::Ref;
 use tokio_util::sync::CancellationToken;
 use tracing::debug;
@@ -13,6 +14,9 @@ use anyhow::anyhow;
 
 use crate::prelude::*;
 
+use super::history::FieldHistory;
+use super::history::DEFAULT_HISTORY_LEN;
+
 /// Field ref controller implemented by the virtual plugin type,
 ///
 pub trait FieldRefController {
@@ -34,6 +38,22 @@ pub trait FieldRefController {
     /// Returns the current state of owner,
     ///
     fn current(&self) -> Self::Owner;
+
+    /// Sets the condition of the field ref at offset,
+    ///
+    /// Returns true if a field ref exists at offset.
+    ///
+    #[allow(unused_variables)]
+    fn set_condition(&mut self, offset: usize, condition: FieldCondition) -> bool {
+        false
+    }
+
+    /// Returns the condition of the field ref at offset,
+    ///
+    #[allow(unused_variables)]
+    fn condition(&self, offset: usize) -> Option<FieldCondition> {
+        None
+    }
 }
 
 /// Wire server can run in the background and manage sending/receiving frames for a plugn,
@@ -44,16 +64,32 @@ where
 {
     /// Provides a pipeline for sending and receiving field packets for plugin P,
     ///
-    listener: FrameListener<P, BUFFER_LEN>,
+    pub(super) listener: FrameListener<P, BUFFER_LEN>,
     /// Provides a packet router that can be used to handle frames accepted by
     /// the frame listener
     ///
-    router: Arc<PacketRouter<P>>,
+    pub(super) router: Arc<PacketRouter<P>>,
     /// Cancellation token,
     ///
-    cancel: CancellationToken,
+    pub(super) cancel: CancellationToken,
+    /// Senders for each open port,
+    ///
+    pub(super) ports: Mutex<Vec<tokio::sync::mpsc::Sender<Arc<Vec<FieldPacket>>>>>,
+    /// History of changes committed by the server,
+    ///
+    pub(super) history: Mutex<FieldHistory>,
+    /// Registry the server records its counters to,
+    ///
+    pub(super) metrics: WireMetricsRegistry,
+    /// Counters for packets handled by this server,
+    ///
+    pub(super) counters: tokio::sync::watch::Sender<WireCounters>,
 }
 
+/// Default number of batches a port can have pending before the server waits,
+///
+pub const DEFAULT_PORT_CAPACITY: usize = 16;
+
 impl<P, const BUFFER_LEN: usize> WireServer<P, BUFFER_LEN>
 where
     P: Plugin,
@@ -70,10 +106,40 @@ where
             if let (Some(router), Some(listener)) =
                 (init.router::<P>().await, init.listener::<P>().await)
             {
+                // Fields defined by properties of the parsed node start in the initial condition
+                let initial = init.initialized::<P>().await.to_frame(ResourceKey::new());
+                let parsed = init
+                    .node()
+                    .await
+                    .root_ref()
+                    .current::<ParsedNode>()
+                    .map(|n| parsed_fields::<P>(&n))
+                    .unwrap_or_default();
+
+                listener.routes().send_modify(|r| {
+                    for offset in parsed.iter() {
+                        r.virtual_mut()
+                            .set_condition(*offset, FieldCondition::Initial);
+                    }
+                });
+
+                let metrics = init
+                    .node()
+                    .await
+                    .root_ref()
+                    .current::<WireMetricsRegistry>()
+                    .unwrap_or_default();
+
                 let server = WireServer::<_, BUFFER_LEN> {
                     router,
                     listener: listener.with_buffer_size(),
                     cancel: tc.cancellation.child_token(),
+                    ports: Mutex::new(vec![]),
+                    history: Mutex::new(
+                        FieldHistory::new(DEFAULT_HISTORY_LEN, initial).with_parsed(parsed),
+                    ),
+                    metrics,
+                    counters: tokio::sync::watch::Sender::new(WireCounters::default()),
                 };
 
                 let server = Arc::new(server);
@@ -89,31 +155,101 @@ where
         Err(anyhow!("Could not create wire server"))
     }
 
-    /// Starts the wire server w/ one port,
+    /// Creates a new client,
     ///
-    pub async fn start(self: Arc<WireServer<P, BUFFER_LEN>>) -> anyhow::Result<()> {
-        let mut listener = self.listener.clone();
-        let router = self.router.clone();
-        let cancel = self.cancel.child_token();
+    pub fn new_client(self: Arc<WireServer<P, BUFFER_LEN>>) -> WireClient<P, BUFFER_LEN> {
+        WireClient(self.clone())
+    }
 
-        // TODO -- Currently only one port starts to route changes
-        let _port = tokio::spawn(self.start_port());
+    /// Subscribe to changes to packet routes,
+    ///
+    pub fn subscribe_packet_routes(
+        self: Arc<WireServer<P, BUFFER_LEN>>,
+    ) -> tokio::sync::watch::Receiver<PacketRoutes<P>> {
+        self.listener.subscribe_virtual()
+    }
+
+    /// Returns the counters for packets handled by this server,
+    ///
+    pub fn counters(&self) -> WireCounters {
+        *self.counters.borrow()
+    }
+
+    /// Subscribe to changes to the counters of this server,
+    ///
+    pub fn subscribe_counters(&self) -> tokio::sync::watch::Receiver<WireCounters> {
+        self.counters.subscribe()
+    }
+
+    /// Updates the counters of this server and of the registry it records to,
+    ///
+    fn record(&self, update: impl Fn(&mut WireCounters)) {
+        self.counters.send_modify(&update);
+        self.metrics.record::<P>(update);
+    }
 
-        // TODO -- if a client dispatcher is in-use this is required to handle new changes
-        // tokio::spawn(async move {
-        //     loop {
-        //         notify_packet_avail.notified().await;
+    /// Opens a port that receives each batch of packets committed by the server w/ the default capacity,
+    ///
+    pub fn open_port(&self) -> WirePort {
+        self.open_port_with_capacity(DEFAULT_PORT_CAPACITY)
+    }
+
+    /// Opens a port that receives each batch of packets committed by the server,
+    ///
+    /// Batches are delivered in the order they were committed. When a port has `capacity` batches pending, the server
+    /// waits for the port to receive the next batch before committing any further batches.
+    ///
+    pub fn open_port_with_capacity(&self, capacity: usize) -> WirePort {
+        let (tx, rx) = tokio::sync::mpsc::channel(usize::max(capacity, 1));
+
+        if let Ok(mut ports) = self.ports.lock() {
+            ports.push(tx);
+        }
+
+        WirePort { rx }
+    }
+
+    /// Notifies subscribers of the packet routes each time the server commits a batch,
+    ///
+    /// **Note** Committed batches are delivered to the ports opened w/ `open_port`, which should be used instead.
+    ///
+    #[deprecated(note = "Use `open_port` to receive each batch committed by the server")]
+    pub async fn start_port(self: Arc<WireServer<P, BUFFER_LEN>>) {
+        let mut port = self.open_port();
+        let cancel = self.cancel.child_token();
 
-        //         if let Some(disp) = disp.as_ref() {
-        //             let mut disp = disp.clone();
-        //             disp.dispatch_all().await;
-        //         }
+        loop {
+            select! {
+                next = port.next() => {
+                    if next.is_none() {
+                        return;
+                    }
+                    self.router.routes.send_if_modified(|_r| true);
+                }
+                _ = cancel.cancelled() => {
+                    debug!("wire server handler is exiting");
+                    return;
+                }
+            }
+        }
+    }
+}
 
-        //         if cancel.is_cancelled() {
-        //             return;
-        //         }
-        //     }
-        // });
+impl<P, const BUFFER_LEN: usize> WireServer<P, BUFFER_LEN>
+where
+    P: Plugin,
+    P::Virtual: NewFn<Inner = P> + FieldRefController<Owner = P>,
+{
+    /// Starts the wire server,
+    ///
+    /// Each batch of packets received by the listener is committed in the order it was received. A batch is only
+    /// committed if every packet in the batch can be applied, otherwise the entire batch is rejected.
+    ///
+    /// After a batch is committed the result of each packet is delivered to each open port.
+    ///
+    pub async fn start(self: Arc<WireServer<P, BUFFER_LEN>>) -> anyhow::Result<()> {
+        let mut listener = self.listener.clone();
+        let cancel = self.cancel.child_token();
 
         while let Ok(next) = select! {
             next = listener.listen() => next,
@@ -121,73 +257,195 @@ where
                 return Err(anyhow!("Process is shutting down down"))
             }
         } {
-            // TODO -- fix the ordering of this
-            for n in next {
-                debug!("Listener got field: {}", n.field_name);
-                if let Err(SendError(pending)) = router.tx.send(n) {
-                    debug!("Could not route next packet, no receivers are currently listening. Will retry.");
-
-                    tokio::time::sleep(Duration::from_millis(100)).await;
-                    // TODO -- This can be an issue since it would reorder the packets in an unexpected way if packets were sent in batch
-                    let resend = listener.new_tx().await?;
-                    resend.send(vec![pending]);
-                } else {
-                    debug!("Sent update to router");
+            let len = next.len() as u64;
+            debug!(len, "Listener got batch");
+            self.record(|c| c.received += len);
+
+            let results = match self.commit(&next) {
+                Ok(results) => results,
+                Err(err) => {
+                    error!("Rejected batch of {len} packets, {err}");
+                    self.record(|c| c.rejected += len);
+                    continue;
+                }
+            };
+
+            select! {
+                _ = self.deliver(Arc::new(results)) => {}
+                _ = cancel.cancelled() => {
+                    return Err(anyhow!("Process is shutting down down"))
                 }
             }
         }
 
         error!("server is exiting");
-        _port.abort();
 
         Ok(())
     }
 
-    /// Creates a new client,
+    /// Commits a batch of packets to the owner of the packet routes,
     ///
-    pub fn new_client(self: Arc<WireServer<P, BUFFER_LEN>>) -> WireClient<P, BUFFER_LEN> {
-        WireClient(self.clone())
+    /// Packets are applied in order to a copy of the current state of the owner w/ the operation of each packet, if any
+    /// packet cannot be applied the batch is rejected and the owner is left unchanged.
+    ///
+    /// If the batch is committed, the packets that changed the owner are recorded in the history of the server, a
+    /// dispatch is queued that pushes them to frame updates and subscribers of the packet routes are notified.
+    ///
+    /// Returns the result of each packet in order, i.e. the packet that was applied or the response to a read packet.
+    ///
+    pub fn commit(&self, batch: &[FieldPacket]) -> anyhow::Result<Vec<FieldPacket>> {
+        let mut history = self
+            .history
+            .lock()
+            .map_err(|_| anyhow!("History is poisoned"))?;
+
+        self.apply(&mut history, batch, |history, applied, next| {
+            history.record(applied, next)
+        })
     }
 
-    /// Subscribe to changes to packet routes,
+    /// Returns the response to a read or util packet w/ the current state of the owner,
     ///
-    pub fn subscribe_packet_routes(
-        self: Arc<WireServer<P, BUFFER_LEN>>,
-    ) -> tokio::sync::watch::Receiver<PacketRoutes<P>> {
-        self.listener.subscribe_virtual()
+    pub fn read(&self, packet: &FieldPacket) -> anyhow::Result<FieldPacket> {
+        self.listener
+            .routes()
+            .borrow()
+            .virtual_ref()
+            .current()
+            .respond(packet)
     }
 
-    /// Starts a port to listen for changes,
+    /// Applies a batch of packets to the owner of the packet routes,
     ///
-    /// **Note**: This is where packets sent from router.tx are handled.
+    /// Before the owner is updated, `update` is called w/ the packets that were applied and the frame of the staged
+    /// owner so that the history can be updated. Afterwards the condition of each field that was applied is updated
+    /// on the packet routes.
     ///
-    /// The packet is applied to all routes once and if successfully applied it is sent to the frame updates
-    /// dispatcher.
+    /// Returns the result of each packet in order.
     ///
-    pub async fn start_port(self: Arc<WireServer<P, BUFFER_LEN>>) {
-        let listening = self.router.clone();
-        let cancel = self.cancel.child_token();
-
-        loop {
-            select! {
-                _ = P::listen_one(listening.clone()) => {
-                    self.router.routes.send_if_modified(|_r| {
-                        true
-                    });
-                }
-                _ = cancel.cancelled() => {
-                    debug!("wire server handler is exiting");
-                    return;
+    pub(super) fn apply(
+        &self,
+        history: &mut FieldHistory,
+        batch: &[FieldPacket],
+        update: impl FnOnce(&mut FieldHistory, &[FieldPacket], Frame),
+    ) -> anyhow::Result<Vec<FieldPacket>> {
+        let routes = self.listener.routes();
+        let owner = routes.borrow().virtual_ref().send_raw();
+
+        // Staging, validation and the update of the owner happen while the owner is locked so that edits made
+        // concurrently w/ `FieldRef::edit_value` are not overwritten
+        let mut update = Some(update);
+        let mut results = vec![];
+        let mut applied = vec![];
+        let mut rejected = None;
+        owner.send_if_modified(|current| {
+            let mut staged = current.clone();
+            for (idx, packet) in batch.iter().enumerate() {
+                match staged.apply_op(packet) {
+                    Ok(OpResult::Applied(packet)) => {
+                        applied.push(packet.clone());
+                        results.push(packet);
+                    }
+                    Ok(OpResult::Response(response)) => {
+                        results.push(response);
+                    }
+                    Err(err) => {
+                        rejected = Some(anyhow!("Could not apply packet {idx}, {err}"));
+                        return false;
+                    }
                 }
             }
+
+            if applied.is_empty() {
+                return false;
+            }
+
+            if let Some(update) = update.take() {
+                update(history, &applied, staged.to_frame(ResourceKey::new()));
+            }
+            *current = staged;
+            true
+        });
+
+        if let Some(err) = rejected {
+            return Err(err);
+        }
+
+        if applied.is_empty() {
+            return Ok(results);
+        }
+
+        let len = applied.len() as u64;
+        self.record(|c| c.forwarded += len);
+
+        // When this is queued to the dispatcher, the next time the remote_plugin is loaded
+        // the dispatcher will drain this queue and frame updates will be updated
+        if self.router.dispatch_batch(applied.clone()) {
+            self.record(|c| c.routed += len);
+        }
+
+        routes.send_modify(|r| {
+            for packet in applied.iter() {
+                let offset = packet.field_offset;
+                r.virtual_mut()
+                    .set_condition(offset, history.condition(offset));
+            }
+        });
+
+        Ok(results)
+    }
+
+    /// Delivers a committed batch to each open port,
+    ///
+    /// **Note** Waits for capacity on each port before returning, ports that have been dropped are removed.
+    ///
+    pub(super) async fn deliver(&self, batch: Arc<Vec<FieldPacket>>) {
+        let ports = self.ports.lock().map(|p| p.clone()).unwrap_or_default();
+
+        for port in ports.iter() {
+            if let Err(TrySendError::Full(batch)) = port.try_send(batch.clone()) {
+                debug!("Port is at capacity, waiting to deliver batch");
+                self.record(|c| c.retried += 1);
+                port.send(batch).await.ok();
+            }
+        }
+
+        if ports.iter().any(|p| p.is_closed()) {
+            if let Ok(mut ports) = self.ports.lock() {
+                ports.retain(|p| !p.is_closed());
+            }
         }
     }
 }
 
+/// Port that receives each batch of packets committed by a wire server,
+///
+pub struct WirePort {
+    /// Receiver for committed batches,
+    ///
+    rx: tokio::sync::mpsc::Receiver<Arc<Vec<FieldPacket>>>,
+}
+
+impl WirePort {
+    /// Waits for the next committed batch,
+    ///
+    /// Returns None if the wire server has been dropped.
+    ///
+    pub async fn next(&mut self) -> Option<Arc<Vec<FieldPacket>>> {
+        self.rx.recv().await
+    }
+
+    /// Returns the next committed batch if one is pending,
+    ///
+    pub fn try_next(&mut self) -> Option<Arc<Vec<FieldPacket>>> {
+        self.rx.try_recv().ok()
+    }
+}
+
 /// Wraps a wire server and provides a client api,
 ///
 #[derive(Clone)]
-pub struct WireClient<P, const BUFFER_LEN: usize = 1>(Arc<WireServer<P, BUFFER_LEN>>)
+pub struct WireClient<P, const BUFFER_LEN: usize = 1>(pub(super) Arc<WireServer<P, BUFFER_LEN>>)
 where
     P: Plugin,
     P::Virtual: NewFn<Inner = P>;
@@ -195,20 +453,24 @@ where
 impl<P> WireClient<P>
 where
     P: Plugin,
-    P::Virtual: NewFn<Inner = P> + FieldRefController,
+    P::Virtual: NewFn<Inner = P> + FieldRefController<Owner = P>,
 {
     /// If modify returns a packet successfully then this fn will try to send that packet to
     /// the listener. If the packet was successfully sent then Ok(()) is returned.
     ///
     /// An error is returned in all other cases since the state could have changed when modify was called.
     ///
+    /// **Note** Modify is called w/ a staged copy of the packet routes, so edits made w/ `FieldRef::edit_value` only
+    /// change the local state once the server has committed the batch. If the server rejects the batch, the local state
+    /// is left unchanged.
+    ///
     pub fn try_borrow_modify(
         &self,
         modify: impl FnOnce(Ref<'_, PacketRoutes<P>>) -> anyhow::Result<FieldPacket>,
     ) -> anyhow::Result<()> {
-        let virt = self.0.listener.routes();
+        let staged = self.staged();
 
-        let packet = modify(virt.borrow())?;
+        let packet = modify(staged.borrow())?;
 
         self.try_send(vec![packet])?;
 
@@ -217,17 +479,29 @@ where
 
     /// Send a batch of field packets at once,
     ///
+    /// **Note** Like `try_borrow_modify`, modify is called w/ a staged copy of the packet routes.
+    ///
     pub fn try_borrow_modify_batch(
         &self,
         modify: impl FnOnce(Ref<'_, PacketRoutes<P>>) -> anyhow::Result<Vec<FieldPacket>>,
     ) -> anyhow::Result<()> {
-        let updates = modify(self.0.listener.routes().borrow())?;
+        let staged = self.staged();
+
+        let updates = modify(staged.borrow())?;
 
         self.try_send(updates)?;
 
         Ok(())
     }
 
+    /// Returns a staged copy of the current packet routes,
+    ///
+    fn staged(&self) -> tokio::sync::watch::Sender<PacketRoutes<P>> {
+        let current = self.0.listener.routes().borrow().virtual_ref().current();
+
+        tokio::sync::watch::Sender::new(PacketRoutes::new(current))
+    }
+
     /// Tries to send a batch of field packets to the frame listener,
     ///
     pub fn try_send(&self, packets: Vec<FieldPacket>) -> anyhow::Result<()> {
@@ -239,6 +513,31 @@ where
         Ok(())
     }
 
+    /// Sends a batch of field packets to the frame listener, waiting for capacity if the listener is full,
+    ///
+    pub async fn send(&self, packets: Vec<FieldPacket>) -> anyhow::Result<()> {
+        self.0
+            .listener
+            .frame_tx()
+            .send(packets)
+            .await
+            .map_err(|_| anyhow!("Wire server is not listening"))
+    }
+
+    /// Returns the response to a read or util packet w/ the current state of the plugin,
+    ///
+    /// **Note** A client connected to a remote wire server reads the state mirrored from the remote server.
+    ///
+    pub fn read(&self, packet: &FieldPacket) -> anyhow::Result<FieldPacket> {
+        self.0
+            .listener
+            .routes()
+            .borrow()
+            .virtual_ref()
+            .current()
+            .respond(packet)
+    }
+
     /// Subscribe to changes on the inner packet routes,
     ///
     pub fn subscribe(&self) -> tokio::sync::watch::Receiver<PacketRoutes<P>> {
@@ -276,6 +575,17 @@ where
     // }
 }
 
+/// Returns the offsets of fields of P defined by properties of a parsed node,
+///
+fn parsed_fields<P: Plugin>(node: &ParsedNode) -> BTreeSet<usize> {
+    node.properties
+        .iter()
+        .filter_map(|p| p.field())
+        .filter(|f| f.owner_name() == Some(std::any::type_name::<P>()))
+        .filter_map(|f| f.offset())
+        .collect()
+}
+
 pub async fn enable_virtual_dependencies<P: Plugin>(tc: &mut ThunkContext) -> anyhow::Result<()>
 where
     P::Virtual: NewFn<Inner = P>,
@@ -363,3 +673,297 @@ async fn test_wire_server() {
     running.await.unwrap().expect_err("should be canceled");
     ()
 }
+
+#[derive(Reality, Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[allow(unused)]
+#[reality(call = test, plugin)]
+pub struct TestEndpoint {
+    #[reality(derive_fromstr)]
+    host: String,
+    port: u16,
+}
+
+#[derive(Reality, Debug, Default, Clone)]
+#[allow(unused)]
+#[reality(call = test, plugin)]
+pub struct TestNestedEndpoint {
+    #[reality(derive_fromstr)]
+    name: String,
+    #[reality(nested)]
+    endpoint: TestEndpoint,
+}
+
+#[tokio::test]
+async fn test_wire_server_field_path() {
+    let mut tc = ThunkContext::new();
+    let server = WireServer::<TestNestedEndpoint>::new(&mut tc)
+        .await
+        .unwrap();
+
+    // Packets addressed by path only do not need the offset or name of the field
+    let mut name = FieldPacket::new::<String>().parse(String::from("demo"));
+    name.field_path = Some(String::from("name"));
+    let mut port = FieldPacket::new::<u16>().parse(String::from("8080"));
+    port.field_path = Some(String::from("endpoint.port"));
+
+    // Changes to a field of a nested field are committed as a change of the nested field
+    let applied = server.commit(&[name, port]).unwrap();
+    assert_eq!(
+        vec!["name", "endpoint"],
+        applied
+            .iter()
+            .map(|p| p.field_name.as_str())
+            .collect::<Vec<_>>()
+    );
+
+    let current = server.listener.routes().borrow().virtual_ref().current();
+    assert_eq!("demo", current.name);
+    assert_eq!(8080, current.endpoint.port);
+
+    // Packets w/o a path fall back to the offset
+    let mut host = FieldPacket::new::<String>().parse(String::from("localhost"));
+    host.field_path = Some(String::from("endpoint.host"));
+    let mut offset = PacketRoutes::<TestNestedEndpoint>::new(TestNestedEndpoint {
+        name: String::from("next"),
+        ..Default::default()
+    })
+    .route::<0>()
+    .encode();
+    offset.field_path = None;
+    server.commit(&[host, offset]).unwrap();
+
+    let current = server.listener.routes().borrow().virtual_ref().current();
+    assert_eq!("next", current.name);
+    assert_eq!("localhost", current.endpoint.host);
+    assert_eq!(8080, current.endpoint.port);
+
+    // Paths that do not resolve to a field are rejected
+    let mut unknown = FieldPacket::new::<u16>().parse(String::from("1"));
+    unknown.field_path = Some(String::from("endpoint.missing"));
+    assert!(server.commit(&[unknown]).is_err());
+}
+
+#[allow(unused)]
+mod tests {
+    use super::*;
+
+    /// Returns a packet that sets the name of Test,
+    ///
+    fn name_packet(name: &str) -> FieldPacket {
+        PacketRoutes::<Test>::new(Test {
+            name: name.to_string(),
+        })
+        .route::<0>()
+        .encode()
+    }
+
+    /// Returns the names set by a batch of packets,
+    ///
+    fn batch_names(batch: &[FieldPacket]) -> Vec<String> {
+        batch
+            .iter()
+            .map(|p| {
+                let mut test = Test::default();
+                assert!(test.set_field(p.clone().into_field_owned()));
+                test.name
+            })
+            .collect()
+    }
+
+    /// Waits for the next batch on a port,
+    ///
+    async fn next_batch(port: &mut WirePort) -> Arc<Vec<FieldPacket>> {
+        tokio::time::timeout(std::time::Duration::from_secs(5), port.next())
+            .await
+            .expect("should receive a batch")
+            .expect("server should be running")
+    }
+
+    /// Waits for the counters of a server to match a condition,
+    ///
+    async fn wait_for_counters(
+        server: &WireServer<Test>,
+        condition: impl FnMut(&WireCounters) -> bool,
+    ) -> WireCounters {
+        let mut counters = server.subscribe_counters();
+        let counters = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            counters.wait_for(condition),
+        )
+        .await
+        .expect("counters should match")
+        .expect("server should be running");
+        *counters
+    }
+
+    /// Returns the name of the current state of Test,
+    ///
+    fn current_name(server: &WireServer<Test>) -> String {
+        server
+            .listener
+            .routes()
+            .borrow()
+            .virtual_ref()
+            .current()
+            .name
+    }
+
+    #[tokio::test]
+    async fn test_wire_server_batches() {
+        let mut tc = ThunkContext::new();
+        let server = WireServer::<Test>::new(&mut tc).await.unwrap();
+
+        let mut first = server.open_port();
+        let mut second = server.open_port_with_capacity(1);
+        tokio::spawn(server.clone().start());
+
+        let client = server.clone().new_client();
+
+        // Batches are applied and delivered to each port in order
+        client
+            .send(vec![name_packet("a"), name_packet("b"), name_packet("c")])
+            .await
+            .unwrap();
+        assert_eq!(
+            vec!["a", "b", "c"],
+            batch_names(&next_batch(&mut first).await)
+        );
+        assert_eq!(
+            vec!["a", "b", "c"],
+            batch_names(&next_batch(&mut second).await)
+        );
+        assert_eq!("c", current_name(&server));
+
+        // A batch w/ a packet that cannot be applied is rejected entirely
+        let mut invalid = name_packet("e");
+        invalid.field_name = String::from("missing");
+        client.send(vec![name_packet("d"), invalid]).await.unwrap();
+        client.send(vec![name_packet("f")]).await.unwrap();
+        assert_eq!(vec!["f"], batch_names(&next_batch(&mut first).await));
+        assert_eq!(vec!["f"], batch_names(&next_batch(&mut second).await));
+        assert_eq!("f", current_name(&server));
+
+        let counters = server.counters();
+        assert_eq!(6, counters.received);
+        assert_eq!(2, counters.rejected);
+        assert_eq!(4, counters.forwarded);
+
+        // Dropped ports are removed
+        drop(second);
+        client.send(vec![name_packet("g")]).await.unwrap();
+        assert_eq!(vec!["g"], batch_names(&next_batch(&mut first).await));
+        assert_eq!(1, server.ports.lock().unwrap().len());
+
+        server.cancel.cancel();
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn test_packet_router_try_route() {
+        let mut tc = ThunkContext::new();
+        let server = WireServer::<Test>::new(&mut tc).await.unwrap();
+
+        // Packets routed one at a time are dispatched to frame updates the same way as a committed batch
+        server
+            .router
+            .try_route::<0>(name_packet("a"))
+            .await
+            .unwrap();
+
+        let mut invalid = name_packet("b");
+        invalid.field_name = String::from("missing");
+        assert!(server.router.try_route::<0>(invalid).await.is_err());
+
+        // Queued dispatches are pushed to the queue by a spawned task, so wait until the update is drained
+        let fields = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            loop {
+                let mut dispatcher = tc.dispatcher::<FrameUpdates>().await;
+                dispatcher.dispatch_all().await;
+                drop(dispatcher);
+
+                let fields = tc
+                    .node()
+                    .await
+                    .resource::<FrameUpdates>(tc.attribute.transmute())
+                    .map(|u| u.frame.fields.clone())
+                    .unwrap_or_default();
+                if !fields.is_empty() {
+                    break fields;
+                }
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("should dispatch frame updates");
+        assert_eq!(vec!["a"], batch_names(&fields));
+    }
+
+    #[tokio::test]
+    async fn test_wire_server_backpressure() {
+        let mut tc = ThunkContext::new();
+        let server = WireServer::<Test>::new(&mut tc).await.unwrap();
+
+        let mut port = server.open_port_with_capacity(1);
+        tokio::spawn(server.clone().start());
+
+        let client = server.clone().new_client();
+        for name in ["1", "2", "3"] {
+            client.send(vec![name_packet(name)]).await.unwrap();
+        }
+
+        // The port is full after the first batch, so the server waits before committing the third batch
+        wait_for_counters(&server, |c| c.retried == 1).await;
+        assert_eq!("2", current_name(&server));
+        assert!(client.try_send(vec![name_packet("4")]).is_err());
+
+        for expected in ["1", "2", "3"] {
+            assert_eq!(vec![expected], batch_names(&next_batch(&mut port).await));
+        }
+        assert_eq!("3", current_name(&server));
+
+        server.cancel.cancel();
+    }
+
+    #[tokio::test]
+    async fn test_wire_client_stages_edits() {
+        let mut tc = ThunkContext::new();
+        let server = WireServer::<Test>::new(&mut tc).await.unwrap();
+
+        let mut port = server.open_port();
+        tokio::spawn(server.clone().start());
+        let client = server.clone().new_client();
+
+        // Edits are made on a staged copy, so the local state is unchanged until the server commits the batch
+        client
+            .try_borrow_modify_batch(|r| {
+                r.route::<0>().edit_value(|_, n| {
+                    *n = String::from("staged");
+                    true
+                });
+
+                let mut invalid = r.route::<0>().encode();
+                invalid.field_name = String::from("missing");
+                Ok(vec![r.route::<0>().encode(), invalid])
+            })
+            .unwrap();
+        assert_eq!("", current_name(&server));
+
+        // The batch is rejected by the server and the local state is left unchanged
+        wait_for_counters(&server, |c| c.rejected == 2).await;
+        assert_eq!("", current_name(&server));
+
+        client
+            .try_borrow_modify(|r| {
+                r.route::<0>().edit_value(|_, n| {
+                    *n = String::from("committed");
+                    true
+                });
+                Ok(r.route::<0>().encode())
+            })
+            .unwrap();
+        assert_eq!(vec!["committed"], batch_names(&next_batch(&mut port).await));
+        assert_eq!("committed", current_name(&server));
+
+        server.cancel.cancel();
+    }
+}
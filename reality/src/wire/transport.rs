@@ -0,0 +1,658 @@
+#[cfg(unix)]
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use anyhow::anyhow;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWrite;
+use tokio::io::AsyncWriteExt;
+use tokio::select;
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+use tracing::error;
+
+use super::history::FieldHistory;
+use super::history::DEFAULT_HISTORY_LEN;
+use super::server::FieldRefController;
+use super::server::WireClient;
+use super::server::WireServer;
+use crate::prelude::*;
+
+/// Version of the wire transport protocol, checked when a remote client connects,
+///
+pub const WIRE_PROTOCOL_VERSION: u32 = 2;
+
+/// Maximum length of a framed message,
+///
+const MAX_MESSAGE_LEN: usize = 64 * 1024 * 1024;
+
+/// Layout of a field of a plugin,
+///
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FieldLayout {
+    /// Offset of the field in the plugin,
+    ///
+    pub offset: usize,
+    /// Name of the field,
+    ///
+    pub name: String,
+    /// Type name of the field's data,
+    ///
+    pub type_name: String,
+}
+
+/// Handshake sent by a remote wire client when it connects to a wire server,
+///
+/// The connection is rejected unless the protocol version, plugin type name and field layout match the plugin hosted by
+/// the server.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Handshake {
+    /// Wire protocol version,
+    ///
+    pub version: u32,
+    /// Type name of the plugin,
+    ///
+    pub plugin: String,
+    /// Layout of each field of the plugin,
+    ///
+    pub fields: Vec<FieldLayout>,
+}
+
+impl Handshake {
+    /// Returns the handshake for plugin P,
+    ///
+    pub fn new<P: Plugin>() -> Self {
+        let fields = P::default()
+            .to_frame(ResourceKey::new())
+            .fields
+            .iter()
+            .map(|f| FieldLayout {
+                offset: f.field_offset,
+                name: f.field_name.to_string(),
+                type_name: f.data_type_name.to_string(),
+            })
+            .collect();
+
+        Self {
+            version: WIRE_PROTOCOL_VERSION,
+            plugin: std::any::type_name::<P>().to_string(),
+            fields,
+        }
+    }
+
+    /// Checks that a remote handshake is compatible w/ this handshake,
+    ///
+    pub fn check(&self, remote: &Handshake) -> anyhow::Result<()> {
+        if self.version != remote.version {
+            return Err(anyhow!(
+                "Unsupported wire protocol version {}, expected {}",
+                remote.version,
+                self.version
+            ));
+        }
+
+        if self.plugin != remote.plugin {
+            return Err(anyhow!(
+                "Plugin mismatch, remote is `{}` but `{}` is hosted",
+                remote.plugin,
+                self.plugin
+            ));
+        }
+
+        if let Some((expected, found)) = self
+            .fields
+            .iter()
+            .zip(remote.fields.iter())
+            .find(|(e, f)| e != f)
+        {
+            return Err(anyhow!(
+                "Field layout mismatch for `{}`, remote has `{}: {}` at offset {} but expected `{}: {}`",
+                self.plugin,
+                found.name,
+                found.type_name,
+                found.offset,
+                expected.name,
+                expected.type_name
+            ));
+        }
+
+        if self.fields.len() != remote.fields.len() {
+            return Err(anyhow!(
+                "Field layout mismatch for `{}`, remote has {} field(s) but expected {}",
+                self.plugin,
+                remote.fields.len(),
+                self.fields.len()
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Enumeration of messages sent over a wire transport,
+///
+#[derive(Serialize, Deserialize, Debug)]
+pub enum WireMessage {
+    /// Sent by the client when connecting,
+    ///
+    Handshake(Handshake),
+    /// Sent by the server if the handshake was accepted,
+    ///
+    Accepted,
+    /// Sent by the server if the handshake was rejected w/ the reason,
+    ///
+    Rejected(String),
+    /// Batch of field packets sent by the client,
+    ///
+    Packets(Vec<FieldPacket>),
+    /// Current state of the plugin sent by the server when the packet routes change,
+    ///
+    Frame(Frame),
+}
+
+/// Writes a length-delimited message to a stream,
+///
+pub async fn write_message(
+    writer: &mut (impl AsyncWrite + Unpin),
+    message: &WireMessage,
+) -> anyhow::Result<()> {
+    let bytes = bincode::serialize(message)?;
+    if bytes.len() > MAX_MESSAGE_LEN {
+        return Err(anyhow!(
+            "Message is too large, {} bytes exceeds {MAX_MESSAGE_LEN}",
+            bytes.len()
+        ));
+    }
+
+    writer.write_u32(bytes.len() as u32).await?;
+    writer.write_all(&bytes).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Reads a length-delimited message from a stream,
+///
+/// Returns None if the stream was closed before the next message.
+///
+pub async fn read_message(
+    reader: &mut (impl AsyncRead + Unpin),
+) -> anyhow::Result<Option<WireMessage>> {
+    let len = match reader.read_u32().await {
+        Ok(len) => len as usize,
+        Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    if len > MAX_MESSAGE_LEN {
+        return Err(anyhow!(
+            "Message is too large, {len} bytes exceeds {MAX_MESSAGE_LEN}"
+        ));
+    }
+
+    let mut bytes = vec![0; len];
+    reader.read_exact(&mut bytes).await?;
+
+    Ok(Some(bincode::deserialize(&bytes)?))
+}
+
+impl<P, const BUFFER_LEN: usize> WireServer<P, BUFFER_LEN>
+where
+    P: Plugin,
+    P::Virtual: NewFn<Inner = P> + FieldRefController<Owner = P>,
+{
+    /// Serves remote wire clients that connect to a tcp listener,
+    ///
+    pub async fn serve_tcp(
+        self: Arc<WireServer<P, BUFFER_LEN>>,
+        listener: tokio::net::TcpListener,
+    ) -> anyhow::Result<()> {
+        let cancel = self.cancel.child_token();
+
+        loop {
+            let (stream, addr) = select! {
+                accepted = listener.accept() => accepted?,
+                _ = cancel.cancelled() => return Ok(()),
+            };
+
+            debug!("Accepted wire connection from {addr}");
+            stream.set_nodelay(true)?;
+            tokio::spawn(self.clone().serve_connection(stream));
+        }
+    }
+
+    /// Serves remote wire clients that connect to a unix domain socket listener,
+    ///
+    #[cfg(unix)]
+    pub async fn serve_unix(
+        self: Arc<WireServer<P, BUFFER_LEN>>,
+        listener: tokio::net::UnixListener,
+    ) -> anyhow::Result<()> {
+        let cancel = self.cancel.child_token();
+
+        loop {
+            let (stream, _) = select! {
+                accepted = listener.accept() => accepted?,
+                _ = cancel.cancelled() => return Ok(()),
+            };
+
+            debug!("Accepted wire connection from unix socket");
+            tokio::spawn(self.clone().serve_connection(stream));
+        }
+    }
+
+    /// Serves a single connection and logs the error if the connection fails,
+    ///
+    async fn serve_connection<S>(self: Arc<WireServer<P, BUFFER_LEN>>, stream: S)
+    where
+        S: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    {
+        if let Err(err) = self.serve_stream(stream).await {
+            error!("Wire connection closed, {err}");
+        }
+    }
+
+    /// Serves a remote wire client over a stream,
+    ///
+    /// After the handshake is accepted, batches of packets sent by the client are forwarded to the listener of the server
+    /// where they are committed in order. Each time the packet routes change, the current state of the plugin is sent to
+    /// the client.
+    ///
+    /// Returns when the client disconnects or the server is cancelled.
+    ///
+    pub async fn serve_stream<S>(
+        self: Arc<WireServer<P, BUFFER_LEN>>,
+        stream: S,
+    ) -> anyhow::Result<()>
+    where
+        S: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    {
+        let (mut reader, mut writer) = tokio::io::split(stream);
+
+        let expected = Handshake::new::<P>();
+        match read_message(&mut reader).await? {
+            Some(WireMessage::Handshake(handshake)) => {
+                if let Err(err) = expected.check(&handshake) {
+                    write_message(&mut writer, &WireMessage::Rejected(err.to_string())).await?;
+                    return Err(err);
+                }
+            }
+            Some(message) => {
+                return Err(anyhow!("Expected a handshake, received {:?}", message));
+            }
+            None => return Err(anyhow!("Connection closed before the handshake")),
+        }
+        write_message(&mut writer, &WireMessage::Accepted).await?;
+
+        let routes = self.listener.routes();
+        let mut changes = routes.subscribe();
+        let frame = current_frame(&routes.borrow());
+        write_message(&mut writer, &WireMessage::Frame(frame)).await?;
+
+        let server = self.clone();
+        let mut incoming = tokio::spawn(async move {
+            while let Some(message) = read_message(&mut reader).await? {
+                match message {
+                    WireMessage::Packets(packets) => {
+                        // Waits for capacity so that a fast client cannot outrun the listener
+                        server.listener.frame_tx().send(packets).await?;
+                    }
+                    message => {
+                        return Err(anyhow!("Unexpected message from client {:?}", message));
+                    }
+                }
+            }
+            Ok::<_, anyhow::Error>(())
+        });
+
+        let cancel = self.cancel.child_token();
+        loop {
+            select! {
+                changed = changes.changed() => {
+                    changed?;
+                    let frame = current_frame(&changes.borrow_and_update());
+                    write_message(&mut writer, &WireMessage::Frame(frame)).await?;
+                }
+                result = &mut incoming => {
+                    return result?;
+                }
+                _ = cancel.cancelled() => {
+                    incoming.abort();
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Applies field packets received from a remote wire server to the mirror and notifies subscribers,
+    ///
+    fn apply_packets(&self, packets: &[FieldPacket]) {
+        let routes = self.listener.routes();
+        let owner = routes.borrow().virtual_ref().send_raw();
+
+        owner.send_if_modified(|owner| {
+            let mut applied = false;
+            for packet in packets {
+                applied |= owner.set_field(packet.clone().into_field_owned());
+            }
+            applied
+        });
+
+        routes.send_modify(|_| {});
+    }
+
+    /// Returns a wire server that mirrors a plugin hosted by a remote wire server,
+    ///
+    fn mirror() -> Arc<WireServer<P, BUFFER_LEN>> {
+        let listener = FrameListener::<P, BUFFER_LEN>::new(P::default());
+
+        Arc::new(WireServer {
+            router: Arc::new(PacketRouter::new(listener.routes())),
+            listener,
+            cancel: CancellationToken::new(),
+            ports: Default::default(),
+            history: Mutex::new(FieldHistory::new(
+                DEFAULT_HISTORY_LEN,
+                P::default().to_frame(ResourceKey::new()),
+            )),
+            metrics: Default::default(),
+            counters: tokio::sync::watch::Sender::new(WireCounters::default()),
+        })
+    }
+}
+
+impl<P, const BUFFER_LEN: usize> WireClient<P, BUFFER_LEN>
+where
+    P: Plugin,
+    P::Virtual: NewFn<Inner = P> + FieldRefController<Owner = P>,
+{
+    /// Connects to a remote wire server listening on a tcp address,
+    ///
+    pub async fn connect_tcp(
+        addr: impl tokio::net::ToSocketAddrs,
+    ) -> anyhow::Result<WireClient<P, BUFFER_LEN>> {
+        let stream = tokio::net::TcpStream::connect(addr).await?;
+        stream.set_nodelay(true)?;
+
+        Self::connect_stream(stream).await
+    }
+
+    /// Connects to a remote wire server listening on a unix domain socket,
+    ///
+    #[cfg(unix)]
+    pub async fn connect_unix(path: impl AsRef<Path>) -> anyhow::Result<WireClient<P, BUFFER_LEN>> {
+        let stream = tokio::net::UnixStream::connect(path).await?;
+
+        Self::connect_stream(stream).await
+    }
+
+    /// Connects to a remote wire server over a stream,
+    ///
+    /// Returns a client whose packet routes mirror the plugin hosted by the remote server. Packets sent w/ the client are
+    /// forwarded to the remote server, and the packet routes are updated each time the remote server sends a frame.
+    ///
+    pub async fn connect_stream<S>(stream: S) -> anyhow::Result<WireClient<P, BUFFER_LEN>>
+    where
+        S: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    {
+        let (mut reader, mut writer) = tokio::io::split(stream);
+
+        write_message(&mut writer, &WireMessage::Handshake(Handshake::new::<P>())).await?;
+        match read_message(&mut reader).await? {
+            Some(WireMessage::Accepted) => {}
+            Some(WireMessage::Rejected(reason)) => {
+                return Err(anyhow!(
+                    "Remote wire server rejected the handshake, {reason}"
+                ));
+            }
+            message => {
+                return Err(anyhow!(
+                    "Expected a handshake response, received {:?}",
+                    message
+                ));
+            }
+        }
+
+        let mirror = WireServer::<P, BUFFER_LEN>::mirror();
+
+        // Wait for the current state so that the client is in sync when it is returned
+        match read_message(&mut reader).await? {
+            Some(WireMessage::Frame(frame)) => mirror.apply_packets(&frame.fields),
+            message => {
+                return Err(anyhow!(
+                    "Expected the current frame, received {:?}",
+                    message
+                ));
+            }
+        }
+
+        let remote = mirror.clone();
+        let mut incoming = tokio::spawn(async move {
+            while let Some(message) = read_message(&mut reader).await? {
+                match message {
+                    WireMessage::Frame(frame) => remote.apply_packets(&frame.fields),
+                    message => {
+                        return Err(anyhow!("Unexpected message from server {:?}", message));
+                    }
+                }
+            }
+            Ok::<_, anyhow::Error>(())
+        });
+
+        let mut listener = mirror.listener.clone();
+        let cancel = mirror.cancel.child_token();
+        tokio::spawn(async move {
+            let result = loop {
+                select! {
+                    next = listener.listen() => {
+                        let sent = match next {
+                            Ok(packets) => write_message(&mut writer, &WireMessage::Packets(packets)).await,
+                            Err(err) => Err(err),
+                        };
+                        if let Err(err) = sent {
+                            break Err(err);
+                        }
+                    }
+                    result = &mut incoming => {
+                        break result.map_err(anyhow::Error::from).and_then(|r| r);
+                    }
+                    _ = cancel.cancelled() => {
+                        incoming.abort();
+                        break Ok(());
+                    }
+                }
+            };
+
+            if let Err(err) = result {
+                error!("Remote wire connection closed, {err}");
+            }
+        });
+
+        Ok(mirror.new_client())
+    }
+
+    /// Disconnects the client from a remote wire server,
+    ///
+    pub fn disconnect(&self) {
+        self.0.cancel.cancel();
+    }
+}
+
+/// Returns the current state of the plugin of packet routes as a frame,
+///
+fn current_frame<P>(routes: &PacketRoutes<P>) -> Frame
+where
+    P: Plugin,
+    P::Virtual: FieldRefController<Owner = P>,
+{
+    routes.virtual_ref().current().to_frame(ResourceKey::new())
+}
+
+#[allow(unused)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::wire::server::Test;
+
+    #[derive(Reality, Debug, Default, Clone)]
+    #[reality(call = test, plugin)]
+    pub struct Other {
+        #[reality(derive_fromstr)]
+        name: std::path::PathBuf,
+    }
+
+    async fn test(_: &mut ThunkContext) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Starts a wire server hosting Test,
+    ///
+    async fn start_server() -> Arc<WireServer<Test>> {
+        let mut tc = ThunkContext::new();
+        let server = WireServer::<Test>::new(&mut tc).await.unwrap();
+        tokio::spawn(server.clone().start());
+        server
+    }
+
+    /// Sends a name from a client and waits for the routes to change to the expected name,
+    ///
+    async fn send_name(
+        client: &WireClient<Test>,
+        mut routes: tokio::sync::watch::Receiver<PacketRoutes<Test>>,
+        name: &str,
+    ) {
+        client
+            .try_borrow_modify(|r| {
+                r.route::<0>().edit_value(|_, n| {
+                    *n = name.to_string();
+                    true
+                });
+                Ok(r.route::<0>().encode())
+            })
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                routes.changed().await.unwrap();
+                let mut current = String::new();
+                routes
+                    .borrow_and_update()
+                    .route::<0>()
+                    .view_value(|v| current = v.to_string());
+                if current == name {
+                    return;
+                }
+            }
+        })
+        .await
+        .expect("should receive the change");
+    }
+
+    #[test]
+    fn test_handshake() {
+        let handshake = Handshake::new::<Test>();
+        assert_eq!(1, handshake.fields.len());
+        assert_eq!("name", handshake.fields[0].name);
+        handshake.check(&Handshake::new::<Test>()).unwrap();
+
+        let mut remote = Handshake::new::<Test>();
+        remote.version += 1;
+        assert!(handshake
+            .check(&remote)
+            .unwrap_err()
+            .to_string()
+            .contains("version"));
+
+        let err = handshake.check(&Handshake::new::<Other>()).unwrap_err();
+        assert!(err.to_string().contains("Plugin mismatch"), "{err}");
+
+        let mut remote = Handshake::new::<Test>();
+        remote.fields[0].type_name = "u64".to_string();
+        assert!(handshake
+            .check(&remote)
+            .unwrap_err()
+            .to_string()
+            .contains("Field layout mismatch"));
+    }
+
+    #[tokio::test]
+    async fn test_tcp_transport() {
+        let server = start_server().await;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(server.clone().serve_tcp(listener));
+
+        let client = WireClient::<Test>::connect_tcp(addr).await.unwrap();
+
+        // Packets sent by the remote client are applied by the server
+        send_name(
+            &client,
+            server.clone().subscribe_packet_routes(),
+            "hello remote",
+        )
+        .await;
+
+        // Changes on the server are sent to the remote client
+        let local = server.clone().new_client();
+        let mut remote_routes = client.subscribe();
+        send_name(
+            &local,
+            server.clone().subscribe_packet_routes(),
+            "hello local",
+        )
+        .await;
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let mut current = String::new();
+                remote_routes
+                    .borrow_and_update()
+                    .route::<0>()
+                    .view_value(|v| current = v.to_string());
+                if current == "hello local" {
+                    return;
+                }
+                remote_routes.changed().await.unwrap();
+            }
+        })
+        .await
+        .expect("remote client should receive the change");
+
+        // Plugins w/ a different type or layout are rejected
+        let Err(err) = WireClient::<Other>::connect_tcp(addr).await else {
+            panic!("should reject a different plugin");
+        };
+        assert!(err.to_string().contains("rejected"), "{err}");
+
+        client.disconnect();
+        server.cancel.cancel();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_transport() {
+        let server = start_server().await;
+
+        let path = std::env::temp_dir().join(format!("reality-wire-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        tokio::spawn(server.clone().serve_unix(listener));
+
+        let client = WireClient::<Test>::connect_unix(&path).await.unwrap();
+        send_name(
+            &client,
+            server.clone().subscribe_packet_routes(),
+            "hello unix",
+        )
+        .await;
+
+        client.disconnect();
+        server.cancel.cancel();
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -117,6 +117,10 @@ impl Parse for EnumData {
                     if meta.path.is_ident("rename_prefix") {
                         meta.input.parse::<Token![=]>()?;
                         enum_data.rename_prefix = meta.input.parse()?;
+                    } else if meta.input.peek(Token![=]) {
+                        // Skip attributes used by other derives, i.e. `Reality`
+                        meta.input.parse::<Token![=]>()?;
+                        meta.input.parse::<syn::Expr>()?;
                     }
                     Ok(())
                 })?;
@@ -163,9 +167,15 @@ impl EnumData {
 
     /// Renders a register fn,
     ///
+    /// **Note** Only rendered if a variant is a plugin, so that the enum can also derive `Reality`,
+    ///
     pub fn render_register(&self) -> TokenStream {
         let name = &self.input.ident;
 
+        if !self.variants.iter().any(|v| v.plugin) {
+            return quote::quote!();
+        }
+
         let (_, ty_generics, where_clause) = self.input.generics.split_for_impl();
 
         let variants = self.variants.iter().filter(|v| v.plugin).map(|v| {
@@ -221,7 +231,7 @@ impl EnumData {
                         let name = &f.ident;
                         let ty = &f.ty;
                         quote_spanned!(f.ident.span()=>
-                            #name: #ty::default(),
+                            #name: <#ty>::default()
                         )
                     });
 
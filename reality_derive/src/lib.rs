@@ -1,11 +1,12 @@
 mod enum_data;
 mod struct_data;
 mod struct_field;
+mod test_data;
 
 use enum_data::EnumData;
-use quote::quote_spanned;
 use struct_data::StructData;
-use syn::{parse_macro_input, DeriveInput};
+use syn::parse_macro_input;
+use test_data::TestData;
 
 /// Derives the AttributeType as well as field parsers,
 ///
@@ -33,11 +34,13 @@ pub fn derive_flags(_item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     enum_data.render().into()
 }
 
+/// Derives a runmd-driven test harness,
+///
+/// Compiles the fixture w/ an isolated engine and initializes each field from the result of running an address.
+///
 #[proc_macro_derive(RealityTest, attributes(reality))]
 pub fn derive_reality_test(_item: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let struct_data = parse_macro_input!(_item as DeriveInput);
+    let test_data = parse_macro_input!(_item as TestData);
 
-    quote_spanned!(struct_data.ident.span()=>
-    )
-    .into()
+    test_data.render().into()
 }
@@ -22,6 +22,7 @@ use syn::TypeGenerics;
 use syn::Visibility;
 use syn::WhereClause;
 
+use crate::struct_field::parse_docs;
 use crate::struct_field::StructField;
 
 /// Parses a struct from derive attribute,
@@ -71,6 +72,9 @@ pub(crate) struct StructData {
     /// Replace thee
     ///
     replace: Option<Type>,
+    /// Doc comments of the struct,
+    ///
+    docs: String,
 }
 
 impl Parse for StructData {
@@ -78,6 +82,7 @@ impl Parse for StructData {
         let derive_input = DeriveInput::parse(input)?;
 
         let name = derive_input.ident;
+        let docs = parse_docs(&derive_input.attrs);
 
         let mut reality_rename = None;
         let mut reality_on_load = None;
@@ -246,6 +251,7 @@ impl Parse for StructData {
                 ext,
                 call,
                 replace,
+                docs,
                 vis: derive_input.vis,
             })
         }
@@ -518,6 +524,25 @@ impl StructData {
             }
         });
 
+        let set_condition_impl = self.iter_virtual_fields().map(|f| {
+            let name = &f.name;
+            let offset = f.offset;
+            quote_spanned! {f.span=>
+                #offset => {
+                    self.#name.set_condition(condition);
+                    true
+                }
+            }
+        });
+
+        let condition_impl = self.iter_virtual_fields().map(|f| {
+            let name = &f.name;
+            let offset = f.offset;
+            quote_spanned! {f.span=>
+                #offset => Some(self.#name.condition()),
+            }
+        });
+
         let on_read_fields = self
             .iter_virtual_fields()
             .map(|f| {
@@ -577,6 +602,20 @@ impl StructData {
                 fn current(&self) -> Self::Owner {
                     self.owner.subscribe().borrow().to_owned()
                 }
+
+                fn set_condition(&mut self, offset: usize, condition: reality::FieldCondition) -> bool {
+                    match offset {
+                        #(#set_condition_impl)*
+                        _ => false
+                    }
+                }
+
+                fn condition(&self, offset: usize) -> Option<reality::FieldCondition> {
+                    match offset {
+                        #(#condition_impl)*
+                        _ => None
+                    }
+                }
             }
 
             impl #impl_generics From<#ident #ty_generics> for #virtual_ident #where_clause  {
@@ -686,6 +725,20 @@ impl StructData {
             let _fromstr_derive = {
                 fields.iter().find(|f| f.derive_fromstr).map(|f| {
                     let name = &f.name;
+                    let offset = &f.offset;
+
+                    // Validate the value if the field has validation attributes
+                    let parse = if f.render_validate_fn().is_some() {
+                        quote_spanned!(f.span=>
+                            let value = s.parse()?;
+                            <Self as OnParseField<#offset>>::validate(&value, s)?;
+                            _s.#name = value;
+                        )
+                    } else {
+                        quote_spanned!(f.span=>
+                            _s.#name = s.parse()?;
+                        )
+                    };
 
                     quote_spanned!(self.span=>
                       impl #impl_generics std::str::FromStr for #owner #ty_generics #where_clause {
@@ -693,7 +746,7 @@ impl StructData {
 
                           fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
                               let mut _s = Self::default();
-                              _s.#name = s.parse()?;
+                              #parse
                               Ok(_s)
                           }
                         }
@@ -790,7 +843,11 @@ impl StructData {
         // let fields = self.fields.clone();
         let fields = self.iter_parse_fields().enumerate().map(|(offset, f)| {
             // let ty = &f.field_ty();
-            if f.attribute_type.as_ref().is_some() {
+            if f.nested {
+                quote_spanned! {f.span=>
+                    parser.add_parseable_nested_field::<#offset, Self>();
+                }
+            } else if f.attribute_type.as_ref().is_some() {
                 quote_spanned! {f.span=>
                     parser.add_parseable_attribute_type_field::<#offset, Self>();
                 }
@@ -810,6 +867,22 @@ impl StructData {
             }
         });
 
+        // Required fields must be defined before the node is unloaded
+        let required = self.iter_parse_fields().filter(|f| f.required).map(|f| {
+            let field_name = f.field_name_lit_str();
+            if f.derive_fromstr {
+                quote_spanned! {f.span=>
+                    if content.as_ref().trim().is_empty() {
+                        parser.require_field::<Self>(#field_name);
+                    }
+                }
+            } else {
+                quote_spanned! {f.span=>
+                    parser.require_field::<Self>(#field_name);
+                }
+            }
+        });
+
         let runir_field_impl = self.iter_virtual_fields().map(|f| {
             let field_ident = f.field_name_lit_str();
             let ty = f.field_ty();
@@ -833,6 +906,10 @@ impl StructData {
                     fn field_name() -> &'static str {
                         #field_ident
                     }
+
+                    fn is_secret() -> bool {
+                        (&SecretFlag::<#ty>(std::marker::PhantomData)).is_secret()
+                    }
                 }
             }
         });
@@ -848,11 +925,18 @@ impl StructData {
             let callback = f.render_field_parse_callback();
             let get_fn = f.render_get_fn();
             let get_mut_fn = f.render_get_mut_fn();
+            let validate_fn = f.render_validate_fn();
 
             let mut_value = f.set_of.as_ref().or(f.map_of.as_ref()).map(|_| quote!(mut));
 
             quote_spanned! {f.span=>
                 impl #impl_generics OnParseField<#offset> for #ident #ty_generics #where_clause {
+                    #validate_fn
+
+                    fn describe_parse_error(err: &<Self::ParseType as std::str::FromStr>::Err) -> Option<String> {
+                        (&DescribeError(err)).describe()
+                    }
+
                     #[allow(unused_variables)]
                     fn on_parse(&mut self, #mut_value value: #ty, _input: &str, _tag: Option<&String>) -> ResourceKey<Property> {
                         let mut hasher = ResourceKeyHashBuilder::new_default_hasher();
@@ -904,10 +988,11 @@ impl StructData {
 
                 impl #impl_generics AttributeType<Shared> for #ident #ty_generics #where_clause {
                     fn parse(parser: &mut AttributeParser<Shared>, content: impl AsRef<str>) {
-                        let mut enable = parser.parse_attribute::<Self>(content.as_ref());
+                        let mut enable = parser.parse_attribute_with::<Self>(content.as_ref(), |err| (&DescribeError(err)).describe());
 
                         if enable.is_ok() {
                             #(#fields)*
+                            #(#required)*
                         }
                     }
                 }
@@ -1120,6 +1205,7 @@ impl StructData {
                     #(#synchronizable)*
                 }
 
+                #[allow(deprecated)]
                 fn listen_one(router: std::sync::Arc<PacketRouter<Self>>) -> std::pin::Pin<Box<dyn Future<Output = ()> + Send>> {
                     Box::pin(async move {
                         let _ = tokio::join!(#(#route_fields),*);
@@ -1183,6 +1269,18 @@ impl StructData {
             from_shared = Some(self.clone().pack_unpack_impl());
         }
 
+        // Schema of the runmd surface of this object,
+        let docs = &self.docs;
+        let input = self
+            .iter_parse_fields()
+            .find(|f| f.derive_fromstr)
+            .map(|f| {
+                let name = f.field_name_lit_str();
+                quote!(Some(#name.to_string()))
+            })
+            .unwrap_or(quote!(None));
+        let schema_fields = self.iter_parse_fields().map(|f| f.render_schema());
+
         let object_type_trait = quote_spanned!(self.span=>
             #[async_trait(?Send)]
             impl #impl_generics BlockObject for #name #ty_generics #where_clause {
@@ -1197,6 +1295,16 @@ impl StructData {
                 fn on_completed(storage: AsyncStorageTarget<Shared>) -> Option<AsyncStorageTarget<Shared>> {
                     #on_completed
                 }
+
+                fn schema() -> PluginSchema {
+                    PluginSchema {
+                        symbol: <Self as runir::prelude::Recv>::symbol().to_string(),
+                        ty: std::any::type_name::<Self>().to_string(),
+                        docs: #docs.to_string(),
+                        input: #input,
+                        fields: vec![#(#schema_fields),*],
+                    }
+                }
             }
 
             impl #impl_generics ToFrame for #name #ty_generics #where_clause {
@@ -1225,18 +1333,107 @@ impl StructData {
     fn object_ty_api(self) -> TokenStream {
         let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
         let name = &self.name;
-        let fields = self.fields.iter().filter(|f| !f.ignore && !f.not_wire && self.replace.is_none()).map(|f| {
+        let wire_fields = || {
+            self.fields
+                .iter()
+                .filter(|f| !f.ignore && !f.not_wire && self.replace.is_none())
+        };
+
+        // Packets addressed by path resolve the field by name
+        let paths = wire_fields().map(|f| {
+            let name = f.field_name_lit_str();
+            let offset = f.offset;
+            quote_spanned!(f.span=> #name => (#offset, #name))
+        });
+
+        // Packets addressed to a field of a nested field are applied to the nested value,
+        // collections are skipped since the path does not select an element
+        let nested = wire_fields()
+            .filter(|f| {
+                f.nested
+                    && f.vec_of.is_none()
+                    && f.vecdeq_of.is_none()
+                    && f.set_of.is_none()
+                    && f.map_of.is_none()
+                    && f.option_of.is_none()
+            })
+            .map(|f| {
+                let name = &f.name;
+                let name_lit = f.field_name_lit_str();
+                let ty = &f.ty;
+                quote_spanned!(f.span=>
+                    #name_lit => <#ty as SetField<FieldPacket>>::set_field(&mut self.#name, FieldOwned { owner, name, offset, value })
+                )
+            });
+
+        let fields = wire_fields().map(|f| {
             let ty = &f.ty;
             let name = f.field_name_lit_str();
             let offset = f.offset;
             let wire_method = f.wire.as_ref().map(|f| quote_spanned!(f.span()=> #f)).unwrap_or(quote!(into_box));
 
+            let load = quote_spanned!(f.span=>
+                if let Some(value) = value.#wire_method::<#ty>() {
+                    <Self as SetField<#ty>>::set_field(self, FieldOwned { owner, name, offset, value: *value })
+                } else {
+                    tracing::error!("Could not read value for {}.{}", stringify!(#ty), #name);
+                    false
+                }
+            );
+
+            // Fields w/ a wire method load parse packets w/ the wire method, otherwise input is parsed the same way as a
+            // property of the field
+            let mut branches = vec![];
+            let parseable = f.is_parse
+                && !f.nested
+                && !f.ext
+                && f.attribute_type.is_none()
+                && f.map_of.is_none()
+                && f.variant.is_none();
+            if f.wire.is_some() {
+                branches.push((quote!(code == Code::Load || code == Code::LoadReplace || code == Code::LoadParse), load));
+            } else {
+                branches.push((quote!(code == Code::Load || code == Code::LoadReplace), load));
+
+                if parseable {
+                    let parse_ty = f.field_ty();
+                    branches.push((quote!(code == Code::LoadParse), quote_spanned!(f.span=>
+                        match value.parse_input().map(|input| (input, input.parse::<#parse_ty>())) {
+                            Some((input, Ok(parsed))) if <Self as OnParseField<#offset>>::validate(&parsed, input).is_ok() => {
+                                <Self as OnParseField<#offset>>::on_parse(self, parsed, input, None);
+                                true
+                            }
+                            _ => {
+                                tracing::error!("Could not parse value for {}.{}", stringify!(#ty), #name);
+                                false
+                            }
+                        }
+                    )));
+                }
+            }
+
+            // Collection fields can load a value by merging or appending
+            let collection = f.vec_of.is_some() || f.vecdeq_of.is_some() || f.set_of.is_some() || f.map_of.is_some();
+            if collection && f.variant.is_none() {
+                for (code, method) in [(quote!(LoadMerge), quote!(load_merge)), (quote!(LoadAppend), quote!(load_append))] {
+                    branches.push((quote!(code == Code::#code), quote_spanned!(f.span=>
+                        if let Some(value) = value.#wire_method::<#ty>() {
+                            LoadCollection::#method(<Self as OnParseField<#offset>>::get_mut(self), *value);
+                            true
+                        } else {
+                            tracing::error!("Could not read value for {}.{}", stringify!(#ty), #name);
+                            false
+                        }
+                    )));
+                }
+            }
+
+            let branches = branches.into_iter().map(|(condition, body)| quote!(if #condition { #body }));
+
             quote_spanned!(f.span=>
                 (#offset, #name) => {
-                    if let Some(value) = value.#wire_method::<#ty>() {
-                        <Self as SetField<#ty>>::set_field(self, FieldOwned { owner, name, offset, value: *value })
-                    } else {
-                        tracing::error!("Could not read value for {}.{}", stringify!(#ty), #name);
+                    let code = value.code();
+                    #(#branches else)* {
                         false
                     }
                 }
@@ -1245,10 +1442,33 @@ impl StructData {
 
         quote_spanned!(self.span=>
             impl #impl_generics SetField<FieldPacket> for #name #ty_generics #where_clause {
+                /// Sets a field from a packet according to the load operation of the packet,
+                ///
+                /// Returns false for any other operation.
+                ///
+                /// If the packet has a field path, the field is resolved from the path instead of the offset.
+                ///
                 fn set_field(&mut self, field: FieldOwned<FieldPacket>) -> bool {
-                    let FieldOwned { owner, name, offset, value } = field;
+                    let FieldOwned { owner, name, offset, mut value } = field;
+
+                    if let Some((head, rest)) = value.field_path.as_deref().and_then(|p| p.split_once('.')) {
+                        let head = head.to_string();
+                        value.field_path = Some(rest.to_string());
+                        return match head.as_str() {
+                            #(#nested,)*
+                            _ => false
+                        };
+                    }
+
+                    let (offset, field_name) = match value.field_path.as_deref() {
+                        Some(path) => match path {
+                            #(#paths,)*
+                            _ => return false
+                        },
+                        None => (offset, value.field_name.as_str()),
+                    };
 
-                    match (offset, value.field_name.as_str()) {
+                    match (offset, field_name) {
                         #(#fields)*
                         _ => false
                     }
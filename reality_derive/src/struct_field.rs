@@ -3,11 +3,13 @@ use std::vec;
 use proc_macro2::Ident;
 use proc_macro2::Span;
 use proc_macro2::TokenStream;
+use quote::quote;
 use quote::quote_spanned;
 use quote::ToTokens;
 use syn::parse::Parse;
 use syn::parse2;
 use syn::parse_quote;
+use syn::spanned::Spanned;
 use syn::Attribute;
 use syn::Expr;
 use syn::LitStr;
@@ -77,6 +79,9 @@ pub(crate) struct StructField {
     /// True if this field should be enabled as an ext,
     ///
     pub ext: bool,
+    /// True if the fields of this field's type are defined in a nested scope,
+    ///
+    pub nested: bool,
     /// True if this field should be enabled as a plugin collection,
     ///
     pub plugin: bool,
@@ -86,6 +91,21 @@ pub(crate) struct StructField {
     /// Disable a field's wire representation,
     ///
     pub not_wire: bool,
+    /// True if the field must be defined by the node,
+    ///
+    pub required: bool,
+    /// Range the parsed value must be contained by,
+    ///
+    pub range: Option<Expr>,
+    /// Pattern the input must match,
+    ///
+    pub regex: Option<LitStr>,
+    /// Comma-seperated list of accepted inputs,
+    ///
+    pub one_of: Option<LitStr>,
+    /// Doc comments of the field,
+    ///
+    pub docs: String,
     pub is_decorated: bool,
     pub offset: usize,
     pub is_virtual: bool,
@@ -523,6 +543,136 @@ impl StructField {
 
         callback
     }
+
+    /// Renders the validate fn of the field's OnParseField impl if the field has validation attributes,
+    ///
+    pub fn render_validate_fn(&self) -> Option<TokenStream> {
+        if self.range.is_none() && self.regex.is_none() && self.one_of.is_none() {
+            return None;
+        }
+
+        let ty = self.field_ty();
+
+        let range = self.range.as_ref().map(|range| {
+            quote_spanned!(range.span()=>
+                if !(#range).contains(value) {
+                    return Err(anyhow::anyhow!("value is out of range, expected {}", stringify!(#range)));
+                }
+            )
+        });
+
+        let regex = self.regex.as_ref().map(|pattern| {
+            quote_spanned!(pattern.span()=>
+                validate_regex(#pattern, input)?;
+            )
+        });
+
+        let one_of = self.one_of.as_ref().map(|one_of| {
+            let accepted = self.one_of_values();
+            quote_spanned!(one_of.span()=>
+                if ![#(#accepted),*].contains(&input.trim()) {
+                    return Err(anyhow::anyhow!("value is not one of {}", #one_of));
+                }
+            )
+        });
+
+        Some(quote_spanned!(self.span=>
+            #[allow(unused_variables)]
+            fn validate(value: &#ty, input: &str) -> anyhow::Result<()> {
+                #range
+                #regex
+                #one_of
+                Ok(())
+            }
+        ))
+    }
+
+    /// Returns the accepted inputs of a field w/ `one_of`,
+    ///
+    fn one_of_values(&self) -> Vec<String> {
+        self.one_of
+            .as_ref()
+            .map(|one_of| {
+                one_of
+                    .value()
+                    .split(',')
+                    .map(|v| v.trim().to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Renders the FieldSchema of the field,
+    ///
+    pub fn render_schema(&self) -> TokenStream {
+        let name = self.field_name_lit_str();
+        let ty = self.field_ty();
+        let docs = &self.docs;
+        let required = self.required;
+
+        let kind = if self.vec_of.is_some() {
+            quote!(Vec)
+        } else if self.vecdeq_of.is_some() {
+            quote!(VecDeque)
+        } else if self.set_of.is_some() {
+            quote!(Set)
+        } else if self.map_of.is_some() {
+            quote!(Map)
+        } else if self.option_of.is_some() {
+            quote!(Option)
+        } else if self.nested {
+            quote!(Nested)
+        } else if self.attribute_type.is_some() {
+            quote!(AttributeType)
+        } else if self.ext {
+            quote!(Ext)
+        } else if self.decorated.is_some() || self.is_decorated {
+            quote!(Decorated)
+        } else {
+            quote!(Scalar)
+        };
+
+        let bound = |b: &Option<Box<Expr>>| {
+            b.as_ref()
+                .map(|b| quote!((&SchemaBound(#b)).to_f64()))
+                .unwrap_or(quote!(None))
+        };
+        let (minimum, maximum, exclusive_maximum) = match self.range.as_ref() {
+            Some(Expr::Range(range)) => match range.limits {
+                syn::RangeLimits::Closed(_) => {
+                    (bound(&range.start), bound(&range.end), quote!(None))
+                }
+                syn::RangeLimits::HalfOpen(_) => {
+                    (bound(&range.start), quote!(None), bound(&range.end))
+                }
+            },
+            _ => (quote!(None), quote!(None), quote!(None)),
+        };
+
+        let pattern = self
+            .regex
+            .as_ref()
+            .map(|r| quote!(Some(#r.to_string())))
+            .unwrap_or(quote!(None));
+
+        let one_of = self.one_of_values();
+
+        quote_spanned!(self.span=>
+            FieldSchema {
+                name: #name.to_string(),
+                ty: std::any::type_name::<#ty>().to_string(),
+                kind: FieldKind::#kind,
+                docs: #docs.to_string(),
+                required: #required,
+                minimum: #minimum,
+                maximum: #maximum,
+                exclusive_maximum: #exclusive_maximum,
+                pattern: #pattern,
+                one_of: vec![#(#one_of.to_string()),*],
+                secret: (&SecretFlag::<#ty>(std::marker::PhantomData)).is_secret(),
+            }
+        )
+    }
 }
 
 impl Parse for StructField {
@@ -543,8 +693,13 @@ impl Parse for StructField {
         let mut wire = None;
         let mut derive_fromstr = false;
         let mut ext = false;
+        let mut nested = false;
         let mut plugin = false;
         let mut not_wire = false;
+        let mut required = false;
+        let mut range = None;
+        let mut regex = None;
+        let mut one_of = None;
         let mut is_virtual = true;
         let mut is_parse = true;
         let span = input.span();
@@ -557,6 +712,8 @@ impl Parse for StructField {
 
         let ty = input.parse::<Type>()?;
 
+        let docs = parse_docs(&attributes);
+
         for attribute in attributes {
             // #[reality(ignore, rename = "SOME_NAME")]
             if attribute.path().is_ident("reality") {
@@ -707,6 +864,10 @@ impl Parse for StructField {
                         ext = true;
                     }
 
+                    if meta.path.is_ident("nested") {
+                        nested = true;
+                    }
+
                     if meta.path.is_ident("plugin") {
                         plugin = true;
                     }
@@ -719,6 +880,25 @@ impl Parse for StructField {
                         derive_fromstr = true;
                     }
 
+                    if meta.path.is_ident("required") {
+                        required = true;
+                    }
+
+                    if meta.path.is_ident("range") {
+                        meta.input.parse::<Token![=]>()?;
+                        range = Some(meta.input.parse::<Expr>()?);
+                    }
+
+                    if meta.path.is_ident("regex") {
+                        meta.input.parse::<Token![=]>()?;
+                        regex = Some(meta.input.parse::<LitStr>()?);
+                    }
+
+                    if meta.path.is_ident("one_of") {
+                        meta.input.parse::<Token![=]>()?;
+                        one_of = Some(meta.input.parse::<LitStr>()?);
+                    }
+
                     Ok(())
                 })?;
             }
@@ -738,8 +918,14 @@ impl Parse for StructField {
             parse_callback: callback,
             attribute_type,
             ext,
+            nested,
             plugin,
             not_wire,
+            required,
+            range,
+            regex,
+            one_of,
+            docs,
             span,
             ignore,
             visibility,
@@ -772,6 +958,29 @@ impl Parse for StructField {
     }
 }
 
+/// Returns the doc comments from a list of attributes joined by new lines,
+///
+pub(crate) fn parse_docs(attributes: &[Attribute]) -> String {
+    attributes
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(doc),
+                        ..
+                    }),
+                ..
+            }) => Some(doc.value().trim().to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
 #[test]
 fn test_struct_field_parsing() {
     use quote::ToTokens;
@@ -794,3 +1003,25 @@ name: String
     assert_eq!("name", field.name.to_string().as_str());
     assert_eq!("String", field.ty.to_token_stream().to_string().as_str());
 }
+
+#[test]
+fn test_struct_field_validation_parsing() {
+    let stream = <proc_macro2::TokenStream as std::str::FromStr>::from_str(
+        r#"
+#[reality(required, range = 1..=10, regex = "^[0-9]+$", one_of = "1, 2")]
+count: u32
+"#,
+    )
+    .unwrap();
+
+    let field = syn::parse2::<StructField>(stream).unwrap();
+
+    assert!(field.required);
+    assert_eq!(
+        "1 ..= 10",
+        field.range.as_ref().unwrap().to_token_stream().to_string()
+    );
+    assert_eq!("^[0-9]+$", field.regex.as_ref().unwrap().value());
+    assert_eq!("1, 2", field.one_of.as_ref().unwrap().value());
+    assert!(field.render_validate_fn().is_some());
+}
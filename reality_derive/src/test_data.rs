@@ -0,0 +1,279 @@
+use proc_macro2::Ident;
+use proc_macro2::Span;
+use proc_macro2::TokenStream;
+use quote::quote;
+use quote::quote_spanned;
+use syn::parse::Parse;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Fields;
+use syn::Generics;
+use syn::LitStr;
+use syn::Path;
+use syn::Token;
+use syn::Type;
+
+/// Parses a runmd test fixture from derive attribute,
+///
+/// ``` norun
+/// #[derive(RealityTest)]
+/// #[reality(fixture = "```runmd ... ```", enable(Echo))]
+/// struct EchoTest {
+///     #[reality(plugin = "echo/test.echo")]
+///     echo: Echo,
+/// }
+/// ```
+pub(crate) struct TestData {
+    /// Span of the struct being derived,
+    ///
+    span: Span,
+    /// Name of the struct,
+    ///
+    name: Ident,
+    /// Generics
+    ///
+    generics: Generics,
+    /// Runmd fixture source,
+    ///
+    fixture: Fixture,
+    /// Plugins to enable w/ the engine,
+    ///
+    enable: Vec<Path>,
+    /// Fields initialized from the results of running the fixture,
+    ///
+    fields: Vec<TestField>,
+}
+
+/// Source of a runmd fixture,
+///
+enum Fixture {
+    /// Inline runmd source,
+    ///
+    Inline(LitStr),
+    /// Path to a runmd file relative to the package manifest,
+    ///
+    File(LitStr),
+}
+
+/// Field of a test fixture,
+///
+struct TestField {
+    /// Span of the field,
+    ///
+    span: Span,
+    /// Name of the field,
+    ///
+    name: Ident,
+    /// Type of the field,
+    ///
+    ty: Type,
+    /// How the field is initialized,
+    ///
+    init: Option<(TestFieldInit, LitStr)>,
+}
+
+/// Enumeration of how a fixture field is initialized from a run,
+///
+enum TestFieldInit {
+    /// Plugin state of the context returned by the address,
+    ///
+    Plugin,
+    /// Resource from the transient storage of the context returned by the address,
+    ///
+    Transient,
+    /// Test run returned by the address,
+    ///
+    Run,
+}
+
+impl Parse for TestData {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let derive_input = DeriveInput::parse(input)?;
+
+        let span = derive_input.span();
+        let mut fixture = None;
+        let mut enable = vec![];
+
+        for attr in derive_input.attrs.iter() {
+            if attr.path().is_ident("reality") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("fixture") {
+                        meta.input.parse::<Token![=]>()?;
+                        fixture = Some(Fixture::Inline(meta.input.parse::<LitStr>()?));
+                    }
+
+                    if meta.path.is_ident("fixture_file") {
+                        meta.input.parse::<Token![=]>()?;
+                        fixture = Some(Fixture::File(meta.input.parse::<LitStr>()?));
+                    }
+
+                    if meta.path.is_ident("enable") {
+                        let content;
+                        syn::parenthesized!(content in meta.input);
+                        let paths = Punctuated::<Path, Token![,]>::parse_terminated(&content)?;
+                        enable.extend(paths);
+                    }
+
+                    Ok(())
+                })?;
+            }
+        }
+
+        let fixture = fixture.ok_or(syn::Error::new(
+            span,
+            "RealityTest requires #[reality(fixture = \"..\")] or #[reality(fixture_file = \"..\")]",
+        ))?;
+
+        let fields = match derive_input.data {
+            Data::Struct(data) => match data.fields {
+                Fields::Named(named) => named
+                    .named
+                    .iter()
+                    .map(TestField::parse)
+                    .collect::<syn::Result<Vec<_>>>()?,
+                Fields::Unit => vec![],
+                Fields::Unnamed(_) => {
+                    return Err(syn::Error::new(
+                        span,
+                        "RealityTest is only supported on structs w/ named fields",
+                    ))
+                }
+            },
+            _ => {
+                return Err(syn::Error::new(
+                    span,
+                    "RealityTest is only supported on structs",
+                ))
+            }
+        };
+
+        Ok(Self {
+            span,
+            name: derive_input.ident,
+            generics: derive_input.generics,
+            fixture,
+            enable,
+            fields,
+        })
+    }
+}
+
+impl TestField {
+    /// Parses a test field,
+    ///
+    fn parse(field: &syn::Field) -> syn::Result<Self> {
+        let mut init = None;
+
+        for attr in field.attrs.iter() {
+            if attr.path().is_ident("reality") {
+                attr.parse_nested_meta(|meta| {
+                    let kind = if meta.path.is_ident("plugin") {
+                        TestFieldInit::Plugin
+                    } else if meta.path.is_ident("transient") {
+                        TestFieldInit::Transient
+                    } else if meta.path.is_ident("run") {
+                        TestFieldInit::Run
+                    } else {
+                        return Err(meta.error("expected `plugin`, `transient` or `run`"));
+                    };
+
+                    meta.input.parse::<Token![=]>()?;
+                    init = Some((kind, meta.input.parse::<LitStr>()?));
+                    Ok(())
+                })?;
+            }
+        }
+
+        Ok(Self {
+            span: field.span(),
+            name: field
+                .ident
+                .clone()
+                .ok_or(syn::Error::new(field.span(), "expected a named field"))?,
+            ty: field.ty.clone(),
+            init,
+        })
+    }
+
+    /// Returns the expression initializing this field from a test harness,
+    ///
+    fn init_expr(&self) -> TokenStream {
+        let ty = &self.ty;
+        match &self.init {
+            Some((TestFieldInit::Plugin, address)) => quote_spanned!(self.span=>
+                harness.run_once(#address).await?.plugin::<#ty>().await
+            ),
+            Some((TestFieldInit::Transient, address)) => quote_spanned!(self.span=>
+                harness.run_once(#address).await?.transient::<#ty>().await?
+            ),
+            Some((TestFieldInit::Run, address)) => quote_spanned!(self.span=>
+                harness.run_once(#address).await?
+            ),
+            None => quote_spanned!(self.span=>
+                <#ty as Default>::default()
+            ),
+        }
+    }
+}
+
+impl TestData {
+    /// Renders the test harness implementation,
+    ///
+    pub(crate) fn render(&self) -> TokenStream {
+        let name = &self.name;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+
+        let fixture = match &self.fixture {
+            Fixture::Inline(source) => quote!(#source),
+            Fixture::File(path) => quote_spanned!(path.span()=>
+                include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/", #path))
+            ),
+        };
+
+        let enable = self.enable.iter().map(|p| {
+            quote_spanned!(p.span()=>
+                builder.enable::<#p>();
+            )
+        });
+
+        let fields = self.fields.iter().map(|f| {
+            let name = &f.name;
+            let init = f.init_expr();
+            quote!(#name: #init)
+        });
+
+        quote_spanned!(self.span=>
+            #[allow(dead_code)]
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Runmd fixture compiled by the test harness,
+                ///
+                pub const FIXTURE: &'static str = #fixture;
+
+                /// Compiles the fixture w/ an isolated engine and returns the started test harness,
+                ///
+                pub async fn harness() -> ::anyhow::Result<::loopio::harness::TestHarness> {
+                    let mut builder = ::loopio::harness::TestHarness::builder();
+                    #(#enable)*
+                    ::loopio::harness::TestHarness::compile(builder, stringify!(#name), Self::FIXTURE).await
+                }
+
+                /// Compiles the fixture and initializes each field from the addresses it runs,
+                ///
+                pub async fn run() -> ::anyhow::Result<Self> {
+                    let harness = Self::harness().await?;
+                    Self::run_with(&harness).await
+                }
+
+                /// Initializes each field from the addresses it runs w/ an existing test harness,
+                ///
+                pub async fn run_with(harness: &::loopio::harness::TestHarness) -> ::anyhow::Result<Self> {
+                    Ok(Self {
+                        #(#fields),*
+                    })
+                }
+            }
+        )
+    }
+}
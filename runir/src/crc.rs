@@ -1,9 +1,12 @@
+use crate::define_intern_table;
 use crate::entropy::ENTROPY;
 use crate::interner::InternResult;
 use crate::interner::LevelFlags;
 use crate::prelude::*;
 use crc::Crc;
 use std::cell::RefCell;
+use std::fmt::Debug;
+use std::fmt::Display;
 use std::hash::Hash;
 use std::hash::Hasher;
 use std::sync::OnceLock;
@@ -11,10 +14,20 @@ use tracing::trace;
 
 /// Interner that uses crc to build intern handles,
 ///
+/// **Note** By default, the tag values of each handle are verified w/ a wider 64-bit checksum so that two different
+/// sets of tag values w/ the same crc are reported as an `InternCollision` instead of aliasing each other's data. The
+/// checksum and a debug rendering of the tag values are stored for each handle so that a collision can be reported.
+///
 pub struct CrcInterner {
     /// Digest builder,
     ///
     digest: RefCell<crc::Digest<'static, u32>>,
+    /// Checksum digest builder, None if tag values are not verified,
+    ///
+    checksum: Option<RefCell<crc::Digest<'static, u64>>>,
+    /// Debug renderings of the tag values being verified,
+    ///
+    rendered: Vec<String>,
     /// Sets the current level flag,
     ///
     flags: LevelFlags,
@@ -38,32 +51,121 @@ impl Default for CrcInterner {
 ///
 static INTERNER_CRC: OnceLock<crc::Crc<u32>> = OnceLock::new();
 
+/// CRC for calculating the checksum used to verify the tag values of an intern handle,
+///
+static CHECKSUM_CRC: OnceLock<crc::Crc<u64>> = OnceLock::new();
+
+define_intern_table!(TAG_CHECKSUMS: u64);
+
+define_intern_table!(TAG_RENDERINGS: String);
+
+/// Error returned when different tag values are interned w/ the same intern handle,
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InternCollision {
+    /// Intern handle both sets of tag values share,
+    ///
+    pub handle: InternHandle,
+    /// Checksum of the tag values the handle was interned w/ first,
+    ///
+    pub existing: u64,
+    /// Debug rendering of the tag values the handle was interned w/ first,
+    ///
+    pub existing_values: String,
+    /// Checksum of the tag values that collided w/ the existing values,
+    ///
+    pub colliding: u64,
+    /// Debug rendering of the tag values that collided w/ the existing values,
+    ///
+    pub colliding_values: String,
+}
+
+impl Display for InternCollision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Intern handle {:x?} at {:?} collides, tag values [{}] (checksum {:016x}) and [{}] (checksum {:016x}) have the same crc",
+            self.handle.as_uuid(),
+            self.handle.level_flags(),
+            self.existing_values,
+            self.existing,
+            self.colliding_values,
+            self.colliding
+        )
+    }
+}
+
+impl std::error::Error for InternCollision {}
+
 impl CrcInterner {
     fn new() -> Self {
         let crc = INTERNER_CRC.get_or_init(|| Crc::<u32>::new(&crc::CRC_24_OPENPGP));
+        let checksum = CHECKSUM_CRC.get_or_init(|| Crc::<u64>::new(&crc::CRC_64_XZ));
 
         let digest = RefCell::new(crc.digest());
 
         CrcInterner {
             digest,
+            checksum: Some(RefCell::new(checksum.digest())),
+            rendered: vec![],
             tags: vec![],
             flags: LevelFlags::ROOT,
             data: 0,
         }
     }
+
+    /// Returns an interner that does not verify the tag values of intern handles,
+    ///
+    /// **Note** Collisions are not detected, instead the values that are interned last alias the values interned first.
+    ///
+    pub fn unverified() -> Self {
+        Self {
+            checksum: None,
+            ..Self::new()
+        }
+    }
+
+    /// Verifies the tag values of an intern handle against the values it was previously interned w/,
+    ///
+    fn verify(&mut self, handle: InternHandle) -> anyhow::Result<()> {
+        let Some(checksum) = self.checksum.as_ref() else {
+            return Ok(());
+        };
+
+        let crc = CHECKSUM_CRC.get_or_init(|| Crc::<u64>::new(&crc::CRC_64_XZ));
+        let checksum = checksum.replace(crc.digest()).finalize();
+        let rendered = self.rendered.drain(..).collect::<Vec<_>>().join(", ");
+
+        TAG_CHECKSUMS.assign_intern_with(handle, checksum, |existing, colliding| {
+            if existing != colliding {
+                Err(InternCollision {
+                    handle,
+                    existing: *existing,
+                    existing_values: TAG_RENDERINGS.clone(&handle).unwrap_or_default(),
+                    colliding: *colliding,
+                    colliding_values: rendered.clone(),
+                }
+                .into())
+            } else {
+                Ok(())
+            }
+        })?;
+
+        TAG_RENDERINGS.assign_intern(handle, rendered)
+    }
 }
 
 impl InternerFactory for CrcInterner {
     #[inline]
-    fn push_tag<T>(
+    fn push_tag<T: Hash + Debug + Send + Sync + 'static>(
         &mut self,
         value: T,
         tag: impl Fn(InternHandle) -> anyhow::Result<()> + Send + Sync + 'static,
-    ) where
-        T: Hash + Send + Sync + 'static,
-    {
+    ) {
         value.hash(self);
-
+        if self.checksum.is_some() {
+            self.rendered.push(format!("{value:?}"));
+        }
         self.tags.push(Box::new(tag));
     }
 
@@ -98,6 +200,9 @@ impl InternerFactory for CrcInterner {
         trace!("Creating {:04x?}", handle);
         let tags = self.tags.drain(..).collect::<Vec<_>>();
 
+        // Tags are only assigned once the values are verified, so that a collision does not alias existing data
+        self.verify(handle)?;
+
         for tag in tags {
             (tag)(handle)?
         }
@@ -123,6 +228,10 @@ impl Hasher for CrcInterner {
 
     fn write(&mut self, bytes: &[u8]) {
         self.digest.borrow_mut().update(bytes);
+
+        if let Some(checksum) = self.checksum.as_ref() {
+            checksum.borrow_mut().update(bytes);
+        }
     }
 }
 
@@ -245,7 +354,7 @@ mod tests {
         let repr = repr.link().unwrap();
         eprintln!("{:x?}", repr);
 
-        let levels = repr.get_levels();
+        let levels = repr.get_levels().unwrap();
         eprintln!("{:#x?}", levels);
         eprintln!("{:x?}", repr.as_u64());
 
@@ -257,7 +366,7 @@ mod tests {
         let mut _drepr = drepr.link().unwrap();
         eprintln!("{:x?}", _drepr);
 
-        let levels = _drepr.get_levels();
+        let levels = _drepr.get_levels().unwrap();
         eprintln!("{:#x?}", levels);
         eprintln!("{:x?}", _drepr.as_u64());
 
@@ -282,4 +391,62 @@ mod tests {
         eprintln!("{:?}", input);
         ()
     }
+
+    #[test]
+    fn test_interner_collision() {
+        use std::collections::HashMap;
+        use std::hash::Hash;
+        use std::hash::Hasher;
+
+        // Find two inputs w/ the same crc
+        let mut seen = HashMap::new();
+        let (a, b) = (0..)
+            .find_map(|i| {
+                let input = format!("collision-{i}");
+                let mut hasher = CrcInterner::unverified();
+                input.hash(&mut hasher);
+                seen.insert(hasher.finish(), input.clone())
+                    .map(|prev| (prev, input))
+            })
+            .unwrap();
+
+        let handle = NodeLevel::new()
+            .with_input(a.as_str())
+            .configure(&mut CrcInterner::default())
+            .unwrap();
+        assert_eq!(
+            handle,
+            NodeLevel::new()
+                .with_input(a.as_str())
+                .configure(&mut CrcInterner::default())
+                .unwrap()
+        );
+
+        let err = NodeLevel::new()
+            .with_input(b.as_str())
+            .configure(&mut CrcInterner::default())
+            .unwrap_err();
+        let collision = err.downcast_ref::<InternCollision>().unwrap();
+        assert_eq!(handle, collision.handle);
+        assert_ne!(collision.existing, collision.colliding);
+
+        // Both sets of tag values are rendered
+        assert!(collision.existing_values.contains(&format!("{a:?}")));
+        assert!(collision.colliding_values.contains(&format!("{b:?}")));
+        let message = err.to_string();
+        assert!(message.contains(&format!("{:016x}", collision.existing)));
+        assert!(message.contains(&format!("{:016x}", collision.colliding)));
+        assert!(message.contains(&format!("{a:?}")), "{message}");
+        assert!(message.contains(&format!("{b:?}")), "{message}");
+
+        // The existing value is not aliased by the colliding value
+        assert_eq!(a, handle.input().unwrap().as_str());
+
+        // Unverified interners do not detect collisions
+        let aliased = NodeLevel::new()
+            .with_input(b.as_str())
+            .configure(&mut CrcInterner::unverified())
+            .unwrap();
+        assert_eq!(handle, aliased);
+    }
 }
@@ -13,7 +13,7 @@ pub struct EntityInterner<Inner: InternerFactory> {
 define_intern_table!(ENTITY: u64);
 
 impl<Inner: InternerFactory> InternerFactory for EntityInterner<Inner> {
-    fn push_tag<T: std::hash::Hash + Send + Sync + 'static>(
+    fn push_tag<T: std::hash::Hash + std::fmt::Debug + Send + Sync + 'static>(
         &mut self,
         value: T,
         tag: impl Fn(crate::prelude::InternHandle) -> anyhow::Result<()> + Send + Sync + 'static,
@@ -1,9 +1,12 @@
 use std::any::TypeId;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt::Debug;
 use std::hash::Hash;
 use std::ops::Deref;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::Once;
 use std::sync::OnceLock;
 use std::sync::Weak;
 
@@ -11,12 +14,13 @@ use anyhow::anyhow;
 use serde::Deserialize;
 use serde::Serialize;
 use tracing::trace;
-use tracing::warn;
 
 use crate::entity::ENTITY;
 use crate::entropy::ENTROPY;
 use crate::prelude::Repr;
 use crate::repr::node::SourceSpan;
+use crate::scope::InternTableStats;
+use crate::scope::ScopedTable;
 
 pub type InternResult = anyhow::Result<InternHandle>;
 
@@ -30,7 +34,7 @@ pub type InternResult = anyhow::Result<InternHandle>;
 pub trait InternerFactory {
     /// Pushes a tag to the current interner state,
     ///
-    fn push_tag<T: Hash + Send + Sync + 'static>(
+    fn push_tag<T: Hash + Debug + Send + Sync + 'static>(
         &mut self,
         value: T,
         assign: impl Fn(InternHandle) -> anyhow::Result<()> + Send + Sync + 'static,
@@ -286,6 +290,20 @@ impl InternHandle {
         crate::repr::field::FIELD_NAME.copy(self)
     }
 
+    /// Returns the field path,
+    ///
+    #[inline]
+    pub fn field_path(&self) -> Option<&'static str> {
+        crate::repr::field::FIELD_PATH.copy(self)
+    }
+
+    /// Returns true if the field is a secret,
+    ///
+    #[inline]
+    pub fn field_secret(&self) -> Option<bool> {
+        crate::repr::field::FIELD_SECRET.copy(self)
+    }
+
     /// Returns the node symbol,
     ///
     #[inline]
@@ -374,7 +392,10 @@ impl InternHandle {
 /// Inner intern table map,
 /// 
 pub struct InternMap<T> {
-    pub(crate) map: BTreeMap<InternHandle, Arc<T>>
+    pub(crate) map: BTreeMap<InternHandle, Arc<T>>,
+    /// Scopes that own each entry,
+    ///
+    pub(crate) owners: BTreeMap<InternHandle, BTreeSet<u64>>,
 }
 
 impl<T> InternMap<T> {
@@ -408,11 +429,59 @@ impl<T> InternMap<T> {
     fn _prune(&mut self) {
 
     }
+
+    /// Removes a scope from the owners of each entry, entries w/o any remaining owners are removed,
+    ///
+    /// Returns true if any entries were modified.
+    ///
+    fn release(&mut self, scope: u64) -> bool {
+        let mut modified = false;
+        let mut removed = vec![];
+        for (handle, owners) in self.owners.iter_mut() {
+            if owners.remove(&scope) {
+                modified = true;
+
+                if owners.is_empty() {
+                    removed.push(*handle);
+                }
+            }
+        }
+
+        for handle in removed {
+            self.owners.remove(&handle);
+            self.map.remove(&handle);
+        }
+
+        modified
+    }
+
+    /// Returns statistics for entries owned by a scope, or for all entries if scope is None,
+    ///
+    fn stats(&self, name: &str, scope: Option<u64>) -> InternTableStats {
+        let mut stats = InternTableStats {
+            name: name.to_string(),
+            ..Default::default()
+        };
+
+        for owners in self.owners.values() {
+            if scope.map(|s| owners.contains(&s)).unwrap_or(true) {
+                stats.entries += 1;
+
+                if !owners.contains(&crate::scope::GLOBAL_SCOPE) {
+                    stats.scoped += 1;
+                }
+            }
+        }
+
+        stats.bytes =
+            stats.entries * (std::mem::size_of::<InternHandle>() + std::mem::size_of::<T>());
+        stats
+    }
 }
 
 impl<T> Default for InternMap<T> {
     fn default() -> Self {
-        Self { map: Default::default() }
+        Self { map: Default::default(), owners: Default::default() }
     }
 }
 
@@ -423,9 +492,15 @@ type InnerTable<T> = tokio::sync::watch::Sender<InternMap<T>>;
 /// Struct maintaining an inner shared intern table,
 ///
 pub struct InternTable<T: Send + Sync + 'static> {
+    /// Name of the table,
+    ///
+    name: &'static str,
     /// Inner table,
     ///
-    inner: OnceLock<InnerTable<T>>,
+    inner: OnceLock<Arc<InnerTable<T>>>,
+    /// Registers the table w/ intern scopes the first time a value is assigned,
+    ///
+    registered: Once,
 }
 
 impl<T: Send + Sync + 'static> InternTable<T> {
@@ -433,34 +508,76 @@ impl<T: Send + Sync + 'static> InternTable<T> {
     ///
     #[inline]
     pub const fn new() -> Self {
+        Self::with_name("")
+    }
+
+    /// Creates a new empty intern table w/ a name used when reporting statistics,
+    ///
+    #[inline]
+    pub const fn with_name(name: &'static str) -> Self {
         Self {
+            name,
             inner: OnceLock::new(),
+            registered: Once::new(),
         }
     }
 
+    /// Returns the name of the table,
+    ///
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
     /// Assigns an intern handle for an immutable value,
     ///
-    /// **Note** If the intern handle already has been assigned a value this will result in a no-op.
+    /// The entry is owned by the intern scope of the current task, or by the global scope if a scope has not been
+    /// entered.
+    ///
+    /// **Note** If the intern handle already has been assigned a value this will result in a no-op, other than adding
+    /// the current scope as an owner of the entry.
     ///
     pub fn assign_intern(&self, handle: InternHandle, value: T) -> anyhow::Result<()> {
-        // Skip if the value has already been created
-        {
-            if self.inner().borrow().map.contains_key(&handle) {
+        self.assign_intern_with(handle, value, |_, _| Ok(()))
+    }
+
+    /// Assigns an intern handle for an immutable value, verifying the value against an existing entry,
+    ///
+    /// **Note** The existing entry is verified and the value is assigned while the table is locked, so another
+    /// assignment of the same handle cannot be interleaved. If verify returns an error, the table is left unchanged.
+    ///
+    pub fn assign_intern_with(
+        &self,
+        handle: InternHandle,
+        value: T,
+        verify: impl FnOnce(&T, &T) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        self.registered.call_once(|| {
+            crate::scope::register(Arc::new(ScopedInner {
+                name: self.name,
+                inner: self.inner().clone(),
+            }))
+        });
+        let scope = crate::scope::current();
+
+        let mut result = Ok(());
+        self.inner().send_if_modified(|t| {
+            // Skip if the value has already been created
+            if let Some(existing) = t.map.get(&handle) {
+                result = verify(existing, &value);
+                if result.is_err() {
+                    return false;
+                }
+
                 trace!("Skipping interning {:?}", handle);
-                return Ok(());
-            }
-        }
-        self.inner().send_modify(|t| {
-            if t.map.insert(handle, Arc::new(value)).is_some() {
-                warn!(
-                    "Replacing intern handle {:?} {:x?}",
-                    handle.level_flags(),
-                    handle
-                );
+                return t.owners.entry(handle).or_default().insert(scope);
             }
+
+            t.owners.entry(handle).or_default().insert(scope);
+            t.map.insert(handle, Arc::new(value));
+            true
         });
 
-        Ok(())
+        result
     }
 
     /// Returns a handle to the interned value,
@@ -514,13 +631,19 @@ impl<T: Send + Sync + 'static> InternTable<T> {
             .clone()
     }
 
+    /// Returns statistics for the entries in this table,
+    ///
+    pub fn stats(&self) -> InternTableStats {
+        self.inner().borrow().stats(self.name, None)
+    }
+
     /// Returns a reference to the inner table,
     /// 
-    fn inner(&self) -> &InnerTable<T> {
+    fn inner(&self) -> &Arc<InnerTable<T>> {
         self.inner.get_or_init(|| {
             let (tx, _) = tokio::sync::watch::channel(InternMap::<T>::default());
 
-            tx
+            Arc::new(tx)
         })
     }
 
@@ -531,6 +654,27 @@ impl<T: Send + Sync + 'static> InternTable<T> {
     }
 }
 
+/// Inner table of an intern table registered w/ intern scopes,
+///
+struct ScopedInner<T> {
+    /// Name of the table,
+    ///
+    name: &'static str,
+    /// Inner table shared w/ the intern table,
+    ///
+    inner: Arc<InnerTable<T>>,
+}
+
+impl<T: Send + Sync + 'static> ScopedTable for ScopedInner<T> {
+    fn release(&self, scope: u64) {
+        self.inner.send_if_modified(|t| t.release(scope));
+    }
+
+    fn stats(&self, scope: Option<u64>) -> InternTableStats {
+        self.inner.borrow().stats(self.name, scope)
+    }
+}
+
 impl<T: Send + Sync + 'static> Default for InternTable<T> {
     fn default() -> Self {
         Self::new()
@@ -3,7 +3,9 @@ mod entropy;
 mod interner;
 mod level;
 mod linker;
+mod query;
 mod repr;
+mod scope;
 mod tag;
 
 #[cfg(feature = "crc-interner")]
@@ -13,6 +15,8 @@ mod crc;
 mod macros {
     /// Defines a global intern table,
     ///
+    /// **Note** The table is named after its module path and identifier when reporting statistics.
+    ///
     /// **Example**
     ///
     /// ```rs no_run
@@ -40,7 +44,8 @@ mod macros {
     #[macro_export]
     macro_rules! define_intern_table {
         ($table:ident: $ty:ty) => {
-            pub static $table: InternTable<$ty> = InternTable::<$ty>::new();
+            pub static $table: InternTable<$ty> =
+                InternTable::<$ty>::with_name(concat!(module_path!(), "::", stringify!($table)));
         };
     }
 
@@ -81,15 +86,27 @@ pub mod prelude {
 
     pub use super::tag::Tag;
 
+    pub use super::query::Predicate;
+    pub use super::query::Query;
+    pub use super::query::QueryKey;
+    pub use super::query::QueryKind;
+    pub use super::query::QueryOp;
+
     pub use super::level::Level;
 
     #[cfg(feature = "crc-interner")]
     pub use super::crc::CrcInterner;
+    #[cfg(feature = "crc-interner")]
+    pub use super::crc::InternCollision;
 
     pub use super::entity::EntityInterner;
 
     pub use super::entropy::new_runtime;
 
+    pub use super::scope::intern_stats;
+    pub use super::scope::InternScope;
+    pub use super::scope::InternTableStats;
+
     /// Type-alias for a function that takes an intern handle and returns a future,
     ///
     pub type InternHandleThunk =
@@ -186,6 +203,71 @@ mod tests {
         );
     }
 
+    define_intern_table!(TEST_SCOPED: String);
+
+    #[tokio::test]
+    async fn test_intern_scope() {
+        let global = InternHandle::default();
+        let scoped = InternHandle {
+            link: 1,
+            ..Default::default()
+        };
+        let shared = InternHandle {
+            link: 2,
+            ..Default::default()
+        };
+
+        TEST_SCOPED
+            .assign_intern(global, String::from("global"))
+            .unwrap();
+
+        let scope = InternScope::new();
+        let other = InternScope::new();
+        scope
+            .enter(async {
+                TEST_SCOPED
+                    .assign_intern(scoped, String::from("scoped"))
+                    .unwrap();
+                TEST_SCOPED
+                    .assign_intern(shared, String::from("shared"))
+                    .unwrap();
+                TEST_SCOPED
+                    .assign_intern(global, String::from("global"))
+                    .unwrap();
+            })
+            .await;
+        other.enter_sync(|| {
+            TEST_SCOPED
+                .assign_intern(shared, String::from("shared"))
+                .unwrap()
+        });
+
+        let stats = TEST_SCOPED.stats();
+        assert!(stats.name.ends_with("tests::TEST_SCOPED"));
+        assert_eq!(3, stats.entries);
+        assert_eq!(2, stats.scoped);
+        assert!(stats.bytes > 0);
+        assert!(intern_stats().contains(&stats));
+
+        let stats = scope.stats();
+        assert_eq!(1, stats.len());
+        assert_eq!(3, stats[0].entries);
+        assert_eq!(2, stats[0].scoped);
+
+        // Entries only owned by the scope are released when the last clone is dropped
+        let clone = scope.clone();
+        drop(scope);
+        assert!(TEST_SCOPED.strong_ref(&scoped).is_some());
+        drop(clone);
+        assert!(TEST_SCOPED.strong_ref(&scoped).is_none());
+        assert!(TEST_SCOPED.strong_ref(&shared).is_some());
+        assert!(TEST_SCOPED.strong_ref(&global).is_some());
+
+        drop(other);
+        assert!(TEST_SCOPED.strong_ref(&shared).is_none());
+        assert_eq!(1, TEST_SCOPED.stats().entries);
+    }
+
     #[test]
     #[tracing_test::traced_test]
     fn test_intern_handle_link() {
@@ -0,0 +1,727 @@
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::prelude::*;
+
+/// Query over interned representations,
+///
+/// A query selects representations by the levels they have and then filters them w/ predicates over the tags of each
+/// level.
+///
+/// **Syntax**
+///
+/// ```md
+/// <kind> [where <key> [<op> <value>] (and <key> [<op> <value>])*]
+/// ```
+///
+/// - kind: `reprs`, `resources`, `fields`, `recvs`, `nodes`, `hosts`
+/// - op: `=`, `!=`, `~` (or `contains`), if omitted the predicate only checks that the key has a value
+/// - value: a word, or a value quoted w/ backticks or double quotes
+///
+/// **Example**
+///
+/// ```rs no_run
+/// // All nodes in host `demo` whose annotations contain `notify`
+/// let query = "nodes where host = `demo` and annotations contains `notify`".parse::<Query>()?;
+///
+/// // All fields of type `PathBuf`
+/// let query = Query::fields().with(QueryKey::Type, QueryOp::Eq, "PathBuf");
+///
+/// let matches = query.run(reprs);
+/// ```
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Query {
+    /// Kind of representation to select,
+    ///
+    pub kind: QueryKind,
+    /// Predicates that must all match,
+    ///
+    pub predicates: Vec<Predicate>,
+}
+
+impl Query {
+    /// Creates a new query that selects a kind of representation,
+    ///
+    pub const fn new(kind: QueryKind) -> Self {
+        Self {
+            kind,
+            predicates: vec![],
+        }
+    }
+
+    /// Creates a new query that selects every representation,
+    ///
+    pub const fn reprs() -> Self {
+        Self::new(QueryKind::Reprs)
+    }
+
+    /// Creates a new query that selects field representations,
+    ///
+    pub const fn fields() -> Self {
+        Self::new(QueryKind::Fields)
+    }
+
+    /// Creates a new query that selects receiver representations,
+    ///
+    pub const fn recvs() -> Self {
+        Self::new(QueryKind::Recvs)
+    }
+
+    /// Creates a new query that selects node representations,
+    ///
+    pub const fn nodes() -> Self {
+        Self::new(QueryKind::Nodes)
+    }
+
+    /// Creates a new query that selects host representations,
+    ///
+    pub const fn hosts() -> Self {
+        Self::new(QueryKind::Hosts)
+    }
+
+    /// Returns the query w/ a predicate that compares the value of a key,
+    ///
+    pub fn with(mut self, key: QueryKey, op: QueryOp, value: impl Into<String>) -> Self {
+        self.predicates.push(Predicate {
+            key,
+            op: Some(op),
+            value: value.into(),
+        });
+        self
+    }
+
+    /// Returns the query w/ a predicate that checks that a key has a value,
+    ///
+    pub fn has(mut self, key: QueryKey) -> Self {
+        self.predicates.push(Predicate {
+            key,
+            op: None,
+            value: String::new(),
+        });
+        self
+    }
+
+    /// Returns true if the representation is selected by this query,
+    ///
+    pub fn matches(&self, repr: &Repr) -> bool {
+        self.kind.matches(repr) && self.predicates.iter().all(|p| p.matches(repr))
+    }
+
+    /// Runs the query, returning each matching representation once in the order it was found,
+    ///
+    pub fn run(&self, reprs: impl IntoIterator<Item = Repr>) -> Vec<Repr> {
+        let mut visited = BTreeSet::new();
+        reprs
+            .into_iter()
+            .filter(|r| visited.insert(*r))
+            .filter(|r| self.matches(r))
+            .collect()
+    }
+}
+
+impl FromStr for Query {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = tokenize(s)?.into_iter().peekable();
+
+        let kind = match tokens.next() {
+            Some(Token::Word(kind)) => kind.parse::<QueryKind>()?,
+            _ => return Err(anyhow!("Expected a query kind, i.e. `nodes`")),
+        };
+
+        let mut query = Query::new(kind);
+        match tokens.next() {
+            None => return Ok(query),
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case("where") => {}
+            Some(t) => return Err(anyhow!("Expected `where`, found `{t}`")),
+        }
+
+        loop {
+            let key = match tokens.next() {
+                Some(Token::Word(key)) => key.parse::<QueryKey>()?,
+                Some(t) => return Err(anyhow!("Expected a key, found `{t}`")),
+                None => return Err(anyhow!("Expected a key after `where`/`and`")),
+            };
+
+            let op = match tokens.peek() {
+                Some(Token::Op(op)) => Some(*op),
+                Some(Token::Word(w)) if w.eq_ignore_ascii_case("contains") => {
+                    Some(QueryOp::Contains)
+                }
+                _ => None,
+            };
+
+            if let Some(op) = op {
+                tokens.next();
+                match tokens.next() {
+                    Some(Token::Word(value)) | Some(Token::Quoted(value)) => {
+                        query = query.with(key, op, value);
+                    }
+                    _ => return Err(anyhow!("Expected a value after `{op}`")),
+                }
+            } else {
+                query = query.has(key);
+            }
+
+            match tokens.next() {
+                None => return Ok(query),
+                Some(Token::Word(w)) if w.eq_ignore_ascii_case("and") => continue,
+                Some(t) => return Err(anyhow!("Expected `and`, found `{t}`")),
+            }
+        }
+    }
+}
+
+impl Display for Query {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.kind)?;
+        for (idx, p) in self.predicates.iter().enumerate() {
+            write!(f, " {} {p}", if idx == 0 { "where" } else { "and" })?;
+        }
+        Ok(())
+    }
+}
+
+/// Kind of representation selected by a query,
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QueryKind {
+    /// Any representation,
+    ///
+    Reprs,
+    /// Representations w/ a resource level,
+    ///
+    Resources,
+    /// Representations w/ a named field level,
+    ///
+    Fields,
+    /// Representations w/ a named receiver level,
+    ///
+    Recvs,
+    /// Representations w/ a node level,
+    ///
+    Nodes,
+    /// Representations w/ a host level that has an address,
+    ///
+    Hosts,
+}
+
+impl QueryKind {
+    /// Returns true if the representation has the levels of this kind,
+    ///
+    pub fn matches(&self, repr: &Repr) -> bool {
+        match self {
+            QueryKind::Reprs => true,
+            QueryKind::Resources => repr.as_resource().is_some(),
+            QueryKind::Fields => repr.as_field().and_then(|f| f.name()).is_some(),
+            QueryKind::Recvs => repr.as_recv().and_then(|r| r.name()).is_some(),
+            QueryKind::Nodes => repr.as_node().is_some(),
+            QueryKind::Hosts => repr.as_host().and_then(|h| h.address()).is_some(),
+        }
+    }
+}
+
+impl FromStr for QueryKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "reprs" | "repr" | "all" => Ok(QueryKind::Reprs),
+            "resources" | "resource" => Ok(QueryKind::Resources),
+            "fields" | "field" => Ok(QueryKind::Fields),
+            "recvs" | "recv" => Ok(QueryKind::Recvs),
+            "nodes" | "node" => Ok(QueryKind::Nodes),
+            "hosts" | "host" => Ok(QueryKind::Hosts),
+            _ => Err(anyhow!("Unknown query kind `{s}`")),
+        }
+    }
+}
+
+impl Display for QueryKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryKind::Reprs => write!(f, "reprs"),
+            QueryKind::Resources => write!(f, "resources"),
+            QueryKind::Fields => write!(f, "fields"),
+            QueryKind::Recvs => write!(f, "recvs"),
+            QueryKind::Nodes => write!(f, "nodes"),
+            QueryKind::Hosts => write!(f, "hosts"),
+        }
+    }
+}
+
+/// Predicate over a tag of a representation,
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Predicate {
+    /// Key of the tag to compare,
+    ///
+    pub key: QueryKey,
+    /// Comparison, if None the predicate matches if the key has a value,
+    ///
+    pub op: Option<QueryOp>,
+    /// Value to compare against,
+    ///
+    pub value: String,
+}
+
+impl Predicate {
+    /// Returns true if the representation matches this predicate,
+    ///
+    pub fn matches(&self, repr: &Repr) -> bool {
+        let values = self.key.values(repr);
+
+        match self.op {
+            None => !values.is_empty(),
+            Some(QueryOp::Eq) => values.iter().any(|v| self.key.equals(v, &self.value)),
+            Some(QueryOp::Ne) => !values.iter().any(|v| self.key.equals(v, &self.value)),
+            Some(QueryOp::Contains) => values.iter().any(|v| v.contains(&self.value)),
+        }
+    }
+}
+
+impl Display for Predicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.key)?;
+        if let Some(op) = self.op {
+            write!(f, " {op} `{}`", self.value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Comparison used by a predicate,
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QueryOp {
+    /// Matches if any value is equal,
+    ///
+    Eq,
+    /// Matches if no value is equal,
+    ///
+    Ne,
+    /// Matches if any value contains the query value,
+    ///
+    Contains,
+}
+
+impl Display for QueryOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryOp::Eq => write!(f, "="),
+            QueryOp::Ne => write!(f, "!="),
+            QueryOp::Contains => write!(f, "~"),
+        }
+    }
+}
+
+/// Key of a tag that can be queried,
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QueryKey {
+    /// Type name of the resource level,
+    ///
+    Type,
+    /// Parse type name of the resource level,
+    ///
+    ParseType,
+    /// FFI type name of the resource level,
+    ///
+    FfiType,
+    /// Name of the field or receiver level,
+    ///
+    Name,
+    /// Type name of the owner of the field level,
+    ///
+    Owner,
+    /// Offset of the field level,
+    ///
+    Offset,
+    /// Path of the field or node level,
+    ///
+    Path,
+    /// Symbol of the node level,
+    ///
+    Symbol,
+    /// Input of the node level,
+    ///
+    Input,
+    /// Tag of the node level,
+    ///
+    Tag,
+    /// Doc headers of the node level,
+    ///
+    Doc,
+    /// Keys and values of the node level's annotations,
+    ///
+    Annotations,
+    /// Value of a specific annotation of the node level,
+    ///
+    Annotation(String),
+    /// Address of the host level,
+    ///
+    Host,
+    /// Names of the extensions of the host level,
+    ///
+    Extension,
+}
+
+impl QueryKey {
+    /// Returns the values of this key for a representation,
+    ///
+    pub fn values(&self, repr: &Repr) -> Vec<String> {
+        let node = || repr.as_node();
+
+        match self {
+            QueryKey::Type => repr
+                .as_resource()
+                .and_then(|r| r.type_name())
+                .map(String::from)
+                .into_iter()
+                .collect(),
+            QueryKey::ParseType => repr
+                .as_resource()
+                .and_then(|r| r.parse_type_name())
+                .map(String::from)
+                .into_iter()
+                .collect(),
+            QueryKey::FfiType => repr.ffi_type().map(String::from).into_iter().collect(),
+            QueryKey::Name => repr
+                .field_name()
+                .map(String::from)
+                .or(repr.as_recv().and_then(|r| r.name()).map(|n| n.to_string()))
+                .into_iter()
+                .collect(),
+            QueryKey::Owner => repr
+                .as_field()
+                .and_then(|f| f.owner_name())
+                .map(String::from)
+                .into_iter()
+                .collect(),
+            QueryKey::Offset => repr
+                .as_field()
+                .and_then(|f| f.offset())
+                .map(|o| o.to_string())
+                .into_iter()
+                .collect(),
+            QueryKey::Path => repr
+                .as_field()
+                .and_then(|f| f.path())
+                .map(String::from)
+                .into_iter()
+                .chain(node().and_then(|n| n.path()).map(|p| p.to_string()))
+                .collect(),
+            QueryKey::Symbol => node()
+                .and_then(|n| n.symbol())
+                .map(|s| s.to_string())
+                .into_iter()
+                .collect(),
+            QueryKey::Input => node()
+                .and_then(|n| n.input())
+                .map(|s| s.to_string())
+                .into_iter()
+                .collect(),
+            QueryKey::Tag => node()
+                .and_then(|n| n.tag())
+                .map(|s| s.to_string())
+                .into_iter()
+                .collect(),
+            QueryKey::Doc => node()
+                .and_then(|n| n.doc_headers())
+                .map(|d| d.to_vec())
+                .unwrap_or_default(),
+            QueryKey::Annotations => node()
+                .and_then(|n| n.annotations())
+                .map(|a| {
+                    a.iter()
+                        .flat_map(|(k, v)| [k.to_string(), v.to_string()])
+                        .collect()
+                })
+                .unwrap_or_default(),
+            QueryKey::Annotation(name) => node()
+                .and_then(|n| n.annotations())
+                .and_then(|a| a.get(name).cloned())
+                .into_iter()
+                .collect(),
+            QueryKey::Host => repr
+                .as_host()
+                .and_then(|h| h.address())
+                .map(|a| a.to_string())
+                .into_iter()
+                .collect(),
+            QueryKey::Extension => repr
+                .as_host()
+                .and_then(|h| h.extensions())
+                .map(|e| {
+                    e.iter()
+                        .filter_map(|e| e.as_recv().and_then(|r| r.name()))
+                        .map(|n| n.to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Returns true if a value of this key is equal to the query value,
+    ///
+    /// **Note** Type names also match on the last segment of their path, i.e. `PathBuf` matches `std::path::PathBuf`.
+    ///
+    fn equals(&self, value: &str, query: &str) -> bool {
+        match self {
+            QueryKey::Type | QueryKey::ParseType | QueryKey::FfiType | QueryKey::Owner => {
+                value == query || value.rsplit("::").next() == Some(query)
+            }
+            _ => value == query,
+        }
+    }
+}
+
+impl FromStr for QueryKey {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(name) = s.strip_prefix("annotation.") {
+            return Ok(QueryKey::Annotation(name.to_string()));
+        }
+
+        match s.to_lowercase().as_str() {
+            "type" => Ok(QueryKey::Type),
+            "parse_type" => Ok(QueryKey::ParseType),
+            "ffi_type" => Ok(QueryKey::FfiType),
+            "name" => Ok(QueryKey::Name),
+            "owner" => Ok(QueryKey::Owner),
+            "offset" => Ok(QueryKey::Offset),
+            "path" => Ok(QueryKey::Path),
+            "symbol" => Ok(QueryKey::Symbol),
+            "input" => Ok(QueryKey::Input),
+            "tag" => Ok(QueryKey::Tag),
+            "doc" => Ok(QueryKey::Doc),
+            "annotations" => Ok(QueryKey::Annotations),
+            "host" => Ok(QueryKey::Host),
+            "extension" | "extensions" => Ok(QueryKey::Extension),
+            _ => Err(anyhow!("Unknown query key `{s}`")),
+        }
+    }
+}
+
+impl Display for QueryKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryKey::Type => write!(f, "type"),
+            QueryKey::ParseType => write!(f, "parse_type"),
+            QueryKey::FfiType => write!(f, "ffi_type"),
+            QueryKey::Name => write!(f, "name"),
+            QueryKey::Owner => write!(f, "owner"),
+            QueryKey::Offset => write!(f, "offset"),
+            QueryKey::Path => write!(f, "path"),
+            QueryKey::Symbol => write!(f, "symbol"),
+            QueryKey::Input => write!(f, "input"),
+            QueryKey::Tag => write!(f, "tag"),
+            QueryKey::Doc => write!(f, "doc"),
+            QueryKey::Annotations => write!(f, "annotations"),
+            QueryKey::Annotation(name) => write!(f, "annotation.{name}"),
+            QueryKey::Host => write!(f, "host"),
+            QueryKey::Extension => write!(f, "extension"),
+        }
+    }
+}
+
+/// Token of the query syntax,
+///
+#[derive(Debug, PartialEq)]
+enum Token {
+    /// Unquoted word,
+    ///
+    Word(String),
+    /// Value quoted w/ backticks or double quotes,
+    ///
+    Quoted(String),
+    /// Comparison operator,
+    ///
+    Op(QueryOp),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Word(w) => write!(f, "{w}"),
+            Token::Quoted(q) => write!(f, "`{q}`"),
+            Token::Op(op) => write!(f, "{op}"),
+        }
+    }
+}
+
+/// Splits query text into tokens,
+///
+fn tokenize(s: &str) -> anyhow::Result<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '`' | '"' => {
+                let mut quoted = String::new();
+                loop {
+                    match chars.next() {
+                        Some(n) if n == c => break,
+                        Some(n) => quoted.push(n),
+                        None => return Err(anyhow!("Unterminated quote in `{s}`")),
+                    }
+                }
+                tokens.push(Token::Quoted(quoted));
+            }
+            '=' => tokens.push(Token::Op(QueryOp::Eq)),
+            '~' => tokens.push(Token::Op(QueryOp::Contains)),
+            '!' if chars.next_if_eq(&'=').is_some() => tokens.push(Token::Op(QueryOp::Ne)),
+            c => {
+                let mut word = String::from(c);
+                while let Some(n) = chars
+                    .next_if(|n| !n.is_whitespace() && !matches!(n, '`' | '"' | '=' | '~' | '!'))
+                {
+                    word.push(n);
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[allow(unused)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    struct Test;
+
+    impl Field<0> for Test {
+        type ParseType = String;
+
+        type ProjectedType = PathBuf;
+
+        type FFIType = String;
+
+        fn field_name() -> &'static str {
+            "path"
+        }
+    }
+
+    #[test]
+    fn test_query_parse() {
+        let query = "nodes where host = `demo` and annotations contains notify"
+            .parse::<Query>()
+            .unwrap();
+        assert_eq!(
+            Query::nodes()
+                .with(QueryKey::Host, QueryOp::Eq, "demo")
+                .with(QueryKey::Annotations, QueryOp::Contains, "notify"),
+            query
+        );
+        assert_eq!(query, query.to_string().parse::<Query>().unwrap());
+
+        let query = "fields where type=PathBuf and annotation.help and offset != 1"
+            .parse::<Query>()
+            .unwrap();
+        assert_eq!(
+            Query::fields()
+                .with(QueryKey::Type, QueryOp::Eq, "PathBuf")
+                .has(QueryKey::Annotation("help".to_string()))
+                .with(QueryKey::Offset, QueryOp::Ne, "1"),
+            query
+        );
+
+        assert_eq!(QueryKind::Nodes, "node".parse::<QueryKind>().unwrap());
+        assert_eq!(QueryKind::Nodes, "Nodes".parse::<QueryKind>().unwrap());
+        assert_eq!(QueryKind::Reprs, "all".parse::<QueryKind>().unwrap());
+        assert!("nodesss".parse::<QueryKind>().is_err());
+        assert!("alls".parse::<QueryKind>().is_err());
+        assert!("widgets".parse::<Query>().is_err());
+        assert!("nodes where".parse::<Query>().is_err());
+        assert!("nodes where color = red".parse::<Query>().is_err());
+        assert!("nodes where path = `demo".parse::<Query>().is_err());
+        assert!("nodes where path = demo or tag".parse::<Query>().is_err());
+    }
+
+    #[test]
+    fn test_query_run() {
+        let mut resource = ResourceLevel::new::<PathBuf>();
+        resource.set_parse_type::<String>();
+
+        let mut linker = Linker::<CrcInterner>::default();
+        linker.push_level(resource).unwrap();
+        linker
+            .push_level(FieldLevel::new::<0, Test>().with_path("demo/path"))
+            .unwrap();
+        let field = linker.link().unwrap();
+
+        let mut annotations = BTreeMap::new();
+        annotations.insert("notify".to_string(), "demo.done".to_string());
+
+        linker
+            .push_level(
+                NodeLevel::new()
+                    .with_symbol("path")
+                    .with_input("./demo")
+                    .with_annotations(annotations),
+            )
+            .unwrap();
+        let node = linker.link().unwrap();
+
+        linker.push_level(HostLevel::new("demo")).unwrap();
+        let host = linker.link().unwrap();
+
+        let other = Linker::new_crc::<String>().link().unwrap();
+
+        let reprs = [other, field, node, host, host];
+
+        assert_eq!(
+            vec![field, node, host],
+            Query::fields()
+                .with(QueryKey::Type, QueryOp::Eq, "PathBuf")
+                .run(reprs)
+        );
+        assert_eq!(
+            vec![host],
+            "nodes where host = demo and annotations ~ notify"
+                .parse::<Query>()
+                .unwrap()
+                .run(reprs)
+        );
+        assert_eq!(
+            vec![node, host],
+            "nodes where annotation.notify = `demo.done` and input = ./demo"
+                .parse::<Query>()
+                .unwrap()
+                .run(reprs)
+        );
+        assert_eq!(
+            vec![other],
+            "resources where type != PathBuf"
+                .parse::<Query>()
+                .unwrap()
+                .run(reprs)
+        );
+        assert_eq!(
+            vec![field, node, host],
+            "reprs where path = demo/path and parse_type = String"
+                .parse::<Query>()
+                .unwrap()
+                .run(reprs)
+        );
+        assert!(Query::hosts()
+            .has(QueryKey::Extension)
+            .run(reprs)
+            .is_empty());
+    }
+}
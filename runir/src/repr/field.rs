@@ -1,5 +1,8 @@
 use std::any::TypeId;
+use std::collections::BTreeSet;
 use std::str::FromStr;
+use std::sync::Mutex;
+use std::sync::OnceLock;
 
 use crate::define_intern_table;
 use crate::prelude::*;
@@ -22,6 +25,35 @@ define_intern_table!(FIELD_OFFSET: usize);
 // Intern table for field names
 define_intern_table!(FIELD_NAME: &'static str);
 
+// Intern table for field paths
+define_intern_table!(FIELD_PATH: &'static str);
+
+// Intern table for field secret flags
+define_intern_table!(FIELD_SECRET: bool);
+
+/// Returns a static reference to a field path,
+///
+/// **Note** Each distinct path is only allocated once, paths are derived from field names so the set of paths is bounded
+/// by the types being parsed.
+///
+fn intern_path(path: String) -> &'static str {
+    static PATHS: OnceLock<Mutex<BTreeSet<&'static str>>> = OnceLock::new();
+
+    let mut paths = PATHS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+
+    match paths.get(path.as_str()) {
+        Some(path) => path,
+        None => {
+            let path: &'static str = Box::leak(path.into_boxed_str());
+            paths.insert(path);
+            path
+        }
+    }
+}
+
 /// Trait allowing a type to identify one of it's fields by offset,
 ///
 pub trait Field<const OFFSET: usize>: Send + Sync + 'static {
@@ -47,6 +79,14 @@ pub trait Field<const OFFSET: usize>: Send + Sync + 'static {
     ///
     fn field_name() -> &'static str;
 
+    /// Returns true if the field is a secret,
+    ///
+    /// **Note** The input of a secret field is a reference to the value, the value must not be shown or persisted.
+    ///
+    fn is_secret() -> bool {
+        false
+    }
+
     /// Creates and returns a linker for this field,
     ///
     fn linker<I: InternerFactory + Default>() -> anyhow::Result<Linker<I>>
@@ -88,6 +128,12 @@ pub struct FieldLevel {
     /// Field name,
     ///
     field_name: Tag<&'static str>,
+    /// Path to the field from the owner of the node, set if the field belongs to a nested field,
+    ///
+    field_path: Option<Tag<&'static str, &'static str>>,
+    /// True if the field is a secret,
+    ///
+    field_secret: Tag<bool>,
 }
 
 impl FieldLevel {
@@ -103,8 +149,27 @@ impl FieldLevel {
             owner_size: Tag::new(&OWNER_SIZE, std::mem::size_of::<Owner>),
             field_offset: Tag::new(&FIELD_OFFSET, || OFFSET),
             field_name: Tag::new(&FIELD_NAME, Owner::field_name),
+            field_path: None,
+            field_secret: Tag::new(&FIELD_SECRET, Owner::is_secret),
         }
     }
+
+    /// Returns the field level w/ path tag set,
+    ///
+    #[inline]
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.set_path(path);
+        self
+    }
+
+    /// Sets the path tag for the field level,
+    ///
+    /// **Note** Paths are `.` seperated field names starting from the field of the node's owner, i.e. `server.port`.
+    ///
+    #[inline]
+    pub fn set_path(&mut self, path: impl Into<String>) {
+        self.field_path = Some(Tag::new(&FIELD_PATH, intern_path(path.into())));
+    }
 }
 
 impl Level for FieldLevel {
@@ -114,6 +179,11 @@ impl Level for FieldLevel {
         push_tag!(interner, self.owner_size);
         push_tag!(interner, self.field_offset);
         push_tag!(interner, self.field_name);
+        push_tag!(interner, self.field_secret);
+
+        if let Some(path) = self.field_path {
+            push_tag!(interner, path);
+        }
 
         interner.set_level_flags(LevelFlags::LEVEL_1);
 
@@ -146,6 +216,22 @@ impl FieldRepr {
         self.0.field_name()
     }
 
+    /// Returns the tag value of the field path,
+    ///
+    /// **Note** Only set for fields of nested fields.
+    ///
+    #[inline]
+    pub fn path(&self) -> Option<&'static str> {
+        self.0.field_path()
+    }
+
+    /// Returns true if the field is a secret,
+    ///
+    #[inline]
+    pub fn is_secret(&self) -> bool {
+        self.0.field_secret().unwrap_or_default()
+    }
+
     /// Returns the tag value of the field offset,
     ///
     #[inline]
@@ -22,6 +22,7 @@ pub mod prelude {
 
     pub use super::node::NodeLevel;
     pub use super::node::NodeRepr;
+    pub use super::node::SourceSpan;
 
     pub use super::dependency::DependencyLevel;
     pub use super::dependency::DependencyRepr;
@@ -128,7 +129,7 @@ impl Repr {
     /// **Error** Returns an error if count exceeds current repr level
     ///
     pub fn downgrade(&self, count: usize) -> anyhow::Result<Repr> {
-        let levels = self.get_levels();
+        let levels = self.get_levels()?;
 
         if let Some(end) = levels.len().checked_sub(count) {
             let mut levels = levels[..end].to_vec();
@@ -155,7 +156,10 @@ impl Repr {
     ///
     /// The vector is ordered w/ the first element as the root and the last as the tail.
     ///
-    pub fn get_levels(&self) -> Vec<InternHandle> {
+    /// **Errors** Returns an error if a level is no longer interned, i.e. the intern scope that owned the level has
+    /// been dropped.
+    ///
+    pub fn get_levels(&self) -> anyhow::Result<Vec<InternHandle>> {
         let mut levels = vec![];
         let mut cursor = self.tail.node();
         loop {
@@ -164,12 +168,14 @@ impl Repr {
                     if let Some(prev) = HANDLES.copy(&prev) {
                         levels.push(current);
                         cursor = prev.node();
+                    } else {
+                        return Err(anyhow!("Level {:?} is not interned", prev));
                     }
                 }
                 (None, current) => {
                     levels.push(current);
                     levels.reverse();
-                    return levels;
+                    return Ok(levels);
                 }
             }
         }
@@ -179,7 +185,7 @@ impl Repr {
     ///
     #[inline]
     pub fn as_resource(&self) -> Option<ResourceRepr> {
-        self.get_levels().first().copied().map(ResourceRepr)
+        self.get_levels().ok()?.first().copied().map(ResourceRepr)
     }
 
     /// Returns the ffi_type name of the resource repr,
@@ -243,35 +249,35 @@ impl Repr {
     #[inline]
     pub fn as_dependency(&self) -> Option<DependencyRepr> {
         // TODO: Check if this is actually DependencyLevel?
-        self.get_levels().get(1).copied().map(DependencyRepr)
+        self.get_levels().ok()?.get(1).copied().map(DependencyRepr)
     }
 
     /// Returns the repr as a receiver repr,
     ///
     #[inline]
     pub fn as_recv(&self) -> Option<RecvRepr> {
-        self.get_levels().get(1).copied().map(RecvRepr)
+        self.get_levels().ok()?.get(1).copied().map(RecvRepr)
     }
 
     /// Returns the repr as a field repr,
     ///
     #[inline]
     pub fn as_field(&self) -> Option<FieldRepr> {
-        self.get_levels().get(1).copied().map(FieldRepr)
+        self.get_levels().ok()?.get(1).copied().map(FieldRepr)
     }
 
     /// Returns the repr as a node repr,
     ///
     #[inline]
     pub fn as_node(&self) -> Option<NodeRepr> {
-        self.get_levels().get(2).copied().map(NodeRepr)
+        self.get_levels().ok()?.get(2).copied().map(NodeRepr)
     }
 
     /// Returns the repr as a host repr,
     ///
     #[inline]
     pub fn as_host(&self) -> Option<HostRepr> {
-        self.get_levels().get(3).copied().map(HostRepr)
+        self.get_levels().ok()?.get(3).copied().map(HostRepr)
     }
 }
 
@@ -325,6 +331,9 @@ fn display_runmd(repr: &Repr, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Resu
             if let Some(name) = field.name() {
                 writeln!(f, "| field_name | {name} |")?;
             }
+            if let Some(path) = field.path() {
+                writeln!(f, "| field_path | {path} |")?;
+            }
             if let Some(offset) = field.offset() {
                 writeln!(f, "| field_offset | {offset} |")?;
             }
@@ -331,6 +331,20 @@ impl NodeLevel {
     pub fn set_source_relative(&mut self, relative: PathBuf) {
         self.relative = Some(Tag::new(&SOURCE_RELATIVE, Arc::new(relative)));
     }
+
+    /// Returns the node level source span,
+    ///
+    #[inline]
+    pub fn source_span(&self) -> Option<Arc<SourceSpan>> {
+        self.span.as_ref().map(|s| s.create_value.clone())
+    }
+
+    /// Returns the node level source relative path,
+    ///
+    #[inline]
+    pub fn source_relative(&self) -> Option<Arc<PathBuf>> {
+        self.relative.as_ref().map(|r| r.create_value.clone())
+    }
 }
 
 impl Level for NodeLevel {
@@ -0,0 +1,186 @@
+use std::future::Future;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Id of the global scope,
+///
+/// **Note** Entries assigned outside of an intern scope are owned by the global scope and are never released.
+///
+pub(crate) const GLOBAL_SCOPE: u64 = 0;
+
+/// Counter for assigning scope ids,
+///
+static NEXT_SCOPE: AtomicU64 = AtomicU64::new(GLOBAL_SCOPE + 1);
+
+/// Intern tables that have had at least one value assigned,
+///
+static TABLES: Mutex<Vec<Arc<dyn ScopedTable>>> = Mutex::new(vec![]);
+
+tokio::task_local!(
+    /// Scope that values are interned w/ by the current task,
+    ///
+    static SCOPE: u64
+);
+
+/// Implemented by intern tables to release and report entries by scope,
+///
+pub(crate) trait ScopedTable: Send + Sync {
+    /// Releases the scope from each entry in the table,
+    ///
+    /// **Note** An entry is removed once it is no longer owned by any scope.
+    ///
+    fn release(&self, scope: u64);
+
+    /// Returns statistics for entries owned by a scope, or for all entries if scope is None,
+    ///
+    fn stats(&self, scope: Option<u64>) -> InternTableStats;
+}
+
+/// Registers an intern table so that it can be released by scope,
+///
+pub(crate) fn register(table: Arc<dyn ScopedTable>) {
+    if let Ok(mut tables) = TABLES.lock() {
+        tables.push(table);
+    }
+}
+
+/// Returns the id of the scope values are currently interned w/,
+///
+pub(crate) fn current() -> u64 {
+    SCOPE.try_with(|s| *s).unwrap_or(GLOBAL_SCOPE)
+}
+
+/// Returns statistics for every intern table that has had a value assigned,
+///
+pub fn intern_stats() -> Vec<InternTableStats> {
+    TABLES
+        .lock()
+        .map(|tables| tables.iter().map(|t| t.stats(None)).collect())
+        .unwrap_or_default()
+}
+
+/// Statistics of an intern table,
+///
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InternTableStats {
+    /// Name of the intern table,
+    ///
+    pub name: String,
+    /// Number of entries,
+    ///
+    pub entries: usize,
+    /// Number of entries that will be released when the scopes that own them are dropped,
+    ///
+    pub scoped: usize,
+    /// Approximate number of bytes used by entries,
+    ///
+    /// **Note** Only counts the size of each handle and value, heap allocations owned by values are not included.
+    ///
+    pub bytes: usize,
+}
+
+/// Scope that owns the values interned while it is entered,
+///
+/// When the last clone of the scope is dropped, entries that are only owned by the scope are removed from every intern
+/// table. Entries that are also assigned by the global scope or by another live scope are kept.
+///
+/// **Example**
+///
+/// ```rs no_run
+/// let scope = InternScope::new();
+///
+/// let repr = scope.enter(async {
+///     let mut repr = Linker::new_crc::<String>();
+///     repr.link()
+/// }).await?;
+///
+/// // Entries are released once the scope is dropped
+/// drop(scope);
+/// assert!(repr.as_resource().is_none());
+/// ```
+///
+#[derive(Clone)]
+pub struct InternScope {
+    /// Releases the scope when dropped,
+    ///
+    inner: Arc<ReleaseOnDrop>,
+}
+
+impl InternScope {
+    /// Creates a new intern scope,
+    ///
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(ReleaseOnDrop(NEXT_SCOPE.fetch_add(1, Ordering::Relaxed))),
+        }
+    }
+
+    /// Returns the id of this scope,
+    ///
+    pub fn id(&self) -> u64 {
+        self.inner.0
+    }
+
+    /// Runs a future w/ this scope, values interned by the future are owned by this scope,
+    ///
+    /// **Note** The scope does not apply to tasks spawned by the future.
+    ///
+    pub async fn enter<F: Future>(&self, fut: F) -> F::Output {
+        SCOPE.scope(self.id(), fut).await
+    }
+
+    /// Calls a function w/ this scope, values interned by the function are owned by this scope,
+    ///
+    pub fn enter_sync<R>(&self, f: impl FnOnce() -> R) -> R {
+        SCOPE.sync_scope(self.id(), f)
+    }
+
+    /// Returns statistics for the entries owned by this scope in every intern table,
+    ///
+    pub fn stats(&self) -> Vec<InternTableStats> {
+        TABLES
+            .lock()
+            .map(|tables| {
+                tables
+                    .iter()
+                    .map(|t| t.stats(Some(self.id())))
+                    .filter(|s| s.entries > 0)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl Default for InternScope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for InternScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InternScope")
+            .field("id", &self.id())
+            .finish()
+    }
+}
+
+/// Releases a scope from every intern table when dropped,
+///
+struct ReleaseOnDrop(u64);
+
+impl Drop for ReleaseOnDrop {
+    fn drop(&mut self) {
+        // Copy the list so that tables are not released while the registry is locked
+        let tables = TABLES.lock().map(|t| t.clone()).unwrap_or_default();
+
+        for table in tables {
+            table.release(self.0);
+        }
+    }
+}
@@ -62,6 +62,22 @@ impl<T: ToOwned<Owned = T> + Send + Sync + 'static> Tag<T, Arc<T>> {
     }
 }
 
+impl<T: Copy + Send + Sync + 'static> Tag<T, T> {
+    /// Assign a value to an intern handle,
+    ///
+    #[inline]
+    pub fn assign(&self, handle: InternHandle) -> anyhow::Result<()> {
+        self.intern_table.assign_intern(handle, self.create_value)
+    }
+
+    /// Returns the inner value,
+    ///
+    #[inline]
+    pub fn value(&self) -> T {
+        self.create_value
+    }
+}
+
 impl Tag<InternHandle, Arc<InternHandle>> {
     /// Creates and assigns an intern handle representing the link between the current intern handle and the
     /// next intern handle.
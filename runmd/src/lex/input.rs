@@ -12,7 +12,9 @@ pub enum Input<'source> {
     EscapedText(&'source str),
     /// Text value,
     ///
-    #[regex(r##"[^\r\n"`#]*"##, on_input)]
+    /// **Note** A `#` only starts a comment when it follows whitespace, so `http://x/#frag` is read as a single value.
+    ///
+    #[regex(r##"([^\r\n"`# \t]#*|[ \t]+)*"##, on_input)]
     Text(&'source str),
     /// Multiple lines of input text,
     ///
@@ -54,6 +56,9 @@ fn test_input_lexer() {
     let mut lex = Input::lexer(r"  hello  world # Test comment");
     assert_eq!(lex.next(), Some(Ok(Input::Text("hello  world"))));
 
+    let mut lex = Input::lexer(r"http://x/#frag # Test comment");
+    assert_eq!(lex.next(), Some(Ok(Input::Text("http://x/#frag"))));
+
     let mut lex = Input::lexer(r"   `hello-world`   # Test comment");
     assert_eq!(lex.next(), Some(Ok(Input::EscapedText("hello-world"))));
 
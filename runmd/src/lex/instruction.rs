@@ -16,7 +16,9 @@ pub enum Instruction {
     AddNode,
     /// Defines a property for a block or node,
     ///
-    #[token(":", on_define_property)]
+    /// **Note** Each additional `:` defines the property one scope deeper, i.e. `::` defines a field of the nested field defined last,
+    ///
+    #[regex(":+", on_define_property)]
     DefineProperty,
     /// Loads an extension for a block or node,
     ///
@@ -86,7 +88,11 @@ fn on_ignored(lex: &mut Lexer<Instruction>) -> Filter<()> {
     }
 }
 
-fn on_block_start(lex: &mut Lexer<Instruction>) {
+fn on_block_start(lex: &mut Lexer<Instruction>) -> Filter<()> {
+    if !is_line_start(lex.source(), lex.span().start) {
+        return Filter::Skip;
+    }
+
     lex.extras.start_block();
 
     // Parse the optional identifier
@@ -106,11 +112,50 @@ fn on_block_start(lex: &mut Lexer<Instruction>) {
 
         lex.bump_line();
     }
+
+    Filter::Emit(())
 }
 
 #[inline]
-fn on_block_end(lex: &mut Lexer<Instruction>) {
-    lex.extras.end_block();
+fn on_block_end(lex: &mut Lexer<Instruction>) -> Filter<()> {
+    if !is_line_start(lex.source(), lex.span().start) {
+        return Filter::Skip;
+    }
+
+    if lex.extras.is_analyzing() {
+        lex.extras.end_block();
+    } else {
+        // Skip code blocks that are not runmd, i.e. ```mermaid, through their closing fence
+        let remainder = lex.remainder();
+        let mut len = remainder.len();
+        let mut offset = 0;
+        for (idx, line) in remainder.split_inclusive('\n').enumerate() {
+            // The first line is the rest of the opening fence, i.e. the language
+            let indent = line.len() - line.trim_start().len();
+            if idx > 0 && line[indent..].starts_with("```") {
+                len = offset + indent + 3;
+                break;
+            }
+            offset += line.len();
+        }
+        lex.bump(len);
+    }
+
+    Filter::Emit(())
+}
+
+/// Returns true if only whitespace precedes idx on its line,
+///
+/// **Note** Fences are only recognized at the start of a line, so that ``` used inline in prose does not open or close a code block.
+///
+#[inline]
+fn is_line_start(source: &str, idx: usize) -> bool {
+    source[..idx]
+        .rsplit('\n')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .is_empty()
 }
 
 #[inline]
@@ -147,7 +192,7 @@ fn on_append_comment(lex: &mut Lexer<Instruction>) {
 fn on_add_node(lex: &mut Lexer<Instruction>) -> Filter<()> {
     if lex.extras.is_analyzing() {
         lex.extras.set_instruction(Instruction::AddNode);
-        on_attribute(lex);
+        on_attribute(lex, 0);
         Filter::Emit(())
     } else {
         Filter::Skip
@@ -157,8 +202,9 @@ fn on_add_node(lex: &mut Lexer<Instruction>) -> Filter<()> {
 #[inline]
 fn on_define_property(lex: &mut Lexer<Instruction>) -> Filter<()> {
     if lex.extras.is_analyzing() {
+        let depth = lex.slice().len() - 1;
         lex.extras.set_instruction(Instruction::DefineProperty);
-        on_attribute(lex);
+        on_attribute(lex, depth);
         Filter::Emit(())
     } else {
         Filter::Skip
@@ -190,7 +236,7 @@ fn on_load_extension_suffix(lex: &mut Lexer<Instruction>) -> Filter<()> {
 /// Parses the parameters of an attribute container,
 ///
 #[inline]
-fn on_attribute(lex: &mut Lexer<Instruction>) {
+fn on_attribute(lex: &mut Lexer<Instruction>, depth: usize) {
     // Morph into tokens lexer
     let tokens: Lexer<Tokens> = lex.clone().morph();
 
@@ -200,6 +246,7 @@ fn on_attribute(lex: &mut Lexer<Instruction>) {
         tag: next_if_token!(peekable, Tag, parse_tag),
         attr: next_if_token!(peekable, Attribute, parse_attr),
         comment: next_if_token!(peekable, Comment, parse_comment),
+        depth,
         ..Default::default()
     });
     lex.bump_line();
@@ -324,3 +371,124 @@ fn test_add_node_instruction() {
     assert!(line.extension.is_none());
     assert!(line.tag.is_none());
 }
+
+#[test]
+fn test_skip_other_code_blocks() {
+    let mut lex = Instruction::lexer_with_extras(
+        r"
+Demo graph,
+
+```mermaid
+flowchart LR
+    n0[[demo]] -->|start| n1[/test/]
+```
+
+```runmd
++ .test hello
+```
+    ",
+        Context::default(),
+    );
+
+    let instructions = lex
+        .by_ref()
+        .filter(|i| i != &Ok(Instruction::Ignored))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            Ok(Instruction::BlockEnd),
+            Ok(Instruction::BlockStart),
+            Ok(Instruction::AddNode),
+            Ok(Instruction::BlockEnd),
+        ],
+        instructions
+    );
+
+    assert_eq!(1, lex.extras.blocks.len());
+    assert_eq!(1, lex.extras.blocks[0].lines.len());
+}
+
+#[test]
+fn test_inline_fence_in_prose() {
+    let mut lex = Instruction::lexer_with_extras(
+        r"
+Blocks are fenced w/ ``` in markdown, a runmd block starts w/ ```runmd.
+
+```runmd
++ .test hello
+```
+
+```mermaid
+flowchart LR
+    n0[[demo]] -- uses ``` --> n1[/test/]
+  ```
+
+```runmd
++ .test world
+```
+    ",
+        Context::default(),
+    );
+
+    let instructions = lex
+        .by_ref()
+        .filter(|i| i != &Ok(Instruction::Ignored))
+        .collect::<Vec<_>>();
+    // The inline fences in prose do not emit a block end, only the fences of each code block do
+    assert_eq!(
+        vec![
+            Ok(Instruction::BlockStart),
+            Ok(Instruction::AddNode),
+            Ok(Instruction::BlockEnd),
+            Ok(Instruction::BlockEnd),
+            Ok(Instruction::BlockStart),
+            Ok(Instruction::AddNode),
+            Ok(Instruction::BlockEnd),
+        ],
+        instructions
+    );
+
+    // The inline fence does not consume the opening fence of either runmd block
+    assert_eq!(2, lex.extras.blocks.len());
+    assert_eq!(1, lex.extras.blocks[0].lines.len());
+    assert_eq!(1, lex.extras.blocks[1].lines.len());
+}
+
+#[test]
+fn test_define_property_depth_instruction() {
+    let mut context = Context::default();
+    context.start_block();
+
+    let mut lex = Instruction::lexer_with_extras(
+        r"
+    + .test hello
+    : .server localhost
+    :: .port 8080
+    ::: .retry 3
+    : .name test
+    ",
+        context,
+    );
+
+    assert_eq!(lex.next(), Some(Ok(Instruction::AddNode)));
+    assert_eq!(lex.next(), Some(Ok(Instruction::DefineProperty)));
+    assert_eq!(lex.next(), Some(Ok(Instruction::DefineProperty)));
+    assert_eq!(lex.next(), Some(Ok(Instruction::DefineProperty)));
+    assert_eq!(lex.next(), Some(Ok(Instruction::DefineProperty)));
+    lex.extras.end_block();
+
+    let block = lex.extras.blocks.pop().expect("should have a block");
+
+    let depths = block.lines.iter().map(|l| l.depth).collect::<Vec<_>>();
+    assert_eq!(vec![0, 0, 1, 2, 0], depths);
+
+    let line = &block.lines[2];
+    assert_eq!(
+        line.attr,
+        Some(Attribute {
+            name: "port",
+            input: Some(Input::Text("8080"))
+        })
+    );
+    assert_eq!(":: .port 8080", line.to_string());
+}
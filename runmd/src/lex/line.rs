@@ -33,6 +33,9 @@ pub struct Line<'a> {
     /// Properties derived from comments,
     ///
     pub comment_properties: BTreeMap<String, String>,
+    /// Number of nested scopes a property is defined in, i.e. `::` is 1,
+    ///
+    pub depth: usize,
 }
 
 impl<'a> std::fmt::Display for Line<'a> {
@@ -87,6 +90,8 @@ impl<'a> std::fmt::Display for Line<'a> {
                     writeln!(f, "# -- {doc_header}")?;
                 }
 
+                let scope = ":".repeat(self.depth + 1);
+
                 match (self.attr.as_ref(), self.tag.as_ref()) {
                     (
                         Some(Attribute {
@@ -95,7 +100,7 @@ impl<'a> std::fmt::Display for Line<'a> {
                         }),
                         Some(tag),
                     ) => {
-                        write!(f, ": {} .{name} {}", tag.0, input.clone().input_str())
+                        write!(f, "{scope} {} .{name} {}", tag.0, input.clone().input_str())
                     }
                     (
                         Some(Attribute {
@@ -104,13 +109,13 @@ impl<'a> std::fmt::Display for Line<'a> {
                         }),
                         None,
                     ) => {
-                        write!(f, ": .{name} {}", input.clone().input_str())
+                        write!(f, "{scope} .{name} {}", input.clone().input_str())
                     }
                     (Some(Attribute { name, input: None }), Some(tag)) => {
-                        write!(f, ": {} .{name}", tag.0)
+                        write!(f, "{scope} {} .{name}", tag.0)
                     }
                     (Some(Attribute { name, input: None }), None) => {
-                        write!(f, ": .{name}")
+                        write!(f, "{scope} .{name}")
                     }
                     _ => return write!(f, "DEFINE_PROP BUG -- {:?}", self.attr),
                 }
@@ -1,4 +1,5 @@
 use logos::Logos;
+use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::ops::DerefMut;
 use std::pin::Pin;
@@ -59,7 +60,8 @@ impl Parser {
         // Apply Lexer analysis
         let mut lexer = Instruction::lexer_with_extras(source.as_ref(), Context::default());
 
-        let mut locations = vec![];
+        // Spans of each line that is parsed, in source order
+        let mut locations = VecDeque::new();
 
         while let Some(line) = lexer.next() {
             trace!(line = format!("{:?}", line), "{:<50}", lexer.slice().trim());
@@ -79,7 +81,7 @@ impl Parser {
                 | Instruction::LoadExtensionSuffix,
             ) = line
             {
-                locations.push(lexer.span());
+                locations.push_back(lexer.span());
             } else if let Ok(Instruction::AppendComment) = line {
                 lexer.extras.append_property();
             }
@@ -98,7 +100,7 @@ impl Parser {
             // let mut last = None;
 
             for (idx, line) in block.lines.drain(..).enumerate() {
-                let span = locations.pop();
+                let span = locations.pop_front();
                 match line.instruction {
                     Instruction::AddNode => {
                         // if let Some((node_info, block_info)) = last.take() {
@@ -120,3 +120,78 @@ async fn test_parser() {
 
     parser.parse(&SOURCE).await;
 }
+
+/// Node that records the source of each parsed line by span,
+///
+#[derive(Debug, Clone, Default)]
+struct Spans(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+impl BlockProvider for Spans {
+    fn provide(&self, _block_info: BlockInfo) -> Option<BoxedNode> {
+        Some(Box::pin(self.clone()))
+    }
+}
+
+#[async_trait(?Send)]
+impl NodeProvider for Spans {
+    async fn provide(
+        &self,
+        _name: &str,
+        _tag: Option<&str>,
+        _input: Option<&str>,
+        _node_info: &NodeInfo,
+        _block_info: &BlockInfo,
+    ) -> Option<BoxedNode> {
+        Some(Box::pin(self.clone()))
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl ExtensionLoader for Spans {
+    async fn load_extension(
+        &self,
+        _extension: &str,
+        _tag: Option<&str>,
+        _input: Option<&str>,
+    ) -> Option<BoxedNode> {
+        Some(Box::pin(self.clone()))
+    }
+
+    async fn unload(&mut self) {}
+}
+
+#[async_trait(?Send)]
+impl Node for Spans {
+    fn set_info(&mut self, _node_info: NodeInfo, _block_info: BlockInfo) {}
+
+    fn parsed_line(&mut self, node_info: NodeInfo, _block_info: BlockInfo) {
+        let span = node_info.span.expect("should have a span");
+        self.0.lock().unwrap().push(SOURCE[span].trim().to_string());
+    }
+
+    async fn define_property(&mut self, _name: &str, _tag: Option<&str>, _input: Option<&str>) {}
+
+    fn completed(self: Box<Self>) {}
+
+    fn assign_path(&mut self, _path: String) {}
+}
+
+#[tokio::test]
+async fn test_parser_spans() {
+    let spans = Spans::default();
+    let mut parser = Parser::new(spans.clone(), spans.clone());
+
+    parser.parse(&SOURCE).await;
+
+    // Each parsed line is given the span of its own source line, in source order
+    let lines = spans.0.lock().unwrap().clone();
+    assert_eq!(12, lines.len());
+    assert!(lines[0].starts_with(": test .block-prop hello prop"));
+    assert!(lines[1].starts_with("+ .test test/test.node"));
+    assert!(lines[2].starts_with("<application/test.extension>"));
+    assert!(lines[3].starts_with(": .name-1 hello-world "));
+    assert!(lines[6].starts_with(": .name-2 'hello-world-3'"));
+    assert!(lines[7].starts_with("+ example .test test/test.node"));
+    assert!(lines[10].starts_with(": .name         cool example"));
+    assert!(lines[11].starts_with("+ .test test/test.node-2"));
+}